@@ -0,0 +1,61 @@
+//! Sources of the current time
+
+use crate::Timezone;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fmt;
+
+/// Tells the current time. Relative date expressions like "today" or "from tomorrow through
+/// 11 days after tomorrow" are resolved against it.
+pub trait Clock: fmt::Debug {
+    /// Returns the current moment.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Returns the current moment as wall-clock time in the given timezone, e.g. in the
+    /// [timezone of a storage](crate::Storage::timezone).
+    fn local_now(&self, timezone: &Timezone) -> NaiveDateTime {
+        timezone.naive_local(self.now())
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Box<C> {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
+
+/// The system's clock. This is the default [`Clock`] of a [`Storage`](crate::Storage).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock which always tells the same moment. Makes relative date expressions reproducible.
+///
+/// Example:
+/// ```
+/// use libmrot::{Clock, FixedClock};
+/// use chrono::{TimeZone, Utc};
+///
+/// let moment = Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap();
+/// let clock = FixedClock::new(moment);
+/// assert_eq!(clock.now(), moment);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(DateTime<Utc>);
+
+impl FixedClock {
+    /// Constructs a clock which is stopped at the given moment.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        FixedClock(now)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
@@ -1,8 +1,9 @@
 //! Functions to convert between various formats of dates.
 
-use crate::{Error, Result};
-use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, TimeDelta};
+use crate::{Error, Result, Timezone};
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime, TimeDelta};
 use tracing::{instrument, Span};
+use two_timer::Config;
 
 /// Parses a given string into a vector of naive dates.
 /// Implicit or explicit time ranges (see two_timer's [literal range](https://docs.rs/two_timer/latest/two_timer/)) may result in multiple dates,
@@ -43,7 +44,35 @@ use tracing::{instrument, Span};
 /// ```
 #[instrument]
 pub fn parse_date(date: &str) -> Result<Vec<NaiveDate>> {
-    let (start_datetime, end_datetime, range_is_explicit) = two_timer::parse(date, None)?;
+    parse_date_at(date, Local::now().naive_local())
+}
+
+/// Like [`parse_date`], but relative date expressions are resolved against the given moment
+/// instead of the current time.
+///
+/// Example:
+/// ```
+/// use libmrot::parse_date_at;
+/// use chrono::NaiveDate;
+///
+/// let now = NaiveDate::from_ymd_opt(2025, 4, 15)
+///     .unwrap()
+///     .and_hms_opt(12, 0, 0)
+///     .unwrap();
+///
+/// let dates = parse_date_at("from yesterday through tomorrow", now).unwrap();
+/// let expected_dates = vec![
+///     NaiveDate::from_ymd_opt(2025, 4, 14).unwrap(),
+///     NaiveDate::from_ymd_opt(2025, 4, 15).unwrap(),
+///     NaiveDate::from_ymd_opt(2025, 4, 16).unwrap(),
+/// ];
+///
+/// assert_eq!(dates, expected_dates);
+/// ```
+#[instrument]
+pub fn parse_date_at(date: &str, now: NaiveDateTime) -> Result<Vec<NaiveDate>> {
+    let config = Config::new().now(now);
+    let (start_datetime, end_datetime, range_is_explicit) = two_timer::parse(date, Some(config))?;
     let mut result = Vec::new();
 
     add_date(start_datetime, &mut result);
@@ -98,6 +127,72 @@ fn remove_last_date(vec: &mut Vec<NaiveDate>) {
     _ = vec.pop();
 }
 
+/// Parses a duration expression like `"10 days"`, `"two weeks"` or `"3 months and 1 week"` into a
+/// number of days. A week has 7 days, a month 30 and a year 365. The number may be written in
+/// digits, as a word up to twelve, or as `a` or `an`.
+///
+/// Example:
+/// ```
+/// use libmrot::parse_duration;
+///
+/// assert_eq!(parse_duration("10 days").unwrap(), 10);
+/// assert_eq!(parse_duration("a week").unwrap(), 7);
+/// assert_eq!(parse_duration("3 months and 1 week").unwrap(), 97);
+/// assert_eq!(parse_duration("1 year, 2 months").unwrap(), 425);
+/// ```
+///
+/// Error:
+///
+/// Returns an error if the expression is not a sum of numbers of days, weeks, months or years.
+/// ```
+/// use libmrot::{parse_duration, Error};
+///
+/// let error_result = parse_duration("a fortnight").unwrap_err();
+/// assert!(matches!(error_result, Error::InvalidDuration(_)));
+/// ```
+#[instrument]
+pub fn parse_duration(expression: &str) -> Result<u32> {
+    let invalid = || Error::InvalidDuration(expression.to_string());
+    let lowercase = expression.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty() && *word != "and")
+        .collect();
+    if words.is_empty() || words.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    words.chunks(2).try_fold(0u32, |days, pair| {
+        let number = parse_number(pair[0]).ok_or_else(invalid)?;
+        let unit_days = match pair[1].trim_end_matches('s') {
+            "day" => 1,
+            "week" => 7,
+            "month" => 30,
+            "year" => 365,
+            _ => return Err(invalid()),
+        };
+        number
+            .checked_mul(unit_days)
+            .and_then(|unit_total| days.checked_add(unit_total))
+            .ok_or_else(invalid)
+    })
+}
+
+fn parse_number(word: &str) -> Option<u32> {
+    const NUMBERS: [&str; 13] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve",
+    ];
+    match word {
+        "a" | "an" => Some(1),
+        _ => word.parse().ok().or_else(|| {
+            NUMBERS
+                .iter()
+                .position(|number| *number == word)
+                .map(|n| n as u32)
+        }),
+    }
+}
+
 /// Convert multiple human-readable date strings to timestamps. The result vector is guaranteed to contain
 /// at least one timestamp per string in the input vector.
 ///
@@ -134,13 +229,48 @@ fn remove_last_date(vec: &mut Vec<NaiveDate>) {
 /// ```
 #[instrument]
 pub fn convert_to_timestamps(dates: &Vec<String>) -> Result<Vec<i64>> {
+    convert_to_timestamps_at(dates, Local::now().naive_local())
+}
+
+/// Like [`convert_to_timestamps`], but relative date expressions are resolved against the given
+/// moment instead of the current time.
+#[instrument]
+pub fn convert_to_timestamps_at(dates: &Vec<String>, now: NaiveDateTime) -> Result<Vec<i64>> {
+    convert_to_timestamps_in(dates, now, &Timezone::UTC)
+}
+
+/// Like [`convert_to_timestamps_at`], but each date is converted to the timestamp of its start in
+/// the given [`Timezone`] instead of UTC.
+///
+/// Example:
+/// ```
+/// use libmrot::{convert_to_timestamps_in, Timezone};
+/// use chrono::NaiveDate;
+///
+/// let now = NaiveDate::from_ymd_opt(2025, 4, 15)
+///     .unwrap()
+///     .and_hms_opt(12, 0, 0)
+///     .unwrap();
+/// let timezone: Timezone = "America/Los_Angeles".parse().unwrap();
+///
+/// let timestamps = convert_to_timestamps_in(&vec!["today".to_string()], now, &timezone).unwrap();
+///
+/// // midnight in Los Angeles is 07:00 UTC during daylight saving time
+/// assert_eq!(timestamps, vec![1744700400]);
+/// ```
+#[instrument]
+pub fn convert_to_timestamps_in(
+    dates: &Vec<String>,
+    now: NaiveDateTime,
+    timezone: &Timezone,
+) -> Result<Vec<i64>> {
     dates
         .iter()
         .map(|date| {
-            parse_date(date).map(|naive_dates| {
+            parse_date_at(date, now).map(|naive_dates| {
                 naive_dates
                     .iter()
-                    .map(|naive_date| convert_date_to_timestamp(naive_date))
+                    .map(|naive_date| timezone.start_of_day(naive_date))
                     .collect::<Vec<i64>>()
             })
         })
@@ -148,15 +278,11 @@ pub fn convert_to_timestamps(dates: &Vec<String>) -> Result<Vec<i64>> {
         .map(|nested| nested.into_iter().flatten().collect())
 }
 
-/// Converts a NaiveDate to Unix timestamp
+/// Converts a NaiveDate to the Unix timestamp of its start in UTC
 #[instrument(level = "debug", fields(result))]
 pub(crate) fn convert_date_to_timestamp(date: &NaiveDate) -> i64 {
-    let timestamp = date
-        .and_hms_opt(0, 0, 0)
-        .expect("invalid hour, minute, or second")
-        .and_utc()
-        .timestamp();
-    Span::current().record("result", &timestamp);
+    let timestamp = Timezone::UTC.start_of_day(date);
+    Span::current().record("result", timestamp);
     timestamp
 }
 
@@ -2,6 +2,10 @@
 
 #[allow(unused_imports)]
 use crate::MealRecord;
+use crate::SCHEMA_VERSION;
+use csv::Error as CsvError;
+use iana_time_zone::GetTimezoneError;
+use serde_json::Error as JsonError;
 use sqlite::Error as SqliteError;
 use std::{convert::From, fmt, io::Error as IoError, num::ParseIntError};
 use two_timer::TimeError;
@@ -25,6 +29,39 @@ pub enum Error {
     ParseMealRecordError,
     /// A date expression parses to more than one date when it should not.
     MoreThanOneDate(String),
+    /// The database has a newer schema version than this library supports.
+    SchemaTooNew(i64),
+    /// The database has an older schema version which must be migrated before reading it.
+    SchemaOutdated(i64),
+    /// A timezone name is not a known IANA timezone.
+    UnknownTimezone(String),
+    /// Wraps [`iana_time_zone::GetTimezoneError`].
+    LocalTimezone(GetTimezoneError),
+    /// Wraps [`csv::Error`].
+    Csv(CsvError),
+    /// Wraps [`serde_json::Error`].
+    Json(JsonError),
+    /// Imported CSV data lacks a column.
+    MissingColumn(String),
+    /// Imported iCalendar data is malformed.
+    InvalidCalendar(String),
+    /// The records of a journaled operation have changed since, so that it cannot be undone or
+    /// redone.
+    ChangedRecords(String),
+    /// The storage does not know a meal of this name.
+    UnknownMeal(String),
+    /// A duration expression cannot be parsed.
+    InvalidDuration(String),
+    /// A record status is not `planned`, `eaten` or `skipped`.
+    InvalidStatus(String),
+    /// A tag is empty or consists of whitespace only.
+    InvalidTag(String),
+    /// An alias is empty, consists of whitespace only or is the name of its own meal.
+    InvalidAlias(String),
+    /// An alias already belongs to another meal.
+    AliasTaken(String),
+    /// The storage does not know an alias of this name.
+    UnknownAlias(String),
 }
 
 impl fmt::Display for Error {
@@ -41,6 +78,52 @@ impl fmt::Display for Error {
                 &format!("date expression '{}' parses to more than one date ", s),
                 f,
             ),
+            Error::SchemaTooNew(version) => fmt::Display::fmt(
+                &format!(
+                    "database schema version {} is newer than the supported version {}",
+                    version, SCHEMA_VERSION
+                ),
+                f,
+            ),
+            Error::SchemaOutdated(version) => fmt::Display::fmt(
+                &format!(
+                    "database schema version {} must be migrated to version {} first",
+                    version, SCHEMA_VERSION
+                ),
+                f,
+            ),
+            Error::UnknownTimezone(s) => fmt::Display::fmt(&format!("unknown timezone '{}'", s), f),
+            Error::LocalTimezone(get_timezone_error) => fmt::Display::fmt(get_timezone_error, f),
+            Error::Csv(csv_error) => fmt::Display::fmt(csv_error, f),
+            Error::Json(json_error) => fmt::Display::fmt(json_error, f),
+            Error::MissingColumn(column) => {
+                fmt::Display::fmt(&format!("missing column '{}'", column), f)
+            }
+            Error::InvalidCalendar(reason) => {
+                fmt::Display::fmt(&format!("invalid calendar: {}", reason), f)
+            }
+            Error::ChangedRecords(operation) => fmt::Display::fmt(
+                &format!("the records of '{}' have changed since", operation),
+                f,
+            ),
+            Error::UnknownMeal(meal) => fmt::Display::fmt(&format!("unknown meal '{}'", meal), f),
+            Error::InvalidDuration(expression) => {
+                fmt::Display::fmt(&format!("cannot parse duration '{}'", expression), f)
+            }
+            Error::InvalidStatus(status) => {
+                fmt::Display::fmt(&format!("unknown status '{}'", status), f)
+            }
+            Error::InvalidTag(tag) => fmt::Display::fmt(&format!("invalid tag '{}'", tag), f),
+            Error::InvalidAlias(alias) => {
+                fmt::Display::fmt(&format!("invalid alias '{}'", alias), f)
+            }
+            Error::AliasTaken(alias) => fmt::Display::fmt(
+                &format!("alias '{}' already belongs to another meal", alias),
+                f,
+            ),
+            Error::UnknownAlias(alias) => {
+                fmt::Display::fmt(&format!("unknown alias '{}'", alias), f)
+            }
         }
     }
 }
@@ -56,6 +139,22 @@ impl std::error::Error for Error {
             Error::InvalidTimestamp(_) => None,
             Error::ParseMealRecordError => None,
             Error::MoreThanOneDate(_) => None,
+            Error::SchemaTooNew(_) => None,
+            Error::SchemaOutdated(_) => None,
+            Error::UnknownTimezone(_) => None,
+            Error::LocalTimezone(ref get_timezone_error) => Some(get_timezone_error),
+            Error::Csv(ref csv_error) => Some(csv_error),
+            Error::Json(ref json_error) => Some(json_error),
+            Error::MissingColumn(_) => None,
+            Error::InvalidCalendar(_) => None,
+            Error::ChangedRecords(_) => None,
+            Error::UnknownMeal(_) => None,
+            Error::InvalidDuration(_) => None,
+            Error::InvalidStatus(_) => None,
+            Error::InvalidTag(_) => None,
+            Error::InvalidAlias(_) => None,
+            Error::AliasTaken(_) => None,
+            Error::UnknownAlias(_) => None,
         }
     }
 }
@@ -83,3 +182,21 @@ impl From<TimeError> for Error {
         Error::TwoTimer(value)
     }
 }
+
+impl From<GetTimezoneError> for Error {
+    fn from(value: GetTimezoneError) -> Self {
+        Error::LocalTimezone(value)
+    }
+}
+
+impl From<CsvError> for Error {
+    fn from(value: CsvError) -> Self {
+        Error::Csv(value)
+    }
+}
+
+impl From<JsonError> for Error {
+    fn from(value: JsonError) -> Self {
+        Error::Json(value)
+    }
+}
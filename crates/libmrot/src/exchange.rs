@@ -0,0 +1,179 @@
+//! Import and export of the meal history
+
+mod ics;
+
+use crate::{Error, MealRecord, Result, Status, Timezone};
+use chrono::NaiveDate;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use std::{
+    fmt,
+    io::{Read, Write},
+};
+
+/// File formats in which a [`Storage`](crate::Storage) can [export](crate::Storage::export) and
+/// [import](crate::Storage::import) its records.
+///
+/// The formats hold the records ordered by date and meal, so that the exported history can be kept
+/// under version control and diffed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExchangeFormat {
+    /// Comma-separated values with the header `date,meal,status`. The `status` column may be
+    /// missing in imported data.
+    Csv,
+    /// A JSON array of objects with the fields `date`, `meal` and `status`. The `status` field
+    /// may be missing in imported data.
+    Json,
+    /// An iCalendar file with an all-day event per record. The summary of the event is the meal.
+    /// The status of the records is not kept. An imported event is recorded on the day of its
+    /// start in the timezone of the storage.
+    /// Each event has a UID derived from the meal and the date, so that calendar applications
+    /// update rather than duplicate the events when the records are exported again.
+    Ics,
+}
+
+/// A record as it is exported. Unlike a [`MealRecord`] it has no timestamp, because the
+/// timestamp depends on the [`Timezone`](crate::Timezone) of the storage.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ExchangeRecord {
+    pub(crate) date: NaiveDate,
+    pub(crate) meal: String,
+    /// The status of the record, if the format keeps it.
+    #[serde(default)]
+    pub(crate) status: Option<Status>,
+}
+
+impl From<&MealRecord> for ExchangeRecord {
+    fn from(record: &MealRecord) -> Self {
+        ExchangeRecord {
+            date: record.naive_date(),
+            meal: record.meal(),
+            status: Some(record.status()),
+        }
+    }
+}
+
+impl ExchangeFormat {
+    /// Writes the records one by one.
+    pub(crate) fn write(&self, records: &[MealRecord], mut writer: impl Write) -> Result<()> {
+        match self {
+            ExchangeFormat::Csv => {
+                let mut csv_writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(writer);
+                csv_writer.write_record(["date", "meal", "status"])?;
+                for record in records {
+                    csv_writer.serialize(ExchangeRecord::from(record))?;
+                }
+                csv_writer.flush()?;
+            }
+            ExchangeFormat::Json => {
+                write!(writer, "[")?;
+                for (index, record) in records.iter().enumerate() {
+                    let separator = if index == 0 { "" } else { "," };
+                    write!(writer, "{}\n  ", separator)?;
+                    serde_json::to_writer(&mut writer, &ExchangeRecord::from(record))?;
+                }
+                writeln!(writer, "\n]")?;
+            }
+            ExchangeFormat::Ics => {
+                let records: Vec<ExchangeRecord> =
+                    records.iter().map(ExchangeRecord::from).collect();
+                ics::write(&records, writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the records and hands them to *visit* one by one. CSV and JSON are deserialized
+    /// record by record, without reading all of the data first. The times of iCalendar events
+    /// are converted to dates in the *timezone*.
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the data is malformed, if the CSV header lacks a column or if an
+    /// iCalendar event lacks a start or a summary, or the first error returned by *visit*. The
+    /// records before the error have been visited then.
+    pub(crate) fn read(
+        &self,
+        reader: impl Read,
+        timezone: &Timezone,
+        mut visit: impl FnMut(ExchangeRecord) -> Result<()>,
+    ) -> Result<()> {
+        match self {
+            ExchangeFormat::Csv => {
+                let mut csv_reader = csv::Reader::from_reader(reader);
+                let headers = csv_reader.headers()?;
+                if let Some(column) = ["date", "meal"]
+                    .into_iter()
+                    .find(|column| !headers.iter().any(|header| header == *column))
+                {
+                    return Err(Error::MissingColumn(column.to_string()));
+                }
+                for exchange_record in csv_reader.into_deserialize() {
+                    visit(exchange_record?)?;
+                }
+                Ok(())
+            }
+            ExchangeFormat::Json => {
+                let mut deserializer = serde_json::Deserializer::from_reader(reader);
+                let mut json_visitor = JsonVisitor { visit, error: None };
+                let result = deserializer.deserialize_seq(&mut json_visitor);
+                if let Some(error) = json_visitor.error {
+                    return Err(error);
+                }
+                result?;
+                Ok(deserializer.end()?)
+            }
+            ExchangeFormat::Ics => ics::read(reader, timezone)?.into_iter().try_for_each(visit),
+        }
+    }
+}
+
+/// Hands the records of a JSON array to *visit* as they are deserialized.
+struct JsonVisitor<F> {
+    visit: F,
+    /// The error returned by *visit*, which stopped the deserialization.
+    error: Option<Error>,
+}
+
+impl<'de, F: FnMut(ExchangeRecord) -> Result<()>> Visitor<'de> for &mut JsonVisitor<F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        while let Some(exchange_record) = seq.next_element()? {
+            if let Err(error) = (self.visit)(exchange_record) {
+                let message = error.to_string();
+                self.error = Some(error);
+                return Err(de::Error::custom(message));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ExchangeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExchangeFormat::Csv => fmt::Display::fmt("CSV", f),
+            ExchangeFormat::Json => fmt::Display::fmt("JSON", f),
+            ExchangeFormat::Ics => fmt::Display::fmt("iCalendar", f),
+        }
+    }
+}
+
+/// The outcome of an [import](crate::Storage::import).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportReport {
+    /// Records which were imported, or which would be imported in a dry run.
+    pub imported: Vec<MealRecord>,
+    /// Records which were skipped because the storage or the imported data already contain the
+    /// same meal on the same date.
+    pub duplicates: Vec<MealRecord>,
+}
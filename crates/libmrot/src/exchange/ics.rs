@@ -0,0 +1,203 @@
+//! iCalendar ([RFC 5545](https://www.rfc-editor.org/rfc/rfc5545)) reading and writing
+
+use super::ExchangeRecord;
+use crate::{Error, Result, Timezone};
+use chrono::{Days, NaiveDate, NaiveDateTime};
+use std::{
+    io::{Read, Write},
+    str::FromStr,
+};
+
+/// Content lines longer than this many octets are folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Writes a calendar with one all-day event per record.
+pub(super) fn write(records: &[ExchangeRecord], mut writer: impl Write) -> Result<()> {
+    write_line(&mut writer, "BEGIN:VCALENDAR")?;
+    write_line(&mut writer, "VERSION:2.0")?;
+    write_line(&mut writer, "PRODID:-//fleetingbytes//mrot//EN")?;
+    write_line(&mut writer, "CALSCALE:GREGORIAN")?;
+    for record in records {
+        let next_day = record
+            .date
+            .checked_add_days(Days::new(1))
+            .unwrap_or(record.date);
+        write_line(&mut writer, "BEGIN:VEVENT")?;
+        write_line(&mut writer, &format!("UID:{}", uid(record)))?;
+        write_line(
+            &mut writer,
+            &format!("DTSTAMP:{}T000000Z", record.date.format("%Y%m%d")),
+        )?;
+        write_line(
+            &mut writer,
+            &format!("DTSTART;VALUE=DATE:{}", record.date.format("%Y%m%d")),
+        )?;
+        write_line(
+            &mut writer,
+            &format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+        )?;
+        write_line(&mut writer, &format!("SUMMARY:{}", escape(&record.meal)))?;
+        write_line(&mut writer, "END:VEVENT")?;
+    }
+    write_line(&mut writer, "END:VCALENDAR")?;
+    Ok(())
+}
+
+/// Reads one record from each event in the calendar: the meal from its summary and the date from
+/// its start, in the *timezone* of the storage. The properties of the components nested in an
+/// event, e.g. of its alarms, are not the event's.
+pub(super) fn read(mut reader: impl Read, timezone: &Timezone) -> Result<Vec<ExchangeRecord>> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let mut records = Vec::new();
+    // the names of the components which the line lies in, the innermost last
+    let mut components: Vec<String> = Vec::new();
+    let mut date: Option<NaiveDate> = None;
+    let mut meal: Option<String> = None;
+    for line in unfold(&content) {
+        let (name_and_parameters, value) = line.split_once(':').ok_or(Error::InvalidCalendar(
+            format!("line without a value: {}", line),
+        ))?;
+        let mut name_and_parameters = name_and_parameters.split(';');
+        let name = name_and_parameters
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let in_event = components
+            .last()
+            .is_some_and(|component| component == "VEVENT");
+        match name.as_str() {
+            "BEGIN" => {
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    date = None;
+                    meal = None;
+                }
+                components.push(value.to_ascii_uppercase());
+            }
+            "END" => {
+                if components.pop() != Some(value.to_ascii_uppercase()) {
+                    return Err(Error::InvalidCalendar(format!("unexpected END:{}", value)));
+                }
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    let date = date
+                        .take()
+                        .ok_or(Error::InvalidCalendar("event without DTSTART".to_string()))?;
+                    let meal = meal
+                        .take()
+                        .ok_or(Error::InvalidCalendar("event without SUMMARY".to_string()))?;
+                    records.push(ExchangeRecord {
+                        date,
+                        meal,
+                        status: None,
+                    });
+                }
+            }
+            "DTSTART" if in_event => {
+                let parameters: Vec<&str> = name_and_parameters.collect();
+                date = Some(parse_start(value, &parameters, timezone)?);
+            }
+            "SUMMARY" if in_event => meal = Some(unescape(value)),
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+/// Returns an identifier of the event of a record which stays the same across exports.
+fn uid(record: &ExchangeRecord) -> String {
+    format!(
+        "{}-{:016x}@mrot",
+        record.date.format("%Y%m%d"),
+        fnv1a(record.meal.as_bytes())
+    )
+}
+
+/// 64-bit FNV-1a hash, which unlike [`std::hash::DefaultHasher`] is stable across Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Writes a content line, folded after every 75 octets, terminated by CRLF.
+fn write_line(writer: &mut impl Write, line: &str) -> Result<()> {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            write!(writer, "\r\n ")?;
+            octets = 1;
+        }
+        write!(writer, "{}", c)?;
+        octets += c.len_utf8();
+    }
+    write!(writer, "\r\n")?;
+    Ok(())
+}
+
+/// Joins folded lines and drops empty ones.
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Parses the start of an event into its date in the *timezone* of the storage. The start is a
+/// `DATE`, a `DATE-TIME` in UTC, a `DATE-TIME` in the timezone given by the `TZID` parameter or a
+/// floating `DATE-TIME`, which is taken as the wall-clock time of the storage. A `TZID` which is
+/// not an IANA timezone name is taken as the timezone of the storage, too.
+fn parse_start(value: &str, parameters: &[&str], timezone: &Timezone) -> Result<NaiveDate> {
+    let invalid = || Error::InvalidCalendar(format!("invalid date: {}", value));
+    let Some((date, time)) = value.split_once(['T', 't']) else {
+        return NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid());
+    };
+    let (time, utc) = match time.strip_suffix(['Z', 'z']) {
+        Some(time) => (time, true),
+        None => (time, false),
+    };
+    let datetime = NaiveDateTime::parse_from_str(&format!("{}T{}", date, time), "%Y%m%dT%H%M%S")
+        .map_err(|_| invalid())?;
+    let option_moment = match utc {
+        true => Some(datetime.and_utc()),
+        false => parameters
+            .iter()
+            .find_map(|parameter| match parameter.split_once('=') {
+                Some((key, tzid)) if key.eq_ignore_ascii_case("TZID") => Some(tzid),
+                _ => None,
+            })
+            .and_then(|tzid| Timezone::from_str(tzid.trim_matches('"')).ok())
+            .and_then(|event_timezone| event_timezone.moment_of(&datetime)),
+    };
+    Ok(match option_moment {
+        Some(moment) => timezone.naive_local(moment).date(),
+        None => datetime.date(),
+    })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => result.push('\n'),
+                Some(escaped) => result.push(escaped),
+                None => result.push('\\'),
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
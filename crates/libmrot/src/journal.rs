@@ -0,0 +1,322 @@
+//! Journal of the operations which changed the records of a storage
+
+use crate::{Meal, MealRecord, Result, Status, Timezone};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// A change of the records of a [`Storage`](crate::Storage), as kept in its journal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    /// The records were added.
+    Add {
+        /// The added records.
+        records: Vec<MealRecord>,
+        /// The meals which adding created. Redoing the operation creates them again with the same
+        /// ids.
+        created_meals: Vec<Meal>,
+    },
+    /// The records were removed.
+    Remove {
+        /// The removed records.
+        records: Vec<MealRecord>,
+        /// The meals which removing deleted because they were left without records. Undoing the
+        /// operation creates them again with the same ids.
+        deleted_meals: Vec<Meal>,
+    },
+    /// The records of the meal `old_name` were renamed to `new_name`.
+    Rename {
+        /// The name of the meal before renaming.
+        old_name: String,
+        /// The name of the meal after renaming.
+        new_name: String,
+        /// The renamed records as they were before renaming.
+        records: Vec<MealRecord>,
+        /// The meal `new_name`, if renaming created it. Redoing the operation creates it again
+        /// with the same id.
+        created_meal: Option<Box<Meal>>,
+        /// The meal `old_name` as it was before renaming deleted it. Undoing the operation creates
+        /// it again with the same id, cooldown, tags and aliases.
+        deleted_meal: Option<Box<Meal>>,
+    },
+    /// The status of the records was changed, e.g. by confirming or skipping them.
+    SetStatus {
+        /// The records with the status they had before the change.
+        records: Vec<MealRecord>,
+        /// The status of each of the records after the change, in the same order.
+        new: Vec<Status>,
+    },
+}
+
+impl Operation {
+    /// Returns the operation which reverts this one.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{MealRecord, Operation};
+    ///
+    /// let records = vec![MealRecord::new("pizza", "April 6, 2025").unwrap()];
+    /// let add = Operation::Add { records: records.clone(), created_meals: Vec::new() };
+    ///
+    /// assert_eq!(add.inverse(), Operation::Remove { records, deleted_meals: Vec::new() });
+    /// assert_eq!(add.inverse().inverse(), add);
+    /// ```
+    pub fn inverse(&self) -> Operation {
+        match self {
+            Operation::Add {
+                records,
+                created_meals,
+            } => Operation::Remove {
+                records: records.clone(),
+                deleted_meals: created_meals.clone(),
+            },
+            Operation::Remove {
+                records,
+                deleted_meals,
+            } => Operation::Add {
+                records: records.clone(),
+                created_meals: deleted_meals.clone(),
+            },
+            Operation::Rename {
+                old_name,
+                new_name,
+                records,
+                created_meal,
+                deleted_meal,
+            } => Operation::Rename {
+                old_name: new_name.clone(),
+                new_name: old_name.clone(),
+                records: records
+                    .iter()
+                    .map(|record| record.renamed(new_name))
+                    .collect(),
+                created_meal: deleted_meal.clone(),
+                deleted_meal: created_meal.clone(),
+            },
+            Operation::SetStatus { records, new } => Operation::SetStatus {
+                records: records
+                    .iter()
+                    .zip(new)
+                    .map(|(record, &status)| record.clone().with_status(status))
+                    .collect(),
+                new: records.iter().map(|record| record.status()).collect(),
+            },
+        }
+    }
+
+    /// Returns the operation with the timestamps of its records moved to the start of their dates
+    /// in the timezone.
+    pub(crate) fn reanchored(&self, timezone: &Timezone) -> Result<Operation> {
+        let records = self
+            .records()
+            .iter()
+            .map(|record| {
+                let timestamp = timezone.start_of_day(&record.naive_date());
+                MealRecord::from_meal_and_timestamp_in(&record.meal(), timestamp, timezone)
+                    .map(|new_record| new_record.with_status(record.status()))
+            })
+            .collect::<Result<Vec<MealRecord>>>()?;
+        let reanchor_meals = |meals: &[Meal]| {
+            meals
+                .iter()
+                .map(|meal| meal.reanchored(timezone))
+                .collect::<Result<Vec<Meal>>>()
+        };
+        let reanchor_meal = |option_meal: &Option<Box<Meal>>| {
+            option_meal
+                .as_ref()
+                .map(|meal| meal.reanchored(timezone).map(Box::new))
+                .transpose()
+        };
+        Ok(match self {
+            Operation::Add { created_meals, .. } => Operation::Add {
+                records,
+                created_meals: reanchor_meals(created_meals)?,
+            },
+            Operation::Remove { deleted_meals, .. } => Operation::Remove {
+                records,
+                deleted_meals: reanchor_meals(deleted_meals)?,
+            },
+            Operation::Rename {
+                old_name,
+                new_name,
+                created_meal,
+                deleted_meal,
+                ..
+            } => Operation::Rename {
+                old_name: old_name.clone(),
+                new_name: new_name.clone(),
+                records,
+                created_meal: reanchor_meal(created_meal)?,
+                deleted_meal: reanchor_meal(deleted_meal)?,
+            },
+            Operation::SetStatus { new, .. } => Operation::SetStatus {
+                records,
+                new: new.clone(),
+            },
+        })
+    }
+
+    /// Returns the operation with the records of the meal *from* given to the meal *to*, after
+    /// *from* was merged into *to*. As *from* is gone, the operation neither creates nor deletes
+    /// it anymore.
+    pub(crate) fn with_meal_merged(&self, from: &str, to: &str) -> Operation {
+        let merge_name = |name: &String| match name == from {
+            true => to.to_string(),
+            false => name.clone(),
+        };
+        let records = self
+            .records()
+            .iter()
+            .map(|record| record.renamed(&merge_name(&record.meal())))
+            .collect();
+        let other_meals = |meals: &[Meal]| {
+            meals
+                .iter()
+                .filter(|meal| meal.name() != from)
+                .cloned()
+                .collect()
+        };
+        let other_meal = |option_meal: &Option<Box<Meal>>| {
+            option_meal.clone().filter(|meal| meal.name() != from)
+        };
+        match self {
+            Operation::Add { created_meals, .. } => Operation::Add {
+                records,
+                created_meals: other_meals(created_meals),
+            },
+            Operation::Remove { deleted_meals, .. } => Operation::Remove {
+                records,
+                deleted_meals: other_meals(deleted_meals),
+            },
+            Operation::Rename {
+                old_name,
+                new_name,
+                created_meal,
+                deleted_meal,
+                ..
+            } => Operation::Rename {
+                old_name: merge_name(old_name),
+                new_name: merge_name(new_name),
+                records,
+                created_meal: other_meal(created_meal),
+                deleted_meal: other_meal(deleted_meal),
+            },
+            Operation::SetStatus { new, .. } => Operation::SetStatus {
+                records,
+                new: new.clone(),
+            },
+        }
+    }
+
+    /// Returns the name of the kind of operation: `add`, `remove`, `rename` or `status`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Rename { .. } => "rename",
+            Operation::SetStatus { .. } => "status",
+        }
+    }
+
+    /// Returns the records affected by the operation.
+    pub fn records(&self) -> &[MealRecord] {
+        match self {
+            Operation::Add { records, .. }
+            | Operation::Remove { records, .. }
+            | Operation::Rename { records, .. }
+            | Operation::SetStatus { records, .. } => records,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    /// Describes the operation in one line. Up to three records are listed, more are counted.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let records = self.records();
+        let listed = match records.len() {
+            0 => "no records".to_string(),
+            1..=3 => records
+                .iter()
+                .map(|record| record.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+            n => format!("{} records", n),
+        };
+        match self {
+            Operation::Rename {
+                old_name, new_name, ..
+            } => write!(f, "rename {} to {}: {}", old_name, new_name, listed),
+            Operation::SetStatus { new, .. } => match new.split_first() {
+                Some((status, others)) if others.iter().all(|other| other == status) => {
+                    write!(f, "mark {} as {}", listed, status.name())
+                }
+                _ => write!(f, "change the status of {}", listed),
+            },
+            _ => write!(f, "{} {}", self.kind(), listed),
+        }
+    }
+}
+
+/// An operation in the journal of a [`Storage`](crate::Storage).
+#[derive(Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    id: i64,
+    performed: DateTime<Utc>,
+    operation: Operation,
+    undone: bool,
+}
+
+impl JournalEntry {
+    /// Constructs a journal entry from its parts as they are kept in a store.
+    pub(crate) fn new(
+        id: i64,
+        performed: DateTime<Utc>,
+        operation: Operation,
+        undone: bool,
+    ) -> Self {
+        JournalEntry {
+            id,
+            performed,
+            operation,
+            undone,
+        }
+    }
+
+    /// Returns a copy of the entry which is marked as undone or not.
+    pub(crate) fn with_undone(&self, undone: bool) -> Self {
+        JournalEntry {
+            undone,
+            ..self.clone()
+        }
+    }
+
+    /// Get the id of the entry. Later entries have greater ids.
+    pub fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// Get the moment when the operation was performed.
+    pub fn performed(&self) -> DateTime<Utc> {
+        self.performed
+    }
+
+    /// Get the operation.
+    pub fn operation(&self) -> &Operation {
+        &self.operation
+    }
+
+    /// Returns true if the operation was undone and can be redone.
+    pub fn is_undone(&self) -> bool {
+        self.undone
+    }
+}
+
+impl fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if self.undone {
+            write!(f, " (undone)")?;
+        }
+        Ok(())
+    }
+}
@@ -4,17 +4,40 @@
 //!
 //! [mrot]: https://docs.rs/mrot
 
+mod clock;
 mod convert;
 mod error;
+mod exchange;
+mod journal;
+mod meal;
 mod meal_record;
+mod migration;
+mod stats;
 mod storage;
+mod store;
+mod suggestion;
+mod timezone;
 
-use crate::convert::convert_date_to_timestamp;
-use chrono::NaiveDate;
-pub use convert::{convert_to_timestamps, parse_date};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use convert::{
+    convert_to_timestamps, convert_to_timestamps_at, convert_to_timestamps_in, parse_date,
+    parse_date_at, parse_duration,
+};
 pub use error::Error;
-pub use meal_record::MealRecord;
+pub use exchange::{ExchangeFormat, ImportReport};
+pub use journal::{JournalEntry, Operation};
+pub use meal::{ignored_tag, normalize_name, Meal, MealId, TagFilter, TAG_PREFIX};
+pub use meal_record::{MealRecord, Status};
+pub use migration::SCHEMA_VERSION;
+pub use stats::{Statistics, Streak};
 pub use storage::Storage;
+pub use store::{MealStore, MemoryStore, RecordFilter, SqliteStore};
+pub use suggestion::{
+    Diversity, LeastFrequent, MealStatistics, OldestFirst, Overdue, SuggestionStrategy,
+    WeightedRandom,
+};
+pub use timezone::Timezone;
 
 /// Type alias for results with libmrot's [`Error`].
 pub type Result<T> = std::result::Result<T, Error>;
@@ -33,7 +56,9 @@ pub struct Period {
 
 impl Period {
     /// Construct a new `Period`. The string argument should be a parsable date expression
-    /// (cf. [`parse_date`]).
+    /// (cf. [`parse_date`]). Relative date expressions are resolved against the system's local
+    /// time, but the days of the period start at midnight UTC; use [`Storage::period`] for the
+    /// days of a storage.
     ///
     /// Examples of successfully constructed `Period`s:
     /// ```
@@ -62,11 +87,56 @@ impl Period {
     /// assert!(matches!(error_result, Error::TwoTimer(_)));
     /// ```
     pub fn new(s: &str) -> Result<Self> {
-        let dates = parse_date(s)?;
-        let first_date = dates.iter().next().unwrap();
+        Self::new_at(s, Local::now().naive_local())
+    }
+
+    /// Like [`Period::new`], but relative date expressions are resolved against the given moment
+    /// instead of the current time (cf. [`parse_date_at`]). The days of the period start at
+    /// midnight UTC, cf. [`Period::new_in`].
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Period;
+    /// use chrono::NaiveDate;
+    ///
+    /// let now = NaiveDate::from_ymd_opt(2025, 3, 9)
+    ///     .unwrap()
+    ///     .and_hms_opt(12, 0, 0)
+    ///     .unwrap();
+    ///
+    /// let period = Period::new_at("from tomorrow through 11 days after tomorrow", now).unwrap();
+    /// assert_eq!(period.first_date(), NaiveDate::from_ymd_opt(2025, 3, 10).unwrap());
+    /// assert_eq!(period.last_date(), NaiveDate::from_ymd_opt(2025, 3, 21).unwrap());
+    /// ```
+    pub fn new_at(s: &str, now: NaiveDateTime) -> Result<Self> {
+        Self::new_in(s, now, &Timezone::UTC)
+    }
+
+    /// Like [`Period::new_at`], but the days of the period start at midnight in the given
+    /// [`Timezone`] instead of UTC.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{Period, Timezone};
+    /// use chrono::NaiveDate;
+    ///
+    /// let now = NaiveDate::from_ymd_opt(2025, 4, 15)
+    ///     .unwrap()
+    ///     .and_hms_opt(12, 0, 0)
+    ///     .unwrap();
+    /// let timezone: Timezone = "Europe/Prague".parse().unwrap();
+    ///
+    /// let period = Period::new_in("today", now, &timezone).unwrap();
+    /// assert_eq!(period.first_date(), NaiveDate::from_ymd_opt(2025, 4, 15).unwrap());
+    /// // midnight in Prague is 22:00 UTC of the previous day during daylight saving time
+    /// assert_eq!(period.first_day_timestamp(), 1744668000);
+    /// ```
+    pub fn new_in(s: &str, now: NaiveDateTime, timezone: &Timezone) -> Result<Self> {
+        let dates = parse_date_at(s, now)?;
+        let first_date = dates.first().unwrap();
         let last_date = dates.iter().last().unwrap();
-        let first_day_timestamp = convert_date_to_timestamp(first_date);
-        let last_day_timestamp = convert_date_to_timestamp(last_date);
+        let first_day_timestamp = timezone.start_of_day(first_date);
+        let last_day_timestamp = timezone.start_of_day(last_date);
         Ok(Self {
             first_day_timestamp,
             last_day_timestamp,
@@ -75,6 +145,20 @@ impl Period {
         })
     }
 
+    /// Constructs the period of the days from *first_date* through *last_date* in the timezone.
+    pub(crate) fn between(
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+        timezone: &Timezone,
+    ) -> Self {
+        Self {
+            first_day_timestamp: timezone.start_of_day(&first_date),
+            last_day_timestamp: timezone.start_of_day(&last_date),
+            first_date,
+            last_date,
+        }
+    }
+
     /// Returns the [`NaiveDate`] of the first day of the period.
     pub fn first_date(&self) -> NaiveDate {
         self.first_date
@@ -94,4 +178,9 @@ impl Period {
     pub fn last_day_timestamp(&self) -> i64 {
         self.last_day_timestamp
     }
+
+    /// Returns true if the timestamp lies within one of the days of the period.
+    pub fn contains_timestamp(&self, timestamp: i64) -> bool {
+        timestamp >= self.first_day_timestamp && timestamp <= self.last_day_timestamp
+    }
 }
@@ -0,0 +1,258 @@
+use crate::{Result, Timezone};
+use caseless::default_case_fold_str;
+use chrono::NaiveDate;
+use std::fmt;
+use unicode_normalization::UnicodeNormalization;
+
+/// Returns the key by which meal names are matched: the name in Unicode NFC, case folded, with
+/// runs of whitespace collapsed to single spaces and without leading or trailing whitespace. Two
+/// names with the same key name the same meal.
+///
+/// Example:
+/// ```
+/// use libmrot::normalize_name;
+///
+/// assert_eq!(normalize_name("  Gratin  à la M. O. "), "gratin à la m. o.");
+/// // "s" followed by a combining caron is the same as "š"
+/// assert_eq!(normalize_name("S\u{30c}unkafleky"), normalize_name("šunkafleky"));
+/// assert_eq!(normalize_name("Rinderbraten"), normalize_name("RINDERBRATEN"));
+/// ```
+pub fn normalize_name(name: &str) -> String {
+    let composed: String = name.nfc().collect();
+    let folded: String = default_case_fold_str(&composed).nfc().collect();
+    folded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// The prefix which marks an entry of an ignore list as a tag, e.g. `tag:fish`. Entries without
+/// it name meals.
+pub const TAG_PREFIX: &str = "tag:";
+
+/// Returns the tag of an ignore list entry with the [`TAG_PREFIX`], or `None` if the entry names
+/// a meal. Leading and trailing whitespace is removed from the tag.
+///
+/// Example:
+/// ```
+/// use libmrot::ignored_tag;
+///
+/// assert_eq!(ignored_tag("tag:fish"), Some("fish"));
+/// assert_eq!(ignored_tag("tag: fish "), Some("fish"));
+/// assert_eq!(ignored_tag("fish"), None);
+/// ```
+pub fn ignored_tag(entry: &str) -> Option<&str> {
+    entry.strip_prefix(TAG_PREFIX).map(str::trim)
+}
+
+/// Returns the Levenshtein distance between *a* and *b*, the least number of characters which
+/// must be inserted, deleted or substituted to turn one into the other.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(a_char != *b_char);
+            row.push(substitution.min(previous_row[j + 1] + 1).min(row[j] + 1));
+        }
+        previous_row = row;
+    }
+    previous_row[b_chars.len()]
+}
+
+/// Stable identifier of a [`Meal`] in the storage. It does not change when the meal is renamed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MealId(i64);
+
+impl MealId {
+    /// Wraps a raw id.
+    pub fn new(id: i64) -> Self {
+        MealId(id)
+    }
+
+    /// Get the raw id.
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl fmt::Display for MealId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A kind of meal known to the storage. Each [`MealRecord`](crate::MealRecord) refers to one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Meal {
+    /// The meal's id.
+    id: MealId,
+    /// The canonical name of the meal.
+    name: String,
+    /// The date of the meal's earliest record at the time when the meal became known to the
+    /// storage, formatted as Unix timestamp.
+    created: i64,
+    /// The date of `created` in the storage's timezone.
+    created_date: NaiveDate,
+    /// The minimum number of days between two records of the meal which are suggested.
+    cooldown: Option<u32>,
+    /// The tags of the meal, sorted and without duplicates.
+    tags: Vec<String>,
+    /// The other names of the meal, sorted and without duplicates.
+    aliases: Vec<String>,
+}
+
+impl Meal {
+    /// Constructs a Meal from its parts as they are kept in a storage with the given timezone.
+    pub(crate) fn new(id: MealId, name: &str, created: i64, timezone: &Timezone) -> Result<Self> {
+        Ok(Meal {
+            id,
+            name: name.to_string(),
+            created,
+            created_date: timezone.date_of(created)?,
+            cooldown: None,
+            tags: Vec::new(),
+            aliases: Vec::new(),
+        })
+    }
+
+    /// Returns the meal with the given cooldown.
+    pub(crate) fn with_cooldown(self, cooldown: Option<u32>) -> Self {
+        Meal { cooldown, ..self }
+    }
+
+    /// Returns the meal with the given tags.
+    pub(crate) fn with_tags(self, mut tags: Vec<String>) -> Self {
+        tags.sort();
+        tags.dedup();
+        Meal { tags, ..self }
+    }
+
+    /// Returns the meal with the given aliases.
+    pub(crate) fn with_aliases(self, mut aliases: Vec<String>) -> Self {
+        aliases.sort();
+        aliases.dedup();
+        Meal { aliases, ..self }
+    }
+
+    /// Changes the name of the meal.
+    pub(crate) fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+
+    /// Returns the meal with its creation moved to the start of its created date in the timezone.
+    pub(crate) fn reanchored(&self, timezone: &Timezone) -> Result<Self> {
+        let created = timezone.start_of_day(&self.created_date);
+        Ok(Meal {
+            created,
+            created_date: timezone.date_of(created)?,
+            ..self.clone()
+        })
+    }
+
+    /// Returns true if the meal has a cooldown, tags or aliases.
+    pub(crate) fn has_settings(&self) -> bool {
+        self.cooldown.is_some() || !self.tags.is_empty() || !self.aliases.is_empty()
+    }
+
+    /// Get the id of the meal.
+    pub fn id(&self) -> MealId {
+        self.id
+    }
+
+    /// Get the name of the meal.
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Get the [NaiveDate] of the meal's earliest record at the time when the meal became known
+    /// to the storage.
+    pub fn created_date(&self) -> NaiveDate {
+        self.created_date
+    }
+
+    /// Get the timestamp of [`created_date`](Meal::created_date).
+    pub fn created_timestamp(&self) -> i64 {
+        self.created
+    }
+
+    /// Get the number of days which must pass after a record of the meal before the meal is
+    /// suggested again, if the meal has a cooldown.
+    pub fn cooldown(&self) -> Option<u32> {
+        self.cooldown
+    }
+
+    /// Get the tags of the meal, sorted alphabetically.
+    pub fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    /// Returns true if the meal has the tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own_tag| own_tag == tag)
+    }
+
+    /// Get the aliases of the meal, sorted alphabetically.
+    pub fn aliases(&self) -> Vec<String> {
+        self.aliases.clone()
+    }
+
+    /// Returns true if the meal is known by the alias.
+    pub fn has_alias(&self, alias: &str) -> bool {
+        self.aliases.iter().any(|own_alias| own_alias == alias)
+    }
+}
+
+/// Selects meals by their [tags](Meal::tags). An empty filter matches all meals.
+///
+/// Example:
+/// ```
+/// use libmrot::{Storage, TagFilter};
+///
+/// let storage = Storage::in_memory();
+/// storage.add_meal_on_dates("ratatouille", &vec![String::from("today")]).unwrap();
+/// storage.add_tags("ratatouille", &[String::from("vegetarian")]).unwrap();
+/// let ratatouille = storage.meal("ratatouille").unwrap().unwrap();
+///
+/// let vegetarian = TagFilter::default().with_tags(&[String::from("vegetarian")]);
+/// assert!(vegetarian.matches(&ratatouille));
+///
+/// let no_fish = TagFilter::default().with_excluded_tags(&[String::from("fish")]);
+/// assert!(no_fish.matches(&ratatouille));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TagFilter {
+    /// Match only the meals which have all of these tags.
+    pub tags: Vec<String>,
+    /// Match only the meals which have none of these tags.
+    pub excluded_tags: Vec<String>,
+}
+
+impl TagFilter {
+    /// Returns the filter which also requires the *tags*.
+    pub fn with_tags(mut self, tags: &[String]) -> Self {
+        self.tags.extend_from_slice(tags);
+        self
+    }
+
+    /// Returns the filter which also excludes the *excluded_tags*.
+    pub fn with_excluded_tags(mut self, excluded_tags: &[String]) -> Self {
+        self.excluded_tags.extend_from_slice(excluded_tags);
+        self
+    }
+
+    /// Returns true if the filter matches all meals.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.excluded_tags.is_empty()
+    }
+
+    /// Returns true if the meal matches the filter.
+    pub fn matches(&self, meal: &Meal) -> bool {
+        self.tags.iter().all(|tag| meal.has_tag(tag))
+            && !self.excluded_tags.iter().any(|tag| meal.has_tag(tag))
+    }
+}
+
+impl fmt::Display for Meal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.id)
+    }
+}
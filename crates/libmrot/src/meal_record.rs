@@ -1,18 +1,98 @@
-use crate::{convert::convert_to_naive_date, convert_date_to_timestamp, parse_date, Error, Result};
-use chrono::NaiveDate;
+use crate::{
+    convert::{convert_date_to_timestamp, convert_to_naive_date},
+    parse_date_at, Error, Result, Timezone,
+};
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 use std::{fmt, str::FromStr};
 
-/// Container for a meal and a date on which it was recorded.
-#[derive(Clone, Debug, Default, PartialEq)]
+/// Whether a recorded meal was eaten.
+///
+/// Records added for a day after today are planned, records added for today or a day before are
+/// eaten. A planned record stays planned when its day passes, until it is
+/// [confirmed](crate::Storage::confirm) or [skipped](crate::Storage::skip).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The meal is planned and not confirmed yet.
+    Planned,
+    /// The meal was eaten.
+    #[default]
+    Eaten,
+    /// The meal was planned, but not eaten.
+    Skipped,
+}
+
+impl Status {
+    /// Returns the status of a new record on the *date*: planned if the date is after *today*,
+    /// eaten otherwise.
+    pub(crate) fn of_new_record(date: NaiveDate, today: NaiveDate) -> Self {
+        match date > today {
+            true => Status::Planned,
+            false => Status::Eaten,
+        }
+    }
+
+    /// Returns the name of the status, as it is stored.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Planned => "planned",
+            Status::Eaten => "eaten",
+            Status::Skipped => "skipped",
+        }
+    }
+}
+
+impl FromStr for Status {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "planned" => Ok(Status::Planned),
+            "eaten" => Ok(Status::Eaten),
+            "skipped" => Ok(Status::Skipped),
+            _ => Err(Error::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.name(), f)
+    }
+}
+
+/// Container for a meal, a date on which it was recorded and the [`Status`] of the record.
+///
+/// Serializes as a map with the `meal`, the `date` as `YYYY-MM-DD`, the `timestamp` and the
+/// `status`.
+///
+/// Example:
+/// ```
+/// use libmrot::MealRecord;
+///
+/// let meal_record: MealRecord = "1743897600, pizza".parse().unwrap();
+/// let json = serde_json::to_string(&meal_record).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"meal":"pizza","date":"2025-04-06","timestamp":1743897600,"status":"eaten"}"#,
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 pub struct MealRecord {
     /// The meal.
     meal: String,
-    /// The date on which this meal was recorded, formatted as Unix timestamp
+    /// The date on which this meal was recorded.
+    date: NaiveDate,
+    /// The start of the date on which this meal was recorded, formatted as Unix timestamp
     timestamp: i64,
+    /// Whether the meal was eaten.
+    status: Status,
 }
 
 impl MealRecord {
-    /// Constructs a new MealRecord from a meal name and a parsable date expression.
+    /// Constructs a new MealRecord from a meal name and a parsable date expression. The day starts
+    /// at midnight UTC.
     ///
     /// Error:
     /// - if the date expression cannot be parsed
@@ -26,19 +106,36 @@ impl MealRecord {
     /// let meal_record = MealRecord::new("pizza", "today").unwrap();
     /// ```
     pub fn new(meal: &str, date: &str) -> Result<Self> {
-        let mut dates = parse_date(date)?;
+        Self::new_at(meal, date, Local::now().naive_local())
+    }
+
+    /// Like [`MealRecord::new`], but a relative date expression is resolved against the given
+    /// moment instead of the current time.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use libmrot::MealRecord;
+    /// use chrono::NaiveDate;
+    ///
+    /// let now = NaiveDate::from_ymd_opt(2025, 4, 15)
+    ///     .unwrap()
+    ///     .and_hms_opt(12, 0, 0)
+    ///     .unwrap();
+    ///
+    /// let meal_record = MealRecord::new_at("pizza", "yesterday", now).unwrap();
+    /// assert_eq!(meal_record.naive_date(), NaiveDate::from_ymd_opt(2025, 4, 14).unwrap());
+    /// ```
+    pub fn new_at(meal: &str, date: &str, now: NaiveDateTime) -> Result<Self> {
+        let mut dates = parse_date_at(date, now)?;
         if dates.len() > 1 {
             return Err(Error::MoreThanOneDate(date.to_string()));
         }
         let naive_date = dates.pop().unwrap();
-        let timestamp = convert_date_to_timestamp(&naive_date);
-        Ok(MealRecord {
-            meal: meal.to_string(),
-            timestamp,
-        })
+        Ok(Self::from_meal_and_naivedate(meal, &naive_date))
     }
 
-    /// Constructs a MealRecord from a meal name and a [NaiveDate].
+    /// Constructs a MealRecord from a meal name and a [NaiveDate]. The day starts at midnight UTC.
     ///
     /// Example:
     ///
@@ -53,12 +150,15 @@ impl MealRecord {
         let timestamp = convert_date_to_timestamp(naive_date);
         MealRecord {
             meal: meal.to_string(),
+            date: *naive_date,
             timestamp,
+            status: Status::default(),
         }
     }
 
     /// Constructs a MealRecord from a meal name and a Unix timestamp.
-    /// The timestamp is quantized to the start of its day.
+    /// The timestamp is quantized to the start of its day in UTC
+    /// (cf. [`MealRecord::from_meal_and_timestamp_in`]).
     ///
     /// Example:
     ///
@@ -90,14 +190,77 @@ impl MealRecord {
     ///
     /// Returns an Error if the timestamp cannot be converted to a [`chrono::DateTime`].
     pub fn from_meal_and_timestamp(meal: &str, timestamp: i64) -> Result<Self> {
-        let naive_date = convert_to_naive_date(timestamp)?;
-        let timestamp = convert_date_to_timestamp(&naive_date);
+        Self::from_meal_and_timestamp_in(meal, timestamp, &Timezone::UTC)
+    }
+
+    /// Constructs a MealRecord from a meal name and a Unix timestamp.
+    /// The timestamp is quantized to the start of its day in the given [`Timezone`].
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use libmrot::{MealRecord, Timezone};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// // 22:02 UTC is already the next morning in Sydney
+    /// let unquantized: i64 = Utc
+    ///     .with_ymd_and_hms(2025, 4, 2, 22, 2, 17)
+    ///     .unwrap()
+    ///     .timestamp();
+    /// let sydney: Timezone = "Australia/Sydney".parse().unwrap();
+    ///
+    /// let meal_record = MealRecord::from_meal_and_timestamp_in(
+    ///     "pizza",
+    ///     unquantized,
+    ///     &sydney,
+    /// ).unwrap();
+    ///
+    /// let expected_date = NaiveDate::from_ymd_opt(2025, 4, 3).unwrap();
+    /// assert_eq!(meal_record.naive_date(), expected_date);
+    /// assert_eq!(meal_record.timestamp(), sydney.start_of_day(&expected_date));
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns an Error if the timestamp cannot be converted to a [`chrono::DateTime`].
+    pub fn from_meal_and_timestamp_in(
+        meal: &str,
+        timestamp: i64,
+        timezone: &Timezone,
+    ) -> Result<Self> {
+        let date = timezone.date_of(timestamp)?;
+        let timestamp = timezone.start_of_day(&date);
         Ok(MealRecord {
             meal: meal.to_string(),
+            date,
             timestamp,
+            status: Status::default(),
         })
     }
 
+    /// Returns a copy of the record with another meal name.
+    pub(crate) fn renamed(&self, meal: &str) -> Self {
+        MealRecord {
+            meal: meal.to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the record with another status.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use libmrot::{MealRecord, Status};
+    ///
+    /// let meal_record = MealRecord::new("pizza", "today").unwrap();
+    /// assert_eq!(meal_record.status(), Status::Eaten);
+    /// assert_eq!(meal_record.with_status(Status::Planned).status(), Status::Planned);
+    /// ```
+    pub fn with_status(self, status: Status) -> Self {
+        MealRecord { status, ..self }
+    }
+
     /// Get the meal name of the MealRecord.
     pub fn meal(&self) -> String {
         self.meal.clone()
@@ -105,14 +268,18 @@ impl MealRecord {
 
     /// Get the [NaiveDate] of the MealRecord.
     pub fn naive_date(&self) -> NaiveDate {
-        convert_to_naive_date(self.timestamp)
-            .expect("this MealRecord somehow bypassed the timestamp validity check")
+        self.date
     }
 
     /// Get the timestamp of the MealRecord.
     pub fn timestamp(&self) -> i64 {
         self.timestamp
     }
+
+    /// Get the [`Status`] of the MealRecord.
+    pub fn status(&self) -> Status {
+        self.status
+    }
 }
 
 impl FromStr for MealRecord {
@@ -125,7 +292,17 @@ impl FromStr for MealRecord {
             .ok_or(Error::ParseMealRecordError)?
             .parse::<i64>()?;
         let meal = String::from(split.next().ok_or(Error::ParseMealRecordError)?);
-        Ok(MealRecord { meal, timestamp })
+        let status = match split.next() {
+            Some(status) => status.parse()?,
+            None => Status::default(),
+        };
+        let date = convert_to_naive_date(timestamp)?;
+        Ok(MealRecord {
+            meal,
+            date,
+            timestamp,
+            status,
+        })
     }
 }
 
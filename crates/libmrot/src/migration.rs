@@ -0,0 +1,393 @@
+//! Versioned schema migrations for the SQLite storage.
+//!
+//! The schema version of a database is kept in SQLite's `PRAGMA user_version`. Each [`Migration`]
+//! brings the schema from the previous version to its own version. When a storage is opened, all
+//! migrations newer than the database's schema version are applied in order, each one in its own
+//! transaction. Migrations which depend on the current time, like the one which plans the records
+//! after today, are given the moment of the migration instead of reading the system's clock.
+
+use crate::{normalize_name, Error, Result, Timezone};
+use chrono::{DateTime, Utc};
+use sqlite::{Connection, State, Value};
+use std::{collections::HashMap, str::FromStr};
+use tracing::{instrument, trace};
+
+/// A single step in the evolution of the database schema.
+struct Migration {
+    /// Schema version of the database after this migration was applied.
+    version: i64,
+    /// Short description of what the migration does. Used in traces.
+    description: &'static str,
+    /// Applies the migration to the database at the given moment.
+    apply: fn(&Connection, DateTime<Utc>) -> Result<()>,
+}
+
+/// All migrations, ordered by version. New migrations must be appended at the end.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create meals table",
+        apply: create_meals_table,
+    },
+    Migration {
+        version: 2,
+        description: "split meals into dishes and occurrences",
+        apply: normalize_meals_table,
+    },
+    Migration {
+        version: 3,
+        description: "create settings table with the timezone",
+        apply: create_settings_table,
+    },
+    Migration {
+        version: 4,
+        description: "create journal tables",
+        apply: create_journal_tables,
+    },
+    Migration {
+        version: 5,
+        description: "add cooldown to dishes and journal_dishes",
+        apply: add_cooldown_column,
+    },
+    Migration {
+        version: 6,
+        description: "add status to occurrences",
+        apply: add_status_columns,
+    },
+    Migration {
+        version: 7,
+        description: "create dish_tags and journal_dish_tags tables",
+        apply: create_dish_tags_table,
+    },
+    Migration {
+        version: 8,
+        description: "create dish_aliases and journal_dish_aliases tables",
+        apply: create_dish_aliases_table,
+    },
+    Migration {
+        version: 9,
+        description: "merge dishes whose names are spelled alike",
+        apply: merge_alike_dishes,
+    },
+    Migration {
+        version: 10,
+        description: "add new_status to journal_records",
+        apply: add_new_status_column,
+    },
+];
+
+/// The schema version which this version of the library creates and understands.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Reads the schema version of the database.
+#[instrument(level = "trace", skip(connection))]
+pub(crate) fn schema_version(connection: &Connection) -> Result<i64> {
+    let mut statement = connection.prepare("PRAGMA user_version")?;
+    statement.next()?;
+    Ok(statement.read::<i64, _>(0)?)
+}
+
+/// Brings the database schema up to [`SCHEMA_VERSION`] at the moment *now*.
+///
+/// Error:
+///
+/// Returns [`Error::SchemaTooNew`] if the database was created by a newer version of the library.
+#[instrument(level = "debug", skip(connection))]
+pub(crate) fn migrate(connection: &Connection, now: DateTime<Utc>) -> Result<()> {
+    let found = schema_version(connection)?;
+    if found > SCHEMA_VERSION {
+        return Err(Error::SchemaTooNew(found));
+    }
+    for migration in MIGRATIONS.iter().filter(|m| m.version > found) {
+        trace!(
+            version = migration.version,
+            description = migration.description,
+            "Apply migration"
+        );
+        connection.execute("BEGIN TRANSACTION")?;
+        let applied = (migration.apply)(connection, now)
+            .and_then(|_| set_schema_version(connection, migration.version));
+        match applied {
+            Ok(_) => connection.execute("COMMIT")?,
+            Err(e) => {
+                let _ = connection.execute("ROLLBACK");
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn set_schema_version(connection: &Connection, version: i64) -> Result<()> {
+    connection.execute(format!("PRAGMA user_version = {}", version))?;
+    Ok(())
+}
+
+/// Version 1: the original single table. Databases created before schema versioning was
+/// introduced already contain it.
+fn create_meals_table(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute("CREATE TABLE IF NOT EXISTS meals (date INTEGER, meal TEXT)")?;
+    Ok(())
+}
+
+/// Version 2: every kind of meal is stored once in `dishes`, each record of a meal is a row in
+/// `occurrences`. The `records` view joins them back together into the shape of the old `meals`
+/// table.
+fn normalize_meals_table(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute(
+        "
+        CREATE TABLE dishes (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            created INTEGER NOT NULL
+        );
+        CREATE TABLE occurrences (
+            id INTEGER PRIMARY KEY,
+            dish_id INTEGER NOT NULL REFERENCES dishes (id),
+            date INTEGER NOT NULL
+        );
+        CREATE INDEX occurrences_dish_id ON occurrences (dish_id);
+        CREATE INDEX occurrences_date ON occurrences (date);
+        INSERT INTO dishes (name, created)
+            SELECT meal, MIN(date) FROM meals
+            WHERE meal IS NOT NULL AND date IS NOT NULL
+            GROUP BY meal
+            ORDER BY MIN(rowid);
+        INSERT INTO occurrences (dish_id, date)
+            SELECT dishes.id, meals.date FROM meals
+            JOIN dishes ON dishes.name = meals.meal
+            WHERE meals.date IS NOT NULL
+            ORDER BY meals.rowid;
+        DROP TABLE meals;
+        CREATE VIEW records AS
+            SELECT occurrences.id AS id, occurrences.date AS date,
+                dishes.id AS dish_id, dishes.name AS meal
+            FROM occurrences JOIN dishes ON dishes.id = occurrences.dish_id;
+        ",
+    )?;
+    Ok(())
+}
+
+/// Version 3: a key-value table for settings which belong to the data rather than to the user's
+/// configuration. Records were always anchored at UTC midnight so far, so this is the timezone
+/// of the existing data.
+fn create_settings_table(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute(
+        "
+        CREATE TABLE settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        INSERT INTO settings (key, value) VALUES ('timezone', 'UTC');
+        ",
+    )?;
+    Ok(())
+}
+
+/// Version 4: every operation which changes records is kept in `journal`, the records it changed
+/// in `journal_records` and the dishes it created or deleted in `journal_dishes`, so that the
+/// operation can be undone and redone. The `role` of a journaled dish is `created` or `deleted`.
+fn create_journal_tables(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute(
+        "
+        CREATE TABLE journal (
+            id INTEGER PRIMARY KEY,
+            performed INTEGER NOT NULL,
+            operation TEXT NOT NULL,
+            old_name TEXT,
+            new_name TEXT,
+            undone INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE TABLE journal_records (
+            id INTEGER PRIMARY KEY,
+            journal_id INTEGER NOT NULL REFERENCES journal (id),
+            meal TEXT NOT NULL,
+            date INTEGER NOT NULL
+        );
+        CREATE INDEX journal_records_journal_id ON journal_records (journal_id);
+        CREATE TABLE journal_dishes (
+            id INTEGER PRIMARY KEY,
+            journal_id INTEGER NOT NULL REFERENCES journal (id),
+            role TEXT NOT NULL,
+            dish_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            created INTEGER NOT NULL
+        );
+        CREATE INDEX journal_dishes_journal_id ON journal_dishes (journal_id);
+        ",
+    )?;
+    Ok(())
+}
+
+/// Version 5: a dish may have a cooldown, the number of days after one of its records during
+/// which it is not suggested. `NULL` means no cooldown. The journal keeps the cooldown of the
+/// dishes it created or deleted.
+fn add_cooldown_column(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute(
+        "
+        ALTER TABLE dishes ADD COLUMN cooldown INTEGER;
+        ALTER TABLE journal_dishes ADD COLUMN cooldown INTEGER;
+        ",
+    )?;
+    Ok(())
+}
+
+/// Version 6: each occurrence has a status, `planned`, `eaten` or `skipped`. Occurrences on days
+/// after the day of the moment *now* in the timezone of the data are planned, the others eaten,
+/// like new records. The journal keeps the status of the records, so that undoing a removal
+/// restores it.
+fn add_status_columns(connection: &Connection, now: DateTime<Utc>) -> Result<()> {
+    let mut statement = connection.prepare("SELECT value FROM settings WHERE key = 'timezone'")?;
+    let timezone = match statement.next()? {
+        State::Row => Timezone::from_str(&statement.read::<String, _>("value")?)?,
+        State::Done => Timezone::default(),
+    };
+    let today = timezone.date_of(now.timestamp())?;
+    let start_of_tomorrow = today
+        .succ_opt()
+        .map_or(i64::MAX, |tomorrow| timezone.start_of_day(&tomorrow));
+    connection.execute(
+        "
+        ALTER TABLE occurrences ADD COLUMN status TEXT NOT NULL DEFAULT 'eaten';
+        ALTER TABLE journal_records ADD COLUMN status TEXT NOT NULL DEFAULT 'eaten';
+        DROP VIEW records;
+        CREATE VIEW records AS
+            SELECT occurrences.id AS id, occurrences.date AS date,
+                occurrences.status AS status, dishes.id AS dish_id, dishes.name AS meal
+            FROM occurrences JOIN dishes ON dishes.id = occurrences.dish_id;
+        ",
+    )?;
+    // occurrences are kept at the start of their day, so those at or after the start of tomorrow
+    // lie on a day after today
+    let mut statement =
+        connection.prepare("UPDATE occurrences SET status = 'planned' WHERE date >= :tomorrow")?;
+    statement.bind((":tomorrow", start_of_tomorrow))?;
+    statement.next()?;
+    Ok(())
+}
+
+/// Version 7: a dish may have any number of tags, e.g. `vegetarian` or `fish`. The journal keeps
+/// the tags of the dishes it created or deleted.
+fn create_dish_tags_table(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute(
+        "
+        CREATE TABLE dish_tags (
+            dish_id INTEGER NOT NULL REFERENCES dishes (id),
+            tag TEXT NOT NULL,
+            PRIMARY KEY (dish_id, tag)
+        );
+        CREATE TABLE journal_dish_tags (
+            journal_dish_id INTEGER NOT NULL REFERENCES journal_dishes (id),
+            tag TEXT NOT NULL
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// Version 8: a dish may be known by any number of aliases, e.g. `spag bol` for `spaghetti
+/// bolognese`. An alias belongs to one dish only. The journal keeps the aliases of the dishes it
+/// created or deleted.
+fn create_dish_aliases_table(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute(
+        "
+        CREATE TABLE dish_aliases (
+            alias TEXT PRIMARY KEY,
+            dish_id INTEGER NOT NULL REFERENCES dishes (id)
+        );
+        CREATE TABLE journal_dish_aliases (
+            journal_dish_id INTEGER NOT NULL REFERENCES journal_dishes (id),
+            alias TEXT NOT NULL
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// Version 9: names are matched by their [normalized](normalize_name) spelling, so dishes whose
+/// names only differ in letter case, Unicode composition or whitespace are the same meal. Each
+/// such dish is merged into the one recorded first: its records, tags and aliases move over, the
+/// earlier creation date is kept, and so is the cooldown unless only the merged dish has one. The
+/// journal refers to the remaining dish instead.
+fn merge_alike_dishes(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    let mut first_dishes: HashMap<String, (i64, String)> = HashMap::new();
+    let mut merges: Vec<((i64, String), (i64, String))> = Vec::new();
+    let mut statement = connection.prepare("SELECT id, name FROM dishes ORDER BY id")?;
+    while let State::Row = statement.next()? {
+        let dish = (
+            statement.read::<i64, _>("id")?,
+            statement.read::<String, _>("name")?,
+        );
+        match first_dishes.get(&normalize_name(&dish.1)) {
+            Some(first_dish) => merges.push((dish, first_dish.clone())),
+            None => {
+                first_dishes.insert(normalize_name(&dish.1), dish);
+            }
+        }
+    }
+    for ((from_id, from_name), (to_id, to_name)) in merges {
+        trace!(%from_name, %to_name, "Merge dish");
+        for query in [
+            "UPDATE occurrences SET dish_id = :to_id WHERE dish_id = :from_id",
+            "UPDATE dishes SET
+                created = MIN(created, (SELECT created FROM dishes WHERE id = :from_id)),
+                cooldown = COALESCE(cooldown, (SELECT cooldown FROM dishes WHERE id = :from_id))
+                WHERE id = :to_id",
+            "INSERT OR IGNORE INTO dish_tags (dish_id, tag)
+                SELECT :to_id, tag FROM dish_tags WHERE dish_id = :from_id",
+            "UPDATE dish_aliases SET dish_id = :to_id WHERE dish_id = :from_id",
+        ] {
+            let mut statement = connection.prepare(query)?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":to_id", to_id.into()),
+                (":from_id", from_id.into()),
+            ])?;
+            statement.next()?;
+        }
+        for query in [
+            "DELETE FROM dish_tags WHERE dish_id = :from_id",
+            "DELETE FROM dishes WHERE id = :from_id",
+        ] {
+            let mut statement = connection.prepare(query)?;
+            statement.bind((":from_id", from_id))?;
+            statement.next()?;
+        }
+        merge_journal_meal(connection, &from_name, &to_name)?;
+    }
+    Ok(())
+}
+
+/// Version 10: changes of the status of records are journaled, too. A journal record keeps the
+/// status it was changed to next to the status it had before; the column is empty for the records
+/// of other operations.
+fn add_new_status_column(connection: &Connection, _now: DateTime<Utc>) -> Result<()> {
+    connection.execute("ALTER TABLE journal_records ADD COLUMN new_status TEXT")?;
+    Ok(())
+}
+
+/// Gives the records of the meal *from* in the journal to the meal *to*, after *from* was merged
+/// into *to*. As *from* is gone, the journal neither creates nor deletes it anymore. Shared by the
+/// [merge of alike dishes](merge_alike_dishes) and the SQLite store's merge of meals; must be
+/// called within a transaction.
+pub(crate) fn merge_journal_meal(connection: &Connection, from: &str, to: &str) -> Result<()> {
+    for query in [
+        "UPDATE journal_records SET meal = :to WHERE meal = :from",
+        "UPDATE journal SET old_name = :to WHERE old_name = :from",
+        "UPDATE journal SET new_name = :to WHERE new_name = :from",
+    ] {
+        let mut statement = connection.prepare(query)?;
+        statement.bind_iter::<_, (_, Value)>([(":to", to.into()), (":from", from.into())])?;
+        statement.next()?;
+    }
+    let condition = "journal_dish_id IN (SELECT id FROM journal_dishes WHERE name = :from)";
+    for query in [
+        format!("DELETE FROM journal_dish_tags WHERE {condition}"),
+        format!("DELETE FROM journal_dish_aliases WHERE {condition}"),
+        String::from("DELETE FROM journal_dishes WHERE name = :from"),
+    ] {
+        let mut statement = connection.prepare(query)?;
+        statement.bind((":from", from))?;
+        statement.next()?;
+    }
+    Ok(())
+}
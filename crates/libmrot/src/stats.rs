@@ -0,0 +1,177 @@
+//! Statistics of the eating habits
+
+use crate::{normalize_name, MealRecord, MealStatistics};
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashMap;
+
+/// The longest run of eaten meals in which no meal was repeated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Streak {
+    meals: usize,
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+}
+
+impl Streak {
+    /// Get the number of meals eaten in the streak.
+    pub fn meals(&self) -> usize {
+        self.meals
+    }
+
+    /// Get the date of the first meal of the streak.
+    pub fn first_date(&self) -> NaiveDate {
+        self.first_date
+    }
+
+    /// Get the date of the last meal of the streak.
+    pub fn last_date(&self) -> NaiveDate {
+        self.last_date
+    }
+}
+
+/// Statistics of the eaten meals, as returned by [`Storage::statistics`](crate::Storage::statistics).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    records: usize,
+    meals: Vec<MealStatistics>,
+    longest_streak: Option<Streak>,
+    busiest_weekday: Option<(Weekday, usize)>,
+}
+
+impl Statistics {
+    /// Computes the statistics of the eaten *records*. Meals whose names are spelled differently
+    /// but [normalize](crate::normalize_name) to the same name are counted as one meal.
+    pub(crate) fn from_records(records: &[MealRecord], today: NaiveDate) -> Self {
+        let mut records = records.to_vec();
+        records.sort_by(|a, b| {
+            a.timestamp()
+                .cmp(&b.timestamp())
+                .then_with(|| a.meal().cmp(&b.meal()))
+        });
+        let mut records_by_meal: HashMap<String, Vec<&MealRecord>> = HashMap::new();
+        for record in records.iter() {
+            records_by_meal
+                .entry(normalize_name(&record.meal()))
+                .or_default()
+                .push(record);
+        }
+        let mut meals: Vec<MealStatistics> = records_by_meal
+            .into_values()
+            .map(|meal_records| {
+                let dates: Vec<NaiveDate> = meal_records
+                    .iter()
+                    .map(|record| record.naive_date())
+                    .collect();
+                let last_record = (*meal_records.last().unwrap()).clone();
+                MealStatistics::from_dates(last_record, &dates, today)
+            })
+            .collect();
+        meals.sort_by(|a, b| {
+            b.count()
+                .cmp(&a.count())
+                .then_with(|| a.meal().cmp(&b.meal()))
+        });
+        Statistics {
+            records: records.len(),
+            meals,
+            longest_streak: Self::find_longest_streak(&records),
+            busiest_weekday: Self::find_busiest_weekday(&records),
+        }
+    }
+
+    /// Finds the longest run of the date-ordered *records* without a repeated meal. Of two runs of
+    /// the same length, the earlier one is returned.
+    fn find_longest_streak(records: &[MealRecord]) -> Option<Streak> {
+        let mut last_positions: HashMap<String, usize> = HashMap::new();
+        let mut start = 0;
+        let mut longest: Option<(usize, usize)> = None;
+        for (position, record) in records.iter().enumerate() {
+            if let Some(last_position) =
+                last_positions.insert(normalize_name(&record.meal()), position)
+            {
+                start = start.max(last_position + 1);
+            }
+            if longest.is_none_or(|(first, last)| position - start > last - first) {
+                longest = Some((start, position));
+            }
+        }
+        longest.map(|(first, last)| Streak {
+            meals: last - first + 1,
+            first_date: records[first].naive_date(),
+            last_date: records[last].naive_date(),
+        })
+    }
+
+    /// Finds the weekday with the most *records*. Of two weekdays with as many records, the one
+    /// earlier in the week is returned.
+    fn find_busiest_weekday(records: &[MealRecord]) -> Option<(Weekday, usize)> {
+        let mut counts = [0; 7];
+        for record in records {
+            counts[record.naive_date().weekday().num_days_from_monday() as usize] += 1;
+        }
+        let mut busiest: Option<(Weekday, usize)> = None;
+        for (days_from_monday, &count) in counts.iter().enumerate() {
+            if count > busiest.map_or(0, |(_, most)| most) {
+                busiest = Some((Weekday::try_from(days_from_monday as u8).unwrap(), count));
+            }
+        }
+        busiest
+    }
+
+    /// Get the number of eaten records.
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Get the number of distinct eaten meals.
+    pub fn distinct_meals(&self) -> usize {
+        self.meals.len()
+    }
+
+    /// Get the statistics of each eaten meal, the most frequent meals first. Meals eaten as often
+    /// are sorted by name.
+    pub fn meals(&self) -> &[MealStatistics] {
+        &self.meals
+    }
+
+    /// Get the statistics of the *number* most frequent meals.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("March 1, 2025")]).unwrap();
+    /// storage.add_meal_on_dates(
+    ///     "curry",
+    ///     &vec![String::from("March 2, 2025"), String::from("March 3, 2025")],
+    ///     ).unwrap();
+    ///
+    /// let statistics = storage.statistics(None).unwrap();
+    /// let most_frequent: Vec<String> = statistics
+    ///     .most_frequent(1)
+    ///     .iter()
+    ///     .map(|meal| meal.meal())
+    ///     .collect();
+    /// assert_eq!(most_frequent, vec![String::from("curry")]);
+    /// ```
+    pub fn most_frequent(&self, number: usize) -> &[MealStatistics] {
+        &self.meals[..number.min(self.meals.len())]
+    }
+
+    /// Get the statistics of the meals which were eaten only once, sorted by name.
+    pub fn eaten_once(&self) -> Vec<&MealStatistics> {
+        self.meals.iter().filter(|meal| meal.count() == 1).collect()
+    }
+
+    /// Get the longest run of eaten meals in which no meal was repeated, if any meal was eaten.
+    pub fn longest_streak(&self) -> Option<&Streak> {
+        self.longest_streak.as_ref()
+    }
+
+    /// Get the weekday on which the most meals were eaten, with the number of meals eaten on it,
+    /// if any meal was eaten.
+    pub fn busiest_weekday(&self) -> Option<(Weekday, usize)> {
+        self.busiest_weekday
+    }
+}
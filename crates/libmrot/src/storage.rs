@@ -1,26 +1,81 @@
 //! Storage for meal records
 
-use crate::{convert::convert_to_timestamps, error::Error, MealRecord, Period, Result};
-use chrono::naive::NaiveDate;
+use crate::{
+    convert::convert_to_timestamps_in, ignored_tag, meal::edit_distance, normalize_name,
+    store::RecordFilter, Clock, Diversity, Error, ExchangeFormat, ImportReport, JournalEntry, Meal,
+    MealRecord, MealStatistics, MealStore, MemoryStore, OldestFirst, Period, Result, SqliteStore,
+    Statistics, Status, SuggestionStrategy, SystemClock, TagFilter, Timezone, WeightedRandom,
+};
+use chrono::{
+    naive::{NaiveDate, NaiveDateTime},
+    DateTime, Utc,
+};
 use rand::seq::IteratorRandom;
-use sqlite::{Connection, State, Value};
-use std::{cmp::min, fmt, path::Path};
-use tracing::{instrument, trace};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{Read, Write},
+};
+use tracing::instrument;
+
+/// The tags of each meal, by the name of the meal.
+type TagsByMeal = HashMap<String, Vec<String>>;
+
+/// The cooldown in days of each meal which has one and the dates of its records, by the name of
+/// the meal.
+type CooldownsByMeal = HashMap<String, (u32, Vec<NaiveDate>)>;
+
+/// The names of the known meals, by which [`Storage::resolve`] finds the canonical name of a meal.
+struct CanonicalNames {
+    /// The names of the meals by their normalized aliases.
+    aliases: HashMap<String, String>,
+    /// The names of the meals as they are spelled.
+    names: HashSet<String>,
+    /// The names of the meals by their normalized names. Of two meals with the same normalized
+    /// name, the older one is kept.
+    normalized_names: HashMap<String, String>,
+}
+
+impl CanonicalNames {
+    /// Returns the name of the meal known by the alias, by exactly the name or by the normalized
+    /// name, in this order of precedence, or the name itself if no meal is known by it.
+    fn resolve(&self, name: &str) -> String {
+        let normalized_name = normalize_name(name);
+        if let Some(owner) = self.aliases.get(&normalized_name) {
+            return owner.clone();
+        }
+        if self.names.contains(name) {
+            return name.to_string();
+        }
+        self.normalized_names
+            .get(&normalized_name)
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Adds the name of a new meal.
+    fn insert(&mut self, name: &str) {
+        self.names.insert(name.to_string());
+        self.normalized_names
+            .entry(normalize_name(name))
+            .or_insert(name.to_string());
+    }
+}
 
 /// Storage for meal records.
-pub struct Storage {
-    connection: Connection,
-    path_string: String,
+///
+/// The records are kept in a [`MealStore`]. By default this is an SQLite database, see
+/// [`Storage::open`]. Relative date expressions are resolved against the storage's [`Clock`], in
+/// the storage's [`Timezone`].
+pub struct Storage<S = SqliteStore> {
+    store: S,
+    clock: Box<dyn Clock>,
 }
 
 impl Storage {
-    /// Query to create all of the databases tables.
-    const QUERY_TO_CREATE_SQL_STORAGE_TABLES: &str = "CREATE TABLE meals (date INTEGER, meal TEXT)";
-    /// Path to an in-memory storage. Useful for testing.
-    const MEMORY: &str = ":memory:";
-
     /// Opens a storage in the given path. Given a path to a non-existing file, this will try to
-    /// create a new storage in that path and then open it.
+    /// create a new storage in that path and then open it. The schema of an existing storage is
+    /// migrated to the current [`SCHEMA_VERSION`](crate::SCHEMA_VERSION).
     ///
     /// Example:
     /// ```no_run
@@ -29,44 +84,188 @@ impl Storage {
     /// let storage = Storage::open("./path/to/my_storage").unwrap();
     /// ```
     ///
-    /// For testing purposes the special path `:memory:` gives access to an in-memory storage which will
-    /// live as long as the returned struct.
+    /// Error:
+    ///
+    /// Returns [`Error::SchemaTooNew`](crate::Error::SchemaTooNew) if the storage was created by a
+    /// newer version of libmrot.
+    #[instrument]
+    pub fn open(path: &str) -> Result<Self> {
+        Ok(Self::with_store(SqliteStore::open(path)?))
+    }
+
+    /// Like [`Storage::open`], but the schema of an existing storage is migrated as of the moment
+    /// *now* instead of the current time, e.g. the moment of the storage's [`Clock`].
     ///
     /// Example:
+    /// ```no_run
+    /// use libmrot::{FixedClock, Storage};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let now = Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap();
+    /// let storage = Storage::open_at("./path/to/my_storage", now)
+    ///     .unwrap()
+    ///     .with_clock(FixedClock::new(now));
     /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::SchemaTooNew`](crate::Error::SchemaTooNew) if the storage was created by a
+    /// newer version of libmrot.
+    #[instrument]
+    pub fn open_at(path: &str, now: DateTime<Utc>) -> Result<Self> {
+        Ok(Self::with_store(SqliteStore::open_at(path, now)?))
+    }
+
+    /// Opens the existing storage in the given path without creating, migrating or otherwise
+    /// changing it. Every change to the returned storage fails.
+    ///
+    /// Example:
+    /// ```no_run
     /// use libmrot::Storage;
     ///
-    /// let test_storage = Storage::open(":memory:").unwrap();
+    /// let storage = Storage::open_read_only("./path/to/my_storage").unwrap();
     /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::SchemaOutdated`](crate::Error::SchemaOutdated) if the storage must be
+    /// migrated first and [`Error::SchemaTooNew`](crate::Error::SchemaTooNew) if it was created by
+    /// a newer version of libmrot.
     #[instrument]
-    pub fn open(path: &str) -> Result<Self> {
-        trace!(%path, "Open database");
-        let connection: Connection = match path {
-            Self::MEMORY => Self::new(&path)?,
-            _ => {
-                trace!(%path, "Path is a real file");
-                if Path::new(path).try_exists()? {
-                    sqlite::open(path)?
-                } else {
-                    Self::new(path)?
-                }
-            }
-        };
-        let path_string = String::from(path);
-        Ok(Self {
-            connection,
-            path_string,
-        })
+    pub fn open_read_only(path: &str) -> Result<Self> {
+        Ok(Self::with_store(SqliteStore::open_read_only(path)?))
+    }
+
+    /// Returns the schema version of the storage.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{SqliteStore, Storage, SCHEMA_VERSION};
+    ///
+    /// let storage = Storage::with_store(SqliteStore::open(":memory:").unwrap());
+    /// assert_eq!(storage.schema_version().unwrap(), SCHEMA_VERSION);
+    /// ```
+    #[instrument]
+    pub fn schema_version(&self) -> Result<i64> {
+        self.store.schema_version()
+    }
+}
+
+impl Storage<MemoryStore> {
+    /// Creates an empty storage which keeps its records in memory for as long as it lives.
+    /// Useful for testing.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let test_storage = Storage::in_memory();
+    /// ```
+    pub fn in_memory() -> Self {
+        Self::with_store(MemoryStore::default())
+    }
+}
+
+impl<S: MealStore> Storage<S> {
+    /// Creates a storage which keeps its records in the given [`MealStore`]. The storage uses the
+    /// [`SystemClock`].
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    /// Replaces the storage's [`Clock`].
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Storage};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// // pretend that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// assert_eq!(storage.today(), NaiveDate::from_ymd_opt(2025, 4, 15).unwrap());
+    /// ```
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Returns the [`MealStore`] of the storage.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the timezone in which the days of the storage begin and end. Storages created
+    /// before the timezone was configurable use UTC.
+    pub fn timezone(&self) -> Timezone {
+        self.store.timezone()
     }
 
-    /// Creates a new storage.
+    /// Changes the timezone in which the days of the storage begin and end. The records keep their
+    /// dates.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Storage, Timezone};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// // it is 22:00 UTC on April 15th, 2025, which is already April 16th in Sydney
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 22, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("April 1, 2025")]).unwrap();
+    /// assert_eq!(storage.today(), NaiveDate::from_ymd_opt(2025, 4, 15).unwrap());
+    ///
+    /// storage.set_timezone("Australia/Sydney".parse().unwrap()).unwrap();
+    /// assert_eq!(storage.today(), NaiveDate::from_ymd_opt(2025, 4, 16).unwrap());
+    /// assert_eq!(
+    ///     storage.when("pizza").unwrap(),
+    ///     vec![NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()],
+    /// );
+    /// ```
     #[instrument]
-    fn new(path: &str) -> Result<Connection> {
-        trace!(%path, "Creating new database");
-        std::fs::create_dir_all(Path::new(path).parent().ok_or(Error::NoParentDirectory)?)?;
-        let connection = sqlite::open(path)?;
-        connection.execute(Self::QUERY_TO_CREATE_SQL_STORAGE_TABLES)?;
-        Ok(connection)
+    pub fn set_timezone(&self, timezone: Timezone) -> Result<()> {
+        self.store.set_timezone(timezone)
+    }
+
+    /// Returns the current moment according to the storage's [`Clock`], as wall-clock time in
+    /// the storage's [`Timezone`].
+    pub fn now(&self) -> NaiveDateTime {
+        self.timezone().naive_local(self.clock.now())
+    }
+
+    /// Returns the current date according to the storage's [`Clock`].
+    pub fn today(&self) -> NaiveDate {
+        self.now().date()
+    }
+
+    /// Constructs a [`Period`] from a date expression, resolving relative dates against the
+    /// storage's [`Clock`] (cf. [`Period::new_in`]).
+    pub fn period(&self, date_expression: &str) -> Result<Period> {
+        Period::new_in(date_expression, self.now(), &self.timezone())
+    }
+
+    /// Constructs the [`Period`] of the days from *first_date* through *last_date* in the
+    /// storage's [`Timezone`].
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    /// use chrono::NaiveDate;
+    ///
+    /// let storage = Storage::in_memory();
+    /// let first_date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+    /// let last_date = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+    ///
+    /// let period = storage.period_between(first_date, last_date);
+    /// assert_eq!(period.first_date(), first_date);
+    /// assert_eq!(period.last_date(), last_date);
+    /// ```
+    pub fn period_between(&self, first_date: NaiveDate, last_date: NaiveDate) -> Period {
+        Period::between(first_date, last_date, &self.timezone())
     }
 
     /// Adds a meal on the given dates to the storage.
@@ -76,7 +275,7 @@ impl Storage {
     /// use libmrot::Storage;
     ///
     /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// let storage = Storage::in_memory();
     ///
     /// // prepare dates where each meal was consumed
     /// let bolognese_dates = vec![String::from("from yesterday through today")];
@@ -91,38 +290,425 @@ impl Storage {
     /// ```
     #[instrument]
     pub fn add_meal_on_dates(&self, meal: &str, dates: &Vec<String>) -> Result<()> {
-        let converted_dates = convert_to_timestamps(dates)?;
+        let converted_dates = convert_to_timestamps_in(dates, self.now(), &self.timezone())?;
+        self.store
+            .add(&self.resolve(meal)?, &converted_dates, self.clock.now())
+    }
+
+    /// Adds a meal on every day of the period to the storage.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Storage};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+    /// storage.add_meal_in_period("spaghetti", &storage.period_between(date, date)).unwrap();
+    ///
+    /// assert_eq!(storage.when("spaghetti").unwrap(), vec![date]);
+    /// ```
+    #[instrument]
+    pub fn add_meal_in_period(&self, meal: &str, period: &Period) -> Result<()> {
+        let timezone = self.timezone();
+        let timestamps: Vec<i64> = period
+            .first_date()
+            .iter_days()
+            .take_while(|date| *date <= period.last_date())
+            .map(|date| timezone.start_of_day(&date))
+            .collect();
+        self.store
+            .add(&self.resolve(meal)?, &timestamps, self.clock.now())
+    }
 
-        self.connection.execute("BEGIN TRANSACTION")?;
-        let query = "INSERT INTO meals VALUES (:date, :meal)";
-        let mut statement = self.connection.prepare(query)?;
+    /// Lists all meals known to the storage, ordered by their ids.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// // open in-memory storage
+    /// let storage = Storage::in_memory();
+    ///
+    /// // fill storage with some data
+    /// storage.add_meal_on_dates("spaghetti", &vec![String::from("today")]).unwrap();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("yesterday")]).unwrap();
+    ///
+    /// let names: Vec<String> = storage.meals().unwrap().iter().map(|m| m.name()).collect();
+    /// assert_eq!(names, vec!["spaghetti", "curry"]);
+    /// ```
+    #[instrument]
+    pub fn meals(&self) -> Result<Vec<Meal>> {
+        self.store.meals()
+    }
 
-        for date in converted_dates {
-            statement.reset()?;
-            statement
-                .bind_iter::<_, (_, Value)>([(":date", date.into()), (":meal", meal.into())])?;
+    /// Looks up a meal by its name or one of its [aliases](Storage::add_aliases). Returns `None`
+    /// if the storage does not know the meal.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// // open in-memory storage
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("spaghetti", &vec![String::from("today")]).unwrap();
+    ///
+    /// let spaghetti = storage.meal("spaghetti").unwrap().unwrap();
+    ///
+    /// // the id survives renaming the meal
+    /// storage.rename("spaghetti", "penne", None).unwrap();
+    /// let penne = storage.meal("penne").unwrap().unwrap();
+    /// assert_eq!(spaghetti.id(), penne.id());
+    /// assert_eq!(storage.meal("spaghetti").unwrap(), None);
+    /// ```
+    #[instrument]
+    pub fn meal(&self, name: &str) -> Result<Option<Meal>> {
+        self.store.meal(&self.resolve(name)?)
+    }
 
-            while let State::Row = statement.next()? {}
+    /// Returns the canonical name of the meal which is known by *name*, or *name* itself if the
+    /// storage knows no such meal. Names are matched by their [normalized](crate::normalize_name)
+    /// form, so the spelling of the meal's name or alias does not matter. An alias comes first,
+    /// then a meal whose name is spelled exactly like *name*.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("spaghetti bolognese", &vec![String::from("today")]).unwrap();
+    /// storage.add_aliases("spaghetti bolognese", &[String::from("spag bol")]).unwrap();
+    ///
+    /// assert_eq!(storage.resolve("spag bol").unwrap(), "spaghetti bolognese");
+    /// assert_eq!(storage.resolve("Spag  Bol").unwrap(), "spaghetti bolognese");
+    /// assert_eq!(storage.resolve("Spaghetti Bolognese").unwrap(), "spaghetti bolognese");
+    /// assert_eq!(storage.resolve("Pizza").unwrap(), "Pizza");
+    /// ```
+    #[instrument]
+    pub fn resolve(&self, name: &str) -> Result<String> {
+        Ok(self.get_canonical_names()?.resolve(name))
+    }
+
+    /// Resolves the alias of the optional meal, see [`resolve`](Storage::resolve).
+    fn resolve_option(&self, option_meal: Option<String>) -> Result<Option<String>> {
+        option_meal.map(|meal| self.resolve(&meal)).transpose()
+    }
+
+    /// Returns the canonical meal names of the known meals.
+    #[instrument(level = "trace")]
+    fn get_canonical_names(&self) -> Result<CanonicalNames> {
+        let mut canonical_names = CanonicalNames {
+            aliases: HashMap::new(),
+            names: HashSet::new(),
+            normalized_names: HashMap::new(),
+        };
+        for (name, aliases) in self.store.meal_names()? {
+            for alias in aliases {
+                canonical_names
+                    .aliases
+                    .insert(normalize_name(&alias), name.clone());
+            }
+            canonical_names.insert(&name);
         }
+        Ok(canonical_names)
+    }
+
+    /// Returns the names of the meals by their normalized aliases.
+    fn owners_by_alias(meals: &[Meal]) -> HashMap<String, String> {
+        meals
+            .iter()
+            .flat_map(|meal| {
+                meal.aliases()
+                    .into_iter()
+                    .map(move |alias| (normalize_name(&alias), meal.name()))
+            })
+            .collect()
+    }
 
-        self.connection.execute("COMMIT")?;
+    /// Adds the *aliases* to a meal, so that each of them can be used in place of the meal's
+    /// name, e.g. to add, remove, rename or ignore the meal. Leading and trailing whitespace is
+    /// removed from the aliases. If the storage knows a meal by the name of an alias, its records,
+    /// tags and aliases are merged into the meal, so that suggestions count them as one. The
+    /// merge cannot be undone, but undoing an earlier operation on a merged meal changes the
+    /// records of the meal. Aliases and names are compared in their
+    /// [normalized](crate::normalize_name) form.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("spaghetti bolognese", &vec![String::from("today")]).unwrap();
+    /// storage.add_meal_on_dates("spag bol", &vec![String::from("yesterday")]).unwrap();
+    ///
+    /// let aliases = vec![String::from("spag bol"), String::from(" bolognese ")];
+    /// storage.add_aliases("spaghetti bolognese", &aliases).unwrap();
+    ///
+    /// // the records of spag bol now belong to spaghetti bolognese
+    /// assert_eq!(storage.meal("spag bol").unwrap(), storage.meal("bolognese").unwrap());
+    /// assert_eq!(storage.when("bolognese").unwrap().len(), 2);
+    /// let meal = storage.meal("spaghetti bolognese").unwrap().unwrap();
+    /// assert_eq!(meal.aliases(), vec!["bolognese", "spag bol"]);
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the storage does not know the meal, if an alias is empty or the name of
+    /// the meal itself, or if an alias already belongs to another meal.
+    /// ```
+    /// use libmrot::{Error, Storage};
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("spaghetti bolognese", &vec![String::from("today")]).unwrap();
+    /// storage.add_meal_on_dates("lasagne", &vec![String::from("today")]).unwrap();
+    /// storage.add_aliases("lasagne", &[String::from("bolognese")]).unwrap();
+    ///
+    /// let meal = "spaghetti bolognese";
+    /// let error_result = storage.add_aliases(meal, &[String::from(" ")]).unwrap_err();
+    /// assert!(matches!(error_result, Error::InvalidAlias(_)));
+    /// let error_result = storage.add_aliases(meal, &[String::from(meal)]).unwrap_err();
+    /// assert!(matches!(error_result, Error::InvalidAlias(_)));
+    /// let error_result = storage.add_aliases(meal, &[String::from("bolognese")]).unwrap_err();
+    /// assert!(matches!(error_result, Error::AliasTaken(_)));
+    /// let error_result = storage.add_aliases("ragù", &[String::from("ragu")]).unwrap_err();
+    /// assert!(matches!(error_result, Error::UnknownMeal(_)));
+    /// ```
+    #[instrument]
+    pub fn add_aliases(&self, meal: &str, aliases: &[String]) -> Result<()> {
+        let known_meal = self.known_meal(meal)?;
+        let meals = self.store.meals()?;
+        let owners_by_alias = Self::owners_by_alias(&meals);
+        let mut new_aliases = known_meal.aliases();
+        let mut normalized_aliases: HashSet<String> = new_aliases
+            .iter()
+            .map(|alias| normalize_name(alias))
+            .collect();
+        for alias in aliases {
+            let trimmed_alias = alias.trim();
+            let normalized_alias = normalize_name(trimmed_alias);
+            if normalized_alias.is_empty() || normalized_alias == normalize_name(&known_meal.name())
+            {
+                return Err(Error::InvalidAlias(alias.to_string()));
+            }
+            match owners_by_alias.get(&normalized_alias) {
+                Some(owner) if *owner != known_meal.name() => {
+                    return Err(Error::AliasTaken(trimmed_alias.to_string()));
+                }
+                _ => {
+                    if normalized_aliases.insert(normalized_alias) {
+                        new_aliases.push(trimmed_alias.to_string());
+                    }
+                }
+            }
+        }
+        let aliased_meals: Vec<Meal> = meals
+            .into_iter()
+            .filter(|other_meal| {
+                other_meal.id() != known_meal.id()
+                    && normalized_aliases.contains(&normalize_name(&other_meal.name()))
+            })
+            .collect();
+        let mut tags = known_meal.tags();
+        for aliased_meal in aliased_meals.iter() {
+            new_aliases.extend(aliased_meal.aliases());
+            tags.extend(aliased_meal.tags());
+        }
+        let merged: Vec<String> = aliased_meals.iter().map(|meal| meal.name()).collect();
+        self.store
+            .merge(&known_meal.name(), &merged, &tags, &new_aliases)
+    }
+
+    /// Removes the *aliases* from the meals which they belong to.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("spaghetti bolognese", &vec![String::from("today")]).unwrap();
+    /// storage.add_aliases("spaghetti bolognese", &[String::from("spag bol")]).unwrap();
+    ///
+    /// storage.remove_aliases(&[String::from("spag bol")]).unwrap();
+    ///
+    /// assert_eq!(storage.meal("spag bol").unwrap(), None);
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::UnknownAlias`] if an alias does not belong to any meal. No alias is
+    /// removed then.
+    #[instrument]
+    pub fn remove_aliases(&self, aliases: &[String]) -> Result<()> {
+        let owners_by_alias = self.get_canonical_names()?.aliases;
+        let mut aliases_by_meal: HashMap<&str, HashSet<String>> = HashMap::new();
+        for alias in aliases {
+            let normalized_alias = normalize_name(alias);
+            let Some(meal) = owners_by_alias.get(&normalized_alias) else {
+                return Err(Error::UnknownAlias(alias.to_string()));
+            };
+            aliases_by_meal
+                .entry(meal.as_str())
+                .or_default()
+                .insert(normalized_alias);
+        }
+        for (meal, removed_aliases) in aliases_by_meal {
+            let new_aliases: Vec<String> = self
+                .known_meal(meal)?
+                .aliases()
+                .into_iter()
+                .filter(|own_alias| !removed_aliases.contains(&normalize_name(own_alias)))
+                .collect();
+            self.store.set_aliases(meal, &new_aliases)?;
+        }
         Ok(())
     }
 
+    /// Sets the cooldown of a meal to the number of days, or clears it with `None`. A meal is not
+    /// suggested, nor picked at random, on the days closer to one of its records than its
+    /// cooldown.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    /// storage.add_meal_on_dates("raclette", &vec![String::from("March 1")]).unwrap();
+    /// storage.add_meal_on_dates("pasta", &vec![String::from("April 8")]).unwrap();
+    ///
+    /// // raclette is the oldest meal
+    /// let suggestions = storage.what(1, None, Vec::new(), &TagFilter::default()).unwrap();
+    /// assert_eq!(suggestions[0].meal(), "raclette");
+    ///
+    /// // but it should not come back within three months
+    /// storage.set_cooldown("raclette", Some(90)).unwrap();
+    /// assert_eq!(storage.meal("raclette").unwrap().unwrap().cooldown(), Some(90));
+    /// let suggestions = storage.what(1, None, Vec::new(), &TagFilter::default()).unwrap();
+    /// assert_eq!(suggestions[0].meal(), "pasta");
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the storage does not know the meal.
+    /// ```
+    /// use libmrot::{Error, Storage};
+    ///
+    /// let storage = Storage::in_memory();
+    /// let error_result = storage.set_cooldown("raclette", Some(90)).unwrap_err();
+    /// assert!(matches!(error_result, Error::UnknownMeal(_)));
+    /// ```
+    #[instrument]
+    pub fn set_cooldown(&self, meal: &str, option_days: Option<u32>) -> Result<()> {
+        self.store.set_cooldown(&self.resolve(meal)?, option_days)
+    }
+
+    /// Adds the *tags* to a meal, e.g. `vegetarian` or `fish`. Leading and trailing whitespace is
+    /// removed from the tags. Tags select the meals which are suggested or shown, see
+    /// [`TagFilter`], and tags in the ignore list of [`what`](Storage::what), given with the
+    /// [`TAG_PREFIX`](crate::TAG_PREFIX) as in `tag:fish`, ignore all meals which have them.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("ratatouille", &vec![String::from("today")]).unwrap();
+    ///
+    /// let tags = vec![String::from("vegetarian"), String::from(" french ")];
+    /// storage.add_tags("ratatouille", &tags).unwrap();
+    ///
+    /// let ratatouille = storage.meal("ratatouille").unwrap().unwrap();
+    /// assert_eq!(ratatouille.tags(), vec!["french", "vegetarian"]);
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the storage does not know the meal or if a tag is empty.
+    /// ```
+    /// use libmrot::{Error, Storage};
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("ratatouille", &vec![String::from("today")]).unwrap();
+    ///
+    /// let error_result = storage.add_tags("ratatouille", &[String::from(" ")]).unwrap_err();
+    /// assert!(matches!(error_result, Error::InvalidTag(_)));
+    /// let error_result = storage.add_tags("raclette", &[String::from("cheese")]).unwrap_err();
+    /// assert!(matches!(error_result, Error::UnknownMeal(_)));
+    /// ```
+    #[instrument]
+    pub fn add_tags(&self, meal: &str, tags: &[String]) -> Result<()> {
+        let known_meal = self.known_meal(meal)?;
+        let mut new_tags = known_meal.tags();
+        for tag in tags {
+            let trimmed_tag = tag.trim();
+            if trimmed_tag.is_empty() {
+                return Err(Error::InvalidTag(tag.to_string()));
+            }
+            new_tags.push(trimmed_tag.to_string());
+        }
+        self.store.set_tags(&known_meal.name(), &new_tags)
+    }
+
+    /// Removes the *tags* from a meal. Tags which the meal does not have are left alone.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("ratatouille", &vec![String::from("today")]).unwrap();
+    /// let tags = vec![String::from("vegetarian"), String::from("french")];
+    /// storage.add_tags("ratatouille", &tags).unwrap();
+    ///
+    /// storage.remove_tags("ratatouille", &[String::from("french")]).unwrap();
+    ///
+    /// let ratatouille = storage.meal("ratatouille").unwrap().unwrap();
+    /// assert_eq!(ratatouille.tags(), vec!["vegetarian"]);
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::UnknownMeal`] if the storage does not know the meal.
+    #[instrument]
+    pub fn remove_tags(&self, meal: &str, tags: &[String]) -> Result<()> {
+        let known_meal = self.known_meal(meal)?;
+        let new_tags: Vec<String> = known_meal
+            .tags()
+            .into_iter()
+            .filter(|own_tag| !tags.iter().any(|tag| tag.trim() == own_tag))
+            .collect();
+        self.store.set_tags(&known_meal.name(), &new_tags)
+    }
+
+    fn known_meal(&self, meal: &str) -> Result<Meal> {
+        self.meal(meal)?.ok_or(Error::UnknownMeal(meal.to_string()))
+    }
+
     /// Suggest meals to cook.
     /// Each suggested meal comes as a [`MealRecord`] with the date of date of its
     /// latest consumption.
     ///
-    /// Function takes two kinds of filters:
+    /// Function takes three kinds of filters:
     /// 1. `option_ignore_period`: optional [`Period`] to ignore the kinds of meals within it
-    /// 2. `ignore`: to ignore specific kinds of meals in general
+    /// 2. `ignore`: to ignore specific kinds of meals in general, or all meals with one of the
+    ///    [tags](Storage::add_tags) in it, given as e.g. `tag:fish`
+    /// 3. `tag_filter`: to suggest only meals with certain tags, or without them
+    ///
+    /// Meals in their [cooldown](Storage::set_cooldown) are not suggested either.
     ///
     /// Example:
     /// ```
-    /// use libmrot::{MealRecord, Period, Storage};
+    /// use libmrot::{FixedClock, MealRecord, Period, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
     ///
-    /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
     ///
     /// // fill storage with data
     /// storage.add_meal_on_dates(
@@ -144,22 +730,22 @@ impl Storage {
     ///
     /// // we are going to ignore the kinds of meals
     /// // which were or will be consumed on these dates:
-    /// let option_period: Option<Period> = Some(Period::new(
+    /// let option_period: Option<Period> = Some(storage.period(
     ///     "from March 10 through March 22"
     ///     ).unwrap());
     /// // we are also going to ignore spaghetti in general
     /// let ignore = vec![String::from("spaghetti")];
     ///
     /// // get meal suggestions
-    /// let suggestions: Vec<MealRecord> = storage.what(3, option_period, ignore).unwrap();
+    /// let suggestions: Vec<MealRecord> = storage.what(3, option_period, ignore, &TagFilter::default()).unwrap();
     ///
     /// // we expect the suggestions to contain the records of pizza, steak, lentils and wieners.
     /// // Meat balls were ignored because one of their dates is inside the ignore period
     /// // Spaghetti were ignored by our `ignore` vector,
     /// let expected_suggestions: Vec<MealRecord> = vec![
-    ///     MealRecord::new("pizza", "March 5").unwrap(),
-    ///     MealRecord::new("steak", "March 6").unwrap(),
-    ///     MealRecord::new("lentils and wieners", "March 9").unwrap(),
+    ///     MealRecord::new("pizza", "March 5, 2025").unwrap(),
+    ///     MealRecord::new("steak", "March 6, 2025").unwrap(),
+    ///     MealRecord::new("lentils and wieners", "March 9, 2025").unwrap(),
     /// ];
     ///
     /// assert_eq!(suggestions, expected_suggestions);
@@ -170,26 +756,176 @@ impl Storage {
         number: u64,
         option_ignore_period: Option<Period>,
         ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
+    ) -> Result<Vec<MealRecord>> {
+        self.suggest(
+            number,
+            option_ignore_period,
+            ignore_list,
+            tag_filter,
+            &Diversity::default(),
+            &OldestFirst,
+        )
+    }
+
+    /// Suggests up to *number* meals ranked by the *strategy*. Like with [`what`](Storage::what),
+    /// the meals in the *ignore_list*, the meals recorded in the *option_ignore_period* and the
+    /// meals not matching the *tag_filter* are not suggested. The ranked meals are thinned out by
+    /// the *diversity* rule. Returns the latest record of each suggested meal.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{Diversity, FixedClock, LeastFrequent, MealRecord, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// // fill storage with data
+    /// storage.add_meal_on_dates(
+    ///     "spaghetti",
+    ///     &vec![String::from("March 1"), String::from("March 8")],
+    ///     ).unwrap();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("March 5")]).unwrap();
+    ///
+    /// // suggest the meal with the fewest records
+    /// let suggestions = storage
+    ///     .suggest(1, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &LeastFrequent)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(suggestions, vec![MealRecord::new("pizza", "March 5, 2025").unwrap()]);
+    /// ```
+    #[instrument]
+    pub fn suggest(
+        &self,
+        number: u64,
+        option_ignore_period: Option<Period>,
+        ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
+        diversity: &Diversity,
+        strategy: &dyn SuggestionStrategy,
     ) -> Result<Vec<MealRecord>> {
-        let mut candidates = self.get_meal_candidates(option_ignore_period, ignore_list)?;
-        let suggestions = Self::pick_n_meal_records(number as usize, &mut candidates);
+        let suggestions = self
+            .suggest_statistics(
+                number,
+                option_ignore_period,
+                ignore_list,
+                tag_filter,
+                diversity,
+                strategy,
+            )?
+            .into_iter()
+            .map(|candidate| candidate.last_record().clone())
+            .collect();
         Ok(suggestions)
     }
 
+    /// Like [`suggest`](Storage::suggest), but returns the statistics of the suggested meals, e.g.
+    /// to show when each of them is [expected](MealStatistics::expected_next) again.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{Diversity, FixedClock, Overdue, Storage, TagFilter};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    /// let dates = ["2025-03-18", "2025-03-25", "2025-04-01"].map(String::from).to_vec();
+    /// storage.add_meal_on_dates("pizza", &dates).unwrap();
+    ///
+    /// let suggestions = storage
+    ///     .suggest_statistics(1, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &Overdue)
+    ///     .unwrap();
+    /// assert_eq!(suggestions[0].expected_next(), NaiveDate::from_ymd_opt(2025, 4, 8));
+    /// ```
+    #[instrument]
+    pub fn suggest_statistics(
+        &self,
+        number: u64,
+        option_ignore_period: Option<Period>,
+        ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
+        diversity: &Diversity,
+        strategy: &dyn SuggestionStrategy,
+    ) -> Result<Vec<MealStatistics>> {
+        let candidates =
+            self.get_candidate_statistics(option_ignore_period, ignore_list, tag_filter)?;
+        let suggestions = self
+            .diversify(strategy.rank(candidates, self.today()), diversity)?
+            .into_iter()
+            .take(number as usize)
+            .collect();
+        Ok(suggestions)
+    }
+
+    #[instrument(level = "debug")]
+    fn get_candidate_statistics(
+        &self,
+        option_period: Option<Period>,
+        ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
+    ) -> Result<Vec<MealStatistics>> {
+        let candidates = self.get_meal_candidates(option_period, ignore_list, tag_filter)?;
+        let mut dates: HashMap<String, Vec<NaiveDate>> = HashMap::new();
+        self.store
+            .select(&RecordFilter::default())?
+            .into_iter()
+            .filter(|record| record.status() != Status::Skipped)
+            .for_each(|record| {
+                dates
+                    .entry(normalize_name(&record.meal()))
+                    .or_default()
+                    .push(record.naive_date())
+            });
+        let today = self.today();
+        let statistics = candidates
+            .into_iter()
+            .map(|record| {
+                let meal_dates = dates
+                    .remove(&normalize_name(&record.meal()))
+                    .unwrap_or_default();
+                MealStatistics::from_dates(record, &meal_dates, today)
+            })
+            .collect();
+        Ok(statistics)
+    }
+
     #[instrument(level = "debug")]
     fn get_meal_candidates(
         &self,
         option_period: Option<Period>,
         ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
     ) -> Result<Vec<MealRecord>> {
-        let mut last_cooked_unique_meals = self.get_last_cooked_unique()?;
+        let mut last_cooked_unique_meals = self.get_last_eaten_unique()?;
+        self.filter_tagged(&mut last_cooked_unique_meals, tag_filter)?;
         let planned_meal_records = match option_period {
             None => Vec::new(),
             Some(period) => self.get_meal_records_in_period(period)?,
         };
-        let mut ignored_meals: Vec<_> = ignore_list
+        let canonical_names = self.get_canonical_names()?;
+        let ignored_tags: Vec<String> = ignore_list
+            .iter()
+            .filter_map(|entry| ignored_tag(entry))
+            .map(String::from)
+            .collect();
+        let mut ignored_meals: Vec<_> = self
+            .get_meals_tagged_with_any(&ignored_tags)?
             .into_iter()
-            .chain(planned_meal_records.into_iter().map(|record| record.meal()))
+            .chain(
+                ignore_list
+                    .iter()
+                    .filter(|entry| ignored_tag(entry).is_none())
+                    .map(|name| canonical_names.resolve(name)),
+            )
+            .chain(
+                planned_meal_records
+                    .into_iter()
+                    .filter(|record| record.status() != Status::Skipped)
+                    .map(|record| record.meal()),
+            )
+            .chain(self.get_meals_in_cooldown(self.today())?)
             .collect();
         ignored_meals.sort();
         ignored_meals.dedup();
@@ -197,25 +933,184 @@ impl Storage {
         Ok(last_cooked_unique_meals)
     }
 
+    /// Returns one record per meal like [`get_last_cooked_unique`](Storage::get_last_cooked_unique),
+    /// but skipped records only count for meals which have no other records.
+    #[instrument(level = "debug")]
+    fn get_last_eaten_unique(&self) -> Result<Vec<MealRecord>> {
+        let mut last_records: HashMap<String, MealRecord> = HashMap::new();
+        for record in self.store.select(&RecordFilter::default())? {
+            let key = normalize_name(&record.meal());
+            let replace = match last_records.get(&key) {
+                None => true,
+                Some(last) => {
+                    record.status() != Status::Skipped || last.status() == Status::Skipped
+                }
+            };
+            if replace {
+                last_records.insert(key, record);
+            }
+        }
+        let mut records: Vec<MealRecord> = last_records.into_values().collect();
+        records.sort_by(|a, b| {
+            a.timestamp()
+                .cmp(&b.timestamp())
+                .then_with(|| a.meal().cmp(&b.meal()))
+        });
+        Ok(records)
+    }
+
+    /// Leaves out the ranked candidates which the diversity rule does not allow.
+    #[instrument(level = "debug")]
+    fn diversify(
+        &self,
+        ranked: Vec<MealStatistics>,
+        diversity: &Diversity,
+    ) -> Result<Vec<MealStatistics>> {
+        if diversity.is_empty() {
+            return Ok(ranked);
+        }
+        let (tags, mut taken_tags) = self.get_diversity_tags(diversity)?;
+        let mut diverse = Vec::new();
+        for candidate in ranked {
+            if Self::has_taken_tag(&tags, &taken_tags, &candidate.meal()) {
+                continue;
+            }
+            if diversity.one_per_tag {
+                taken_tags.extend(tags.get(&candidate.meal()).cloned().unwrap_or_default());
+            }
+            diverse.push(candidate);
+        }
+        Ok(diverse)
+    }
+
+    /// Returns the tags of all meals and the tags which the diversity rule takes from the start,
+    /// i.e. the tags of the meals recorded in its look-back period. Skipped records do not count.
+    #[instrument(level = "trace")]
+    fn get_diversity_tags(&self, diversity: &Diversity) -> Result<(TagsByMeal, HashSet<String>)> {
+        let tags: TagsByMeal = self
+            .store
+            .meals()?
+            .into_iter()
+            .map(|meal| (meal.name(), meal.tags()))
+            .collect();
+        let mut taken_tags = HashSet::new();
+        if let Some(ref look_back) = diversity.look_back {
+            self.get_meal_records_in_period(look_back.clone())?
+                .iter()
+                .filter(|record| record.status() != Status::Skipped)
+                .filter_map(|record| tags.get(&record.meal()))
+                .for_each(|meal_tags| taken_tags.extend(meal_tags.iter().cloned()));
+        }
+        Ok((tags, taken_tags))
+    }
+
+    fn has_taken_tag(tags: &TagsByMeal, taken_tags: &HashSet<String>, meal: &str) -> bool {
+        tags.get(meal)
+            .is_some_and(|meal_tags| meal_tags.iter().any(|tag| taken_tags.contains(tag)))
+    }
+
+    /// Returns the names of the meals which have at least one of the tags.
+    #[instrument(level = "debug")]
+    fn get_meals_tagged_with_any(&self, tags: &[String]) -> Result<Vec<String>> {
+        let meals = self
+            .store
+            .meals()?
+            .into_iter()
+            .filter(|meal| tags.iter().any(|tag| meal.has_tag(tag)))
+            .map(|meal| meal.name())
+            .collect();
+        Ok(meals)
+    }
+
+    /// Keeps only the records of the meals matching the tag filter.
+    #[instrument(level = "trace")]
+    fn filter_tagged(&self, records: &mut Vec<MealRecord>, tag_filter: &TagFilter) -> Result<()> {
+        if tag_filter.is_empty() {
+            return Ok(());
+        }
+        let matching_meals: HashSet<String> = self
+            .store
+            .meals()?
+            .into_iter()
+            .filter(|meal| tag_filter.matches(meal))
+            .map(|meal| meal.name())
+            .collect();
+        records.retain(|record| matching_meals.contains(&record.meal()));
+        Ok(())
+    }
+
+    /// Returns the names of the meals with a record closer to the date than their cooldown.
+    /// Skipped records do not count.
+    #[instrument(level = "debug")]
+    fn get_meals_in_cooldown(&self, date: NaiveDate) -> Result<Vec<String>> {
+        Ok(Self::meals_in_cooldown(&self.get_cooldowns()?, date))
+    }
+
+    /// Returns the cooldowns of the meals which have one, with the dates of their records which
+    /// were not skipped.
+    #[instrument(level = "debug")]
+    fn get_cooldowns(&self) -> Result<CooldownsByMeal> {
+        let mut cooldowns: CooldownsByMeal = self
+            .store
+            .meals()?
+            .into_iter()
+            .filter_map(|meal| Some((meal.name(), (meal.cooldown()?, Vec::new()))))
+            .collect();
+        if cooldowns.is_empty() {
+            return Ok(cooldowns);
+        }
+        let filter = RecordFilter {
+            meal: None,
+            period: None,
+            status: None,
+        };
+        for record in self.store.select(&filter)? {
+            if record.status() == Status::Skipped {
+                continue;
+            }
+            if let Some((_, dates)) = cooldowns.get_mut(&record.meal()) {
+                dates.push(record.naive_date());
+            }
+        }
+        Ok(cooldowns)
+    }
+
+    /// Returns the names of the meals with a record closer to the date than their cooldown.
+    fn meals_in_cooldown(cooldowns: &CooldownsByMeal, date: NaiveDate) -> Vec<String> {
+        let mut meals: Vec<String> = cooldowns
+            .iter()
+            .filter(|(_, (cooldown, dates))| {
+                dates.iter().any(|recorded_date| {
+                    (date - *recorded_date).num_days().abs() < i64::from(*cooldown)
+                })
+            })
+            .map(|(meal, _)| meal.clone())
+            .collect();
+        meals.sort();
+        meals
+    }
+
     #[instrument(level = "trace")]
     fn get_meal_records_in_period(&self, period: Period) -> Result<Vec<MealRecord>> {
-        let condition = "WHERE date >= :start AND date <= :end";
-        let condition_params: Vec<(&str, Value)> = vec![
-            (":start", period.first_day_timestamp().into()),
-            (":end", period.last_day_timestamp().into()),
-        ];
-        self.select_records(condition, &condition_params)
+        let filter = RecordFilter {
+            meal: None,
+            period: Some(period),
+            status: None,
+        };
+        self.store.select(&filter)
     }
 
     /// Outputs meal records with unique meals and their respective last dates. The result vector is sorted
-    /// by date.
+    /// by date. Only the meals matching the *tag_filter* are included.
     ///
     /// Example:
     /// ```
-    /// use libmrot::{MealRecord, Storage};
+    /// use libmrot::{FixedClock, MealRecord, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
     ///
-    /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
     ///
     /// // fill storage with some data
     /// storage.add_meal_on_dates(
@@ -228,7 +1123,7 @@ impl Storage {
     ///      ).unwrap();
     ///
     /// // get unique meals
-    /// let unique_meals = storage.get_last_cooked_unique().unwrap();
+    /// let unique_meals = storage.get_last_cooked_unique(&TagFilter::default()).unwrap();
     /// let expected_meal_records = vec![
     ///     MealRecord::new("spaghetti", "March 2, 2025").unwrap(),
     ///     MealRecord::new("curry", "March 4, 2025").unwrap(),
@@ -236,44 +1131,93 @@ impl Storage {
     /// assert_eq!(unique_meals, expected_meal_records);
     /// ```
     #[instrument]
-    pub fn get_last_cooked_unique(&self) -> Result<Vec<MealRecord>> {
-        let query = "SELECT meal, MAX(date) AS date FROM meals GROUP BY meal ORDER BY date ASC";
-        let mut statement = self.connection.prepare(query)?;
-        let mut records = Vec::new();
-        while let Ok(State::Row) = statement.next() {
-            let timestamp = statement.read::<i64, _>("date")?;
-            let meal = statement.read::<String, _>("meal")?;
-            records.push(MealRecord::from_meal_and_timestamp(&meal, timestamp)?);
+    pub fn get_last_cooked_unique(&self, tag_filter: &TagFilter) -> Result<Vec<MealRecord>> {
+        let mut unique = Self::unique_by_normalized_name(self.store.unique()?);
+        self.filter_tagged(&mut unique, tag_filter)?;
+        Ok(unique)
+    }
+
+    /// Returns the names of the known meals which are spelled similarly to *name*, the most
+    /// similar first, e.g. to ask "did you mean" when *name* is misspelled. The similarity of two
+    /// names is the edit distance between their [normalized](crate::normalize_name) forms, which
+    /// may be up to a third of the length of *name*, but at least one. Meals with the same
+    /// normalized name as *name* are not returned.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// for meal in ["spaghetti", "spaghetti bolognese", "pizza", "pita"] {
+    ///     storage.add_meal_on_dates(meal, &vec![String::from("today")]).unwrap();
+    /// }
+    ///
+    /// assert_eq!(storage.similar_meals("spagetti").unwrap(), vec!["spaghetti"]);
+    /// assert_eq!(storage.similar_meals("pitza").unwrap(), vec!["pita", "pizza"]);
+    /// assert_eq!(storage.similar_meals("Pizzza").unwrap(), vec!["pizza"]);
+    /// assert!(storage.similar_meals("ratatouille").unwrap().is_empty());
+    /// ```
+    #[instrument]
+    pub fn similar_meals(&self, name: &str) -> Result<Vec<String>> {
+        let normalized_name = normalize_name(name);
+        let max_distance = (normalized_name.chars().count() / 3).max(1);
+        let mut similar: Vec<(usize, String)> = self
+            .get_last_cooked_unique(&TagFilter::default())?
+            .into_iter()
+            .map(|record| {
+                let distance = edit_distance(&normalized_name, &normalize_name(&record.meal()));
+                (distance, record.meal())
+            })
+            .filter(|(distance, _)| (1..=max_distance).contains(distance))
+            .collect();
+        similar.sort();
+        Ok(similar.into_iter().map(|(_, meal)| meal).collect())
+    }
+
+    /// Keeps only the last of the records whose meals have the same
+    /// [normalized](crate::normalize_name) name, e.g. of `Pizza` and `pizza`.
+    fn unique_by_normalized_name(records: Vec<MealRecord>) -> Vec<MealRecord> {
+        let mut last_records: HashMap<String, MealRecord> = HashMap::new();
+        for record in records {
+            let key = normalize_name(&record.meal());
+            match last_records.get(&key) {
+                Some(last) if last.timestamp() >= record.timestamp() => {}
+                _ => {
+                    last_records.insert(key, record);
+                }
+            }
         }
-        Ok(records)
+        let mut unique: Vec<MealRecord> = last_records.into_values().collect();
+        unique.sort_by(|a, b| {
+            a.timestamp()
+                .cmp(&b.timestamp())
+                .then_with(|| a.meal().cmp(&b.meal()))
+        });
+        unique
     }
 
     #[instrument(level = "trace")]
     fn filter_meal_records(records: &mut Vec<MealRecord>, ignore: &Vec<String>) -> () {
-        records.retain(|r| !ignore.contains(&r.meal()));
-    }
-
-    #[instrument(level = "debug")]
-    fn pick_n_meal_records(number: usize, candidates: &mut Vec<MealRecord>) -> Vec<MealRecord> {
-        _ = candidates.split_off(min(number, candidates.len()));
-        candidates.drain(..).collect()
+        let ignored: HashSet<String> = ignore.iter().map(|name| normalize_name(name)).collect();
+        records.retain(|r| !ignored.contains(&normalize_name(&r.meal())));
     }
 
-    /// Samples one random meal record from all unique recorded meals.
+    /// Samples one random meal record from all unique recorded meals matching the *tag_filter*,
+    /// except the meals in their [cooldown](Storage::set_cooldown).
     ///
     /// Example:
     /// ```
-    /// use libmrot::Storage;
+    /// use libmrot::{Storage, TagFilter};
     ///
     /// // prepare storage with some data
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// let storage = Storage::in_memory();
     /// let yesterday = vec![String::from("yesterday")];
     /// let today = vec![String::from("today")];
     /// storage.add_meal_on_dates("pork liver", &yesterday).unwrap();
     /// storage.add_meal_on_dates("champaign and caviar", &today).unwrap();
     ///
     /// // pick a random meal
-    /// let random_pick = storage.random().unwrap().unwrap();
+    /// let random_pick = storage.random(&TagFilter::default()).unwrap().unwrap();
     /// println!("Let's have {} again, yay!", random_pick.meal());
     /// ```
     ///
@@ -281,27 +1225,190 @@ impl Storage {
     ///
     /// Returns none if there are no meal records in the storage.
     /// ```
-    /// use libmrot::Storage;
+    /// use libmrot::{Storage, TagFilter};
     ///
     /// // open an empty in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// let storage = Storage::in_memory();
     ///
-    /// assert_eq!(storage.random().unwrap(), None);
+    /// assert_eq!(storage.random(&TagFilter::default()).unwrap(), None);
     /// ```
     #[instrument]
-    pub fn random(&self) -> Result<Option<MealRecord>> {
-        let unique = self.get_last_cooked_unique()?;
+    pub fn random(&self, tag_filter: &TagFilter) -> Result<Option<MealRecord>> {
+        let mut unique = self.get_last_cooked_unique(tag_filter)?;
+        Self::filter_meal_records(&mut unique, &self.get_meals_in_cooldown(self.today())?);
         Ok(unique.into_iter().choose(&mut rand::rng()))
     }
 
-    /// Show what meals were consumed in the given date range.
+    /// Samples up to *number* distinct meal records from the same candidates as
+    /// [`what`](Storage::what), i.e. the unique recorded meals matching the *tag_filter* without
+    /// the ones in the *ignore_list* or recorded in the *option_ignore_period*. Unlike `what`, the oldest meals are
+    /// not always picked, they are only more likely to be picked: each meal is weighted by the
+    /// number of days since it was last consumed plus one. Meals recorded only for today or later
+    /// have the weight one.
+    ///
+    /// The records are returned in the order in which they were picked. Passing the same
+    /// *option_seed* to a storage with the same records picks the same meals.
     ///
     /// Example:
     /// ```
-    /// use libmrot::{MealRecord, Storage};
+    /// use libmrot::{FixedClock, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
     ///
-    /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// // fill storage with data
+    /// storage.add_meal_on_dates("spaghetti", &vec![String::from("March 1")]).unwrap();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("April 14")]).unwrap();
+    /// storage.add_meal_on_dates("steak", &vec![String::from("April 10")]).unwrap();
+    ///
+    /// // pick two meals, ignoring steak; spaghetti is 23 times more likely to be picked first
+    /// let ignore = vec![String::from("steak")];
+    /// let picks = storage.weighted_random(2, None, ignore.clone(), &TagFilter::default(), Some(7)).unwrap();
+    /// let mut meals: Vec<String> = picks.iter().map(|record| record.meal()).collect();
+    ///
+    /// // the same seed picks the same meals
+    /// assert_eq!(picks, storage.weighted_random(2, None, ignore, &TagFilter::default(), Some(7)).unwrap());
+    ///
+    /// meals.sort();
+    /// assert_eq!(meals, vec!["pizza", "spaghetti"]);
+    /// ```
+    #[instrument]
+    pub fn weighted_random(
+        &self,
+        number: u64,
+        option_ignore_period: Option<Period>,
+        ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
+        option_seed: Option<u64>,
+    ) -> Result<Vec<MealRecord>> {
+        self.suggest(
+            number,
+            option_ignore_period,
+            ignore_list,
+            tag_filter,
+            &Diversity::default(),
+            &WeightedRandom::new(option_seed),
+        )
+    }
+
+    /// Plans meals for the days of the *period* which have no record yet. The candidates are
+    /// ranked by the *strategy* like for [`suggest`](Storage::suggest), without the meals in the
+    /// *ignore_list*, the meals not matching the *tag_filter* and the meals recorded in the
+    /// *option_ignore_period* or in the *period* itself. Each day gets the best candidate which is
+    /// not in its [cooldown](Storage::set_cooldown) on that day and which the *diversity* rule
+    /// allows, and every meal is planned at most once. With [one meal per
+    /// tag](Diversity::one_per_tag), the tags of a planned meal are taken for the rest of the
+    /// plan. Days stay empty when the candidates run out.
+    ///
+    /// The plan is only returned, the storage is left unchanged. Pass it to
+    /// [`add_records`](Storage::add_records) to record it.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{Diversity, FixedClock, MealRecord, OldestFirst, Status, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// // fill storage with data, curry is already planned for April 17th
+    /// storage.add_meal_on_dates("spaghetti", &vec![String::from("March 1")]).unwrap();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("March 5")]).unwrap();
+    /// storage.add_meal_on_dates("steak", &vec![String::from("March 8")]).unwrap();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("April 17")]).unwrap();
+    ///
+    /// let period = storage.period("from April 16 through April 18").unwrap();
+    /// let plan = storage
+    ///     .plan(period, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &OldestFirst)
+    ///     .unwrap();
+    /// assert_eq!(plan, vec![
+    ///     MealRecord::new("spaghetti", "April 16, 2025").unwrap().with_status(Status::Planned),
+    ///     MealRecord::new("pizza", "April 18, 2025").unwrap().with_status(Status::Planned),
+    /// ]);
+    ///
+    /// storage.add_records(&plan).unwrap();
+    /// assert_eq!(storage.show("April 18", &TagFilter::default()).unwrap(), vec![plan[1].clone()]);
+    /// ```
+    #[instrument]
+    pub fn plan(
+        &self,
+        period: Period,
+        option_ignore_period: Option<Period>,
+        ignore_list: Vec<String>,
+        tag_filter: &TagFilter,
+        diversity: &Diversity,
+        strategy: &dyn SuggestionStrategy,
+    ) -> Result<Vec<MealRecord>> {
+        let recorded: Vec<MealRecord> = self
+            .get_meal_records_in_period(period.clone())?
+            .into_iter()
+            .filter(|record| record.status() != Status::Skipped)
+            .collect();
+        let recorded_dates: HashSet<NaiveDate> =
+            recorded.iter().map(|record| record.naive_date()).collect();
+        let ignore_list = ignore_list
+            .into_iter()
+            .chain(recorded.iter().map(|record| record.meal()))
+            .collect();
+        let candidates =
+            self.get_candidate_statistics(option_ignore_period, ignore_list, tag_filter)?;
+        let mut pool: Vec<String> = strategy
+            .rank(candidates, self.today())
+            .iter()
+            .map(|candidate| candidate.meal())
+            .collect();
+        let (tags, mut taken_tags) = self.get_diversity_tags(diversity)?;
+        let cooldowns = self.get_cooldowns()?;
+        let timezone = self.timezone();
+        let mut plan = Vec::new();
+        for date in period.first_date().iter_days() {
+            if date > period.last_date() || pool.is_empty() {
+                break;
+            }
+            if recorded_dates.contains(&date) {
+                continue;
+            }
+            let in_cooldown = Self::meals_in_cooldown(&cooldowns, date);
+            let Some(index) = pool.iter().position(|meal| {
+                !in_cooldown.contains(meal) && !Self::has_taken_tag(&tags, &taken_tags, meal)
+            }) else {
+                continue;
+            };
+            let meal = pool.remove(index);
+            if diversity.one_per_tag {
+                taken_tags.extend(tags.get(&meal).cloned().unwrap_or_default());
+            }
+            let record = MealRecord::from_meal_and_timestamp_in(
+                &meal,
+                timezone.start_of_day(&date),
+                &timezone,
+            )?;
+            plan.push(record.with_status(Status::Planned));
+        }
+        Ok(plan)
+    }
+
+    /// Adds the records to the storage in one transaction, e.g. a [plan](Storage::plan). Either
+    /// all records are added or none.
+    #[instrument]
+    pub fn add_records(&self, records: &[MealRecord]) -> Result<()> {
+        self.store.add_records(records, self.clock.now())
+    }
+
+    /// Show what meals were consumed in the given date range, only the meals matching the
+    /// *tag_filter*.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, MealRecord, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
     ///
     /// // fill storage with some data
     /// storage.add_meal_on_dates(
@@ -314,7 +1421,7 @@ impl Storage {
     ///     ).unwrap();
     ///
     /// // get recorded data
-    /// let actual_meal_records = storage.show("March 2025").unwrap();
+    /// let actual_meal_records = storage.show("March 2025", &TagFilter::default()).unwrap();
     /// let expected_meal_records = vec![
     ///     MealRecord::new("spaghetti", "March 1, 2025").unwrap(),
     ///     MealRecord::new("spaghetti", "March 2, 2025").unwrap(),
@@ -333,24 +1440,48 @@ impl Storage {
     /// curry (2025-03-04)
     /// ```
     #[instrument]
-    pub fn show(&self, date_range: &str) -> Result<Vec<MealRecord>> {
-        //let timestamps = convert_to_timestamps(&vec![String::from(date_range)])?;
-        // timestamps are guaranteed to be a vector of at least one element, so we can unwrap
-        //let start = timestamps.iter().next().unwrap();
-        //let end = timestamps.iter().last().unwrap();
-        let period = Period::new(date_range)?;
-        self.get_meal_records_in_period(period)
+    pub fn show(&self, date_range: &str, tag_filter: &TagFilter) -> Result<Vec<MealRecord>> {
+        self.show_period(self.period(date_range)?, tag_filter)
+    }
+
+    /// Like [`show`](Storage::show), but for a [`Period`] instead of a date expression.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, MealRecord, Storage, TagFilter};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    /// storage.add_meal_on_dates("spaghetti", &vec![String::from("March 1, 2025")]).unwrap();
+    ///
+    /// let first_date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+    /// let last_date = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+    /// let period = storage.period_between(first_date, last_date);
+    ///
+    /// assert_eq!(
+    ///     storage.show_period(period, &TagFilter::default()).unwrap(),
+    ///     vec![MealRecord::new("spaghetti", "March 1, 2025").unwrap()],
+    /// );
+    /// ```
+    #[instrument]
+    pub fn show_period(&self, period: Period, tag_filter: &TagFilter) -> Result<Vec<MealRecord>> {
+        let mut records = self.get_meal_records_in_period(period)?;
+        self.filter_tagged(&mut records, tag_filter)?;
+        Ok(records)
     }
 
     /// Show on what dates a meal was recorded.
     ///
     /// Example:
     /// ```
-    /// use libmrot::Storage;
-    /// use chrono::NaiveDate;
+    /// use libmrot::{FixedClock, Storage};
+    /// use chrono::{NaiveDate, TimeZone, Utc};
     ///
-    /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
     ///
     /// // fill storage with some data
     /// storage.add_meal_on_dates(
@@ -379,9 +1510,12 @@ impl Storage {
     /// ```
     #[instrument]
     pub fn when(&self, meal: &str) -> Result<Vec<NaiveDate>> {
-        let condition = "WHERE meal = :meal";
-        let condition_params: Vec<(&str, Value)> = vec![(":meal", meal.into())];
-        let meal_records = self.select_records(condition, &condition_params)?;
+        let filter = RecordFilter {
+            meal: Some(self.resolve(meal)?),
+            period: None,
+            status: None,
+        };
+        let meal_records = self.store.select(&filter)?;
         let naive_dates: Vec<NaiveDate> =
             meal_records.into_iter().map(|r| r.naive_date()).collect();
         Ok(naive_dates)
@@ -392,10 +1526,12 @@ impl Storage {
     ///
     /// Example:
     /// ```
-    /// use libmrot::{MealRecord, Period, Storage};
+    /// use libmrot::{FixedClock, MealRecord, Storage};
+    /// use chrono::{TimeZone, Utc};
     ///
-    /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
     ///
     /// // fill storage with some data
     /// storage.add_meal_on_dates(
@@ -408,61 +1544,123 @@ impl Storage {
     ///     ).unwrap();
     ///
     /// // remove spaghetti in March
-    /// let period = Period::new("March").unwrap();
+    /// let period = storage.period("March").unwrap();
     /// let deleted_records = storage.remove(period, Some("spaghetti".to_string())).unwrap();
     ///
     /// let expected_deleted_records = vec![
-    ///     MealRecord::new("spaghetti", "March 1").unwrap(),
-    ///     MealRecord::new("spaghetti", "March 2").unwrap(),
+    ///     MealRecord::new("spaghetti", "March 1, 2025").unwrap(),
+    ///     MealRecord::new("spaghetti", "March 2, 2025").unwrap(),
     /// ];
     ///
     /// assert_eq!(deleted_records, expected_deleted_records);
     ///
     /// // remove all meals on March 3rd
-    /// let period = Period::new("March 3").unwrap();
+    /// let period = storage.period("March 3").unwrap();
     /// let deleted_records = storage.remove(period, None).unwrap();
     ///
     /// let expected_deleted_records = vec![
-    ///     MealRecord::new("curry", "March 3").unwrap(),
+    ///     MealRecord::new("curry", "March 3, 2025").unwrap(),
     /// ];
     ///
     /// assert_eq!(deleted_records, expected_deleted_records);
     /// ```
     #[instrument]
     pub fn remove(&self, period: Period, option_meal: Option<String>) -> Result<Vec<MealRecord>> {
-        let action_clause = "DELETE FROM meals";
-        let action_params = Vec::new();
-
-        let condition = match option_meal {
-            None => "WHERE date >= :start AND DATE <= :end",
-            Some(_) => "WHERE meal = :meal AND date >= :start AND DATE <= :end",
+        let filter = RecordFilter {
+            meal: self.resolve_option(option_meal)?,
+            period: Some(period),
+            status: None,
         };
-        let mut condition_params: Vec<(&str, Value)> = vec![
-            (":start", period.first_day_timestamp().into()),
-            (":end", period.last_day_timestamp().into()),
-        ];
-        if let Some(meal) = option_meal {
-            condition_params.push((":meal", meal.into()));
-        }
+        self.store.remove(&filter, self.clock.now())
+    }
 
-        let delete_func = || {
-            let records = self.select_records(condition, &condition_params)?;
-            self.manipulate_records(action_clause, &action_params, condition, &condition_params)?;
-            Ok(records)
-        };
+    /// Removes exactly the given records, e.g. one of several records of a meal on the same day.
+    /// Returns the removed records; records which are not in the storage are skipped.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, MealRecord, Storage};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// // record spaghetti twice on March 1st
+    /// storage.add_meal_on_dates(
+    ///     "spaghetti",
+    ///     &vec![String::from("March 1, 2025"), String::from("March 1, 2025")],
+    ///     ).unwrap();
+    ///
+    /// // remove one of them
+    /// let record = MealRecord::new("spaghetti", "March 1, 2025").unwrap();
+    /// let deleted_records = storage.remove_records(&[record.clone()]).unwrap();
+    ///
+    /// assert_eq!(deleted_records, vec![record]);
+    /// assert_eq!(storage.when("spaghetti").unwrap().len(), 1);
+    /// ```
+    #[instrument]
+    pub fn remove_records(&self, records: &[MealRecord]) -> Result<Vec<MealRecord>> {
+        self.store.remove_records(records, self.clock.now())
+    }
 
-        let records = self.sql_transaction(delete_func)?;
-        Ok(records)
+    /// Lists the meal records which [`remove`](Storage::remove) would delete, without deleting
+    /// them.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, MealRecord, Storage};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// // fill storage with some data
+    /// storage.add_meal_on_dates(
+    ///     "spaghetti",
+    ///     &vec![String::from("from March 1 through March 2, 2025")],
+    ///     ).unwrap();
+    ///
+    /// // preview removing the meals on March 2nd
+    /// let period = storage.period("March 2").unwrap();
+    /// let records = storage.preview_remove(period, None).unwrap();
+    ///
+    /// assert_eq!(records, vec![MealRecord::new("spaghetti", "March 2, 2025").unwrap()]);
+    ///
+    /// // nothing was removed
+    /// assert_eq!(storage.when("spaghetti").unwrap().len(), 2);
+    /// ```
+    #[instrument]
+    pub fn preview_remove(
+        &self,
+        period: Period,
+        option_meal: Option<String>,
+    ) -> Result<Vec<MealRecord>> {
+        let filter = RecordFilter {
+            meal: self.resolve_option(option_meal)?,
+            period: Some(period),
+            status: None,
+        };
+        self.store.select(&filter)
     }
 
     /// Rename a meal from *old_name* to *new_name*, optionally rename only in the given period.
+    /// Returns the renamed records as they were before renaming.
+    ///
+    /// Renaming a meal without a period keeps its [`MealId`](crate::MealId). If a meal named
+    /// *new_name* is already known, the records of *old_name* are merged into it. A *new_name*
+    /// which is only another spelling of the meal's name, e.g. in other letter case, corrects the
+    /// spelling of the whole meal, regardless of the period.
     ///
     /// Example:
     /// ```
-    /// use libmrot::{MealRecord, Period, Storage};
+    /// use libmrot::{FixedClock, MealRecord, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
     ///
-    /// // open in-memory storage
-    /// let storage = Storage::open(":memory:").unwrap();
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
     ///
     /// // fill storage with some data
     /// storage.add_meal_on_dates(
@@ -475,19 +1673,19 @@ impl Storage {
     ///     ).unwrap();
     ///
     /// // rename spaghetti to penne on March 1st
-    /// let old_records = storage.rename("spaghetti", "penne", Some(Period::new("March 1").unwrap())).unwrap();
+    /// let old_records = storage.rename("spaghetti", "penne", Some(storage.period("March 1").unwrap())).unwrap();
     ///
     /// let expected_old_records = vec![
-    ///     MealRecord::new("spaghetti", "March 1").unwrap(),
+    ///     MealRecord::new("spaghetti", "March 1, 2025").unwrap(),
     /// ];
     ///
     /// assert_eq!(old_records, expected_old_records);
     ///
     /// // check current records
-    /// let current_records = storage.show("from March 1 through March 2").unwrap();
+    /// let current_records = storage.show("from March 1 through March 2", &TagFilter::default()).unwrap();
     /// let expected_current_records = vec![
-    ///     MealRecord::new("penne", "March 1").unwrap(),
-    ///     MealRecord::new("spaghetti", "March 2").unwrap(),
+    ///     MealRecord::new("penne", "March 1, 2025").unwrap(),
+    ///     MealRecord::new("spaghetti", "March 2, 2025").unwrap(),
     /// ];
     ///
     /// assert_eq!(current_records, expected_current_records);
@@ -499,93 +1697,467 @@ impl Storage {
         new_name: &str,
         option_period: Option<Period>,
     ) -> Result<Vec<MealRecord>> {
-        let action_clause = "UPDATE meals SET meal = :new_name";
-        let action_params: Vec<(&str, Value)> = vec![(":new_name", new_name.into())];
-
-        let condition = match option_period {
-            None => "WHERE meal = :meal",
-            Some(_) => "WHERE meal = :meal AND date >= :start AND date <= :end",
-        };
-        let mut condition_params: Vec<(&str, Value)> = vec![(":meal", old_name.into())];
-        if let Some(period) = option_period {
-            condition_params.push((":start", period.first_day_timestamp().into()));
-            condition_params.push((":end", period.last_day_timestamp().into()));
+        let old_name = self.resolve(old_name)?;
+        if normalize_name(new_name) == normalize_name(&old_name) {
+            return self
+                .store
+                .rename(&old_name, new_name, None, self.clock.now());
         }
+        self.store.rename(
+            &old_name,
+            &self.resolve(new_name)?,
+            option_period.as_ref(),
+            self.clock.now(),
+        )
+    }
 
-        let update_func = || {
-            let records = self.select_records(condition, &condition_params)?;
-            self.manipulate_records(action_clause, &action_params, condition, &condition_params)?;
-            Ok(records)
+    /// Lists the meal records which [`rename`](Storage::rename) would rename, without renaming
+    /// them.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, MealRecord, Storage};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// // fill storage with some data
+    /// storage.add_meal_on_dates(
+    ///     "spaghetti",
+    ///     &vec![String::from("from March 1 through March 2")],
+    ///     ).unwrap();
+    ///
+    /// // preview renaming spaghetti on March 1st
+    /// let records = storage.preview_rename("spaghetti", Some(storage.period("March 1").unwrap())).unwrap();
+    ///
+    /// assert_eq!(records, vec![MealRecord::new("spaghetti", "March 1, 2025").unwrap()]);
+    ///
+    /// // nothing was renamed
+    /// assert_eq!(storage.when("spaghetti").unwrap().len(), 2);
+    /// ```
+    #[instrument]
+    pub fn preview_rename(
+        &self,
+        old_name: &str,
+        option_period: Option<Period>,
+    ) -> Result<Vec<MealRecord>> {
+        let filter = RecordFilter {
+            meal: Some(self.resolve(old_name)?),
+            period: option_period,
+            status: None,
         };
+        self.store.select(&filter)
+    }
 
-        let records = self.sql_transaction(update_func)?;
-        Ok(records)
+    /// Confirms that the meals recorded in the *option_period* were eaten, optionally only the
+    /// records of the meal *option_meal*. The period ends before today at the latest, as the meals
+    /// of today and later days cannot have been eaten yet. Without a period, the
+    /// [pending](Storage::pending) records are confirmed. Returns the records whose status
+    /// changed. Confirming is journaled and can be [undone](Storage::undo).
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Status, Storage};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // plan two meals on April 15th, 2025
+    /// let plan_clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(plan_clock);
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("April 16")]).unwrap();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("April 17")]).unwrap();
+    ///
+    /// // on April 20th, both are pending
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 20, 12, 0, 0).unwrap());
+    /// let storage = storage.with_clock(clock);
+    /// assert_eq!(storage.pending().unwrap().len(), 2);
+    ///
+    /// let confirmed = storage.confirm(None, Some(String::from("pizza"))).unwrap();
+    /// assert_eq!(confirmed.len(), 1);
+    /// assert_eq!(confirmed[0].status(), Status::Eaten);
+    /// assert_eq!(storage.pending().unwrap()[0].meal(), "curry");
+    /// ```
+    #[instrument]
+    pub fn confirm(
+        &self,
+        option_period: Option<Period>,
+        option_meal: Option<String>,
+    ) -> Result<Vec<MealRecord>> {
+        self.set_status(option_period, option_meal, Status::Eaten)
+    }
+
+    /// Marks the meals recorded in the *option_period* as skipped, optionally only the records of
+    /// the meal *option_meal*. Without a period, the [pending](Storage::pending) records are
+    /// skipped. Returns the records whose status changed. Skipping is journaled and can be
+    /// [undone](Storage::undo).
+    ///
+    /// Skipped records are kept, but they do not count as the last time a meal was eaten when
+    /// suggesting meals, and they neither make a meal ignored nor start its cooldown.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, MealRecord, Storage, TagFilter};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("March 1")]).unwrap();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("March 5")]).unwrap();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("April 14")]).unwrap();
+    ///
+    /// // we did not have the pizza yesterday after all
+    /// let period = storage.period("yesterday").unwrap();
+    /// storage.skip(Some(period), Some(String::from("pizza"))).unwrap();
+    ///
+    /// let suggestions = storage.what(1, None, Vec::new(), &TagFilter::default()).unwrap();
+    /// assert_eq!(suggestions, vec![MealRecord::new("pizza", "March 1, 2025").unwrap()]);
+    /// ```
+    #[instrument]
+    pub fn skip(
+        &self,
+        option_period: Option<Period>,
+        option_meal: Option<String>,
+    ) -> Result<Vec<MealRecord>> {
+        self.set_status(option_period, option_meal, Status::Skipped)
     }
 
-    fn select_records(
+    #[instrument(level = "debug")]
+    fn set_status(
         &self,
-        condition: &str,
-        selection_params: &[(&str, Value)],
+        option_period: Option<Period>,
+        option_meal: Option<String>,
+        status: Status,
     ) -> Result<Vec<MealRecord>> {
-        let select_query = format!(
-            "SELECT date, meal FROM meals {} ORDER BY date ASC",
-            condition
-        );
-        let mut select_statement = self.connection.prepare(select_query)?;
-        for &(name, ref value) in selection_params {
-            select_statement.bind((name, value))?;
-        }
+        let option_meal = self.resolve_option(option_meal)?;
+        let Some(yesterday) = self.today().pred_opt() else {
+            return Ok(Vec::new());
+        };
+        let filter = match option_period {
+            // meals of today and later days cannot have been eaten yet
+            Some(period) if status == Status::Eaten => {
+                if period.first_date() > yesterday {
+                    return Ok(Vec::new());
+                }
+                let last_date = period.last_date().min(yesterday);
+                RecordFilter {
+                    meal: option_meal,
+                    period: Some(Period::between(
+                        period.first_date(),
+                        last_date,
+                        &self.timezone(),
+                    )),
+                    status: None,
+                }
+            }
+            Some(period) => RecordFilter {
+                meal: option_meal,
+                period: Some(period),
+                status: None,
+            },
+            None => {
+                let Some(first_pending) = self.pending()?.first().map(|r| r.naive_date()) else {
+                    return Ok(Vec::new());
+                };
+                RecordFilter {
+                    meal: option_meal,
+                    period: Some(Period::between(first_pending, yesterday, &self.timezone())),
+                    status: Some(Status::Planned),
+                }
+            }
+        };
+        self.store.set_status(&filter, status, self.clock.now())
+    }
 
-        let mut records = Vec::new();
-        while let Ok(State::Row) = select_statement.next() {
-            let timestamp = select_statement.read::<i64, _>("date")?;
-            let meal = select_statement.read::<String, _>("meal")?;
-            records.push(MealRecord::from_meal_and_timestamp(&meal, timestamp)?);
-        }
+    /// Returns the planned records of the days before today, which were neither
+    /// [confirmed](Storage::confirm) nor [skipped](Storage::skip), ordered by date.
+    #[instrument]
+    pub fn pending(&self) -> Result<Vec<MealRecord>> {
+        let today = self.today();
+        let filter = RecordFilter {
+            meal: None,
+            period: None,
+            status: Some(Status::Planned),
+        };
+        let pending = self
+            .store
+            .select(&filter)?
+            .into_iter()
+            .filter(|record| record.naive_date() < today)
+            .collect();
+        Ok(pending)
+    }
 
-        Ok(records)
+    /// Computes the [`Statistics`] of the eaten meals, optionally only of those eaten in the given
+    /// [`Period`]. Planned and skipped records are not counted.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Storage};
+    /// use chrono::{NaiveDate, TimeZone, Utc, Weekday};
+    ///
+    /// // open in-memory storage, pretending that today is April 15th, 2025
+    /// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+    /// let storage = Storage::in_memory().with_clock(clock);
+    ///
+    /// storage.add_meal_on_dates(
+    ///     "pizza",
+    ///     &vec![String::from("March 1, 2025"), String::from("March 8, 2025")],
+    ///     ).unwrap();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("March 2, 2025")]).unwrap();
+    /// storage.add_meal_on_dates("steak", &vec![String::from("March 3, 2025")]).unwrap();
+    /// // planned meals are not counted
+    /// storage.add_meal_on_dates("curry", &vec![String::from("April 20, 2025")]).unwrap();
+    ///
+    /// let statistics = storage.statistics(None).unwrap();
+    /// assert_eq!(statistics.records(), 4);
+    /// assert_eq!(statistics.distinct_meals(), 3);
+    /// assert_eq!(statistics.most_frequent(1)[0].meal(), "pizza");
+    /// assert_eq!(statistics.most_frequent(1)[0].average_interval(), Some(7.0));
+    /// assert_eq!(statistics.eaten_once().len(), 2);
+    /// let streak = statistics.longest_streak().unwrap();
+    /// assert_eq!(streak.meals(), 3);
+    /// assert_eq!(streak.last_date(), NaiveDate::from_ymd_opt(2025, 3, 3).unwrap());
+    /// assert_eq!(statistics.busiest_weekday(), Some((Weekday::Sat, 2)));
+    ///
+    /// let period = storage.period("from March 2 through March 8, 2025").unwrap();
+    /// let statistics = storage.statistics(Some(period)).unwrap();
+    /// assert_eq!(statistics.records(), 3);
+    /// ```
+    #[instrument]
+    pub fn statistics(&self, option_period: Option<Period>) -> Result<Statistics> {
+        let filter = RecordFilter {
+            meal: None,
+            period: option_period,
+            status: Some(Status::Eaten),
+        };
+        let records = self.store.select(&filter)?;
+        Ok(Statistics::from_records(&records, self.today()))
     }
 
-    fn manipulate_records(
+    /// Writes the records of the storage, ordered by date and meal, in the given format. Optionally,
+    /// only the records in the given period are written.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{ExchangeFormat, Storage};
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("spaghetti", &vec![String::from("March 2, 2025")]).unwrap();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("March 1, 2025")]).unwrap();
+    ///
+    /// let mut csv = Vec::new();
+    /// storage.export(ExchangeFormat::Csv, None, &mut csv).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(csv).unwrap(),
+    ///     "date,meal,status\n2025-03-01,curry,eaten\n2025-03-02,spaghetti,eaten\n",
+    /// );
+    ///
+    /// let mut json = Vec::new();
+    /// storage.export(ExchangeFormat::Json, None, &mut json).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(json).unwrap(),
+    ///     "[\n  {\"date\":\"2025-03-01\",\"meal\":\"curry\",\"status\":\"eaten\"},\n  \
+    ///     {\"date\":\"2025-03-02\",\"meal\":\"spaghetti\",\"status\":\"eaten\"}\n]\n",
+    /// );
+    ///
+    /// let mut ics = Vec::new();
+    /// let period = storage.period("March 2, 2025").unwrap();
+    /// storage.export(ExchangeFormat::Ics, Some(period), &mut ics).unwrap();
+    /// let ics = String::from_utf8(ics).unwrap();
+    /// assert!(ics.contains("DTSTART;VALUE=DATE:20250302\r\n"));
+    /// assert!(ics.contains("SUMMARY:spaghetti\r\n"));
+    /// assert!(!ics.contains("curry"));
+    /// ```
+    #[instrument(skip(writer))]
+    pub fn export(
         &self,
-        action_clause: &str,
-        action_params: &[(&str, Value)],
-        condition: &str,
-        condition_params: &[(&str, Value)],
+        format: ExchangeFormat,
+        option_period: Option<Period>,
+        writer: impl Write,
     ) -> Result<()> {
-        let query = format!("{} {}", action_clause, condition);
-        let mut statement = self.connection.prepare(query)?;
-        for &(name, ref value) in action_params.into_iter().chain(condition_params) {
-            statement.bind((name, value))?;
+        let mut records = self.store.select(&RecordFilter {
+            meal: None,
+            period: option_period,
+            status: None,
+        })?;
+        records.sort_by(|a, b| {
+            a.timestamp()
+                .cmp(&b.timestamp())
+                .then_with(|| a.meal().cmp(&b.meal()))
+        });
+        format.write(&records, writer)
+    }
+
+    /// Reads records in the given format and adds them to the storage. Records without a status
+    /// are planned if their date is after today, eaten otherwise. Records of a meal on a date
+    /// which already has a record of that meal are skipped and reported as duplicates. All records
+    /// are added in one transaction. With `dry_run`, the storage is left unchanged, but the report
+    /// tells what would be imported.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{ExchangeFormat, MealRecord, Storage};
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("curry", &vec![String::from("March 1, 2025")]).unwrap();
+    ///
+    /// let csv = "date,meal\n2025-03-01,curry\n2025-03-02,spaghetti\n";
+    ///
+    /// let report = storage.import(ExchangeFormat::Csv, csv.as_bytes(), true).unwrap();
+    /// assert_eq!(report.imported, vec![MealRecord::new("spaghetti", "March 2, 2025").unwrap()]);
+    /// assert_eq!(report.duplicates, vec![MealRecord::new("curry", "March 1, 2025").unwrap()]);
+    /// assert_eq!(storage.when("spaghetti").unwrap(), vec![]);
+    ///
+    /// storage.import(ExchangeFormat::Csv, csv.as_bytes(), false).unwrap();
+    /// assert_eq!(storage.when("spaghetti").unwrap().len(), 1);
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the data cannot be read in the given format. Nothing is imported then.
+    #[instrument(skip(reader))]
+    pub fn import(
+        &self,
+        format: ExchangeFormat,
+        reader: impl Read,
+        dry_run: bool,
+    ) -> Result<ImportReport> {
+        let timezone = self.timezone();
+        let today = self.today();
+        let mut known: HashSet<(String, NaiveDate)> = self
+            .store
+            .select(&RecordFilter::default())?
+            .into_iter()
+            .map(|record| (record.meal(), record.naive_date()))
+            .collect();
+        let mut canonical_names = self.get_canonical_names()?;
+        let mut report = ImportReport::default();
+        format.read(reader, &timezone, |exchange_record| {
+            let meal = canonical_names.resolve(&exchange_record.meal);
+            canonical_names.insert(&meal);
+            let timestamp = timezone.start_of_day(&exchange_record.date);
+            let record = MealRecord::from_meal_and_timestamp_in(&meal, timestamp, &timezone)?
+                .with_status(
+                    exchange_record
+                        .status
+                        .unwrap_or_else(|| Status::of_new_record(exchange_record.date, today)),
+                );
+            match known.insert((meal, exchange_record.date)) {
+                true => report.imported.push(record),
+                false => report.duplicates.push(record),
+            }
+            Ok(())
+        })?;
+        if !dry_run {
+            self.store.add_records(&report.imported, self.clock.now())?;
         }
-        statement.next()?;
-        Ok(())
+        Ok(report)
     }
 
-    fn sql_transaction<F>(&self, func: F) -> Result<Vec<MealRecord>>
-    where
-        F: Fn() -> Result<Vec<MealRecord>>,
-    {
-        self.connection.execute("BEGIN TRANSACTION")?;
+    /// Returns the journal of the operations which changed the records of the storage, ordered
+    /// from the oldest to the latest. Adding, importing, removing and renaming records are
+    /// journaled, and so are [confirming](Storage::confirm) and [skipping](Storage::skip) them.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{FixedClock, Storage};
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let moment = Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap();
+    /// let storage = Storage::in_memory().with_clock(FixedClock::new(moment));
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("today")]).unwrap();
+    /// storage.rename("pizza", "calzone", None).unwrap();
+    ///
+    /// let journal = storage.journal().unwrap();
+    /// assert_eq!(journal.len(), 2);
+    /// assert_eq!(journal[0].to_string(), "add pizza (2025-04-15)");
+    /// assert_eq!(journal[1].to_string(), "rename pizza to calzone: pizza (2025-04-15)");
+    /// assert_eq!(journal[1].performed(), moment);
+    /// ```
+    #[instrument]
+    pub fn journal(&self) -> Result<Vec<JournalEntry>> {
+        self.store.journal()
+    }
 
-        let records = func()?;
+    /// Reverts the latest operation in the journal which has not been undone yet. Returns its
+    /// journal entry, or `None` if there is nothing to undo.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("April 15, 2025")]).unwrap();
+    /// storage.remove(storage.period("April 2025").unwrap(), None).unwrap();
+    /// assert!(storage.when("pizza").unwrap().is_empty());
+    ///
+    /// let undone = storage.undo().unwrap().unwrap();
+    /// assert_eq!(undone.to_string(), "remove pizza (2025-04-15) (undone)");
+    /// assert_eq!(storage.when("pizza").unwrap().len(), 1);
+    ///
+    /// storage.undo().unwrap();
+    /// assert!(storage.undo().unwrap().is_none());
+    /// assert!(storage.when("pizza").unwrap().is_empty());
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::ChangedRecords`] and changes nothing if one of the records of the
+    /// operation cannot be found anymore.
+    #[instrument]
+    pub fn undo(&self) -> Result<Option<JournalEntry>> {
+        let journal = self.store.journal()?;
+        let Some(entry) = journal.iter().rev().find(|entry| !entry.is_undone()) else {
+            return Ok(None);
+        };
+        self.store
+            .apply(entry, &entry.operation().inverse(), true)?;
+        Ok(Some(entry.with_undone(true)))
+    }
 
-        self.connection.execute("COMMIT").map_err(|e| {
-            let _ = self.connection.execute("ROLLBACK");
-            e
-        })?;
-        Ok(records)
+    /// Performs the earliest undone operation in the journal again. Returns its journal entry, or
+    /// `None` if there is nothing to redo. Any new operation discards the undone operations, so
+    /// they cannot be redone after it.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Storage;
+    ///
+    /// let storage = Storage::in_memory();
+    /// storage.add_meal_on_dates("pizza", &vec![String::from("April 15, 2025")]).unwrap();
+    /// storage.undo().unwrap();
+    /// assert!(storage.when("pizza").unwrap().is_empty());
+    ///
+    /// storage.redo().unwrap();
+    /// assert_eq!(storage.when("pizza").unwrap().len(), 1);
+    /// assert!(storage.redo().unwrap().is_none());
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::ChangedRecords`] and changes nothing if one of the records of the
+    /// operation cannot be found anymore.
+    #[instrument]
+    pub fn redo(&self) -> Result<Option<JournalEntry>> {
+        let journal = self.store.journal()?;
+        let Some(entry) = journal.iter().find(|entry| entry.is_undone()) else {
+            return Ok(None);
+        };
+        self.store.apply(entry, entry.operation(), false)?;
+        Ok(Some(entry.with_undone(false)))
     }
 }
 
-impl fmt::Debug for Storage {
+impl<S: fmt::Debug> fmt::Debug for Storage<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Storage(chg: {})", self.connection.total_change_count())
+        write!(f, "Storage({:?}, {:?})", self.store, self.clock)
     }
 }
 
-impl fmt::Display for Storage {
+impl<S: fmt::Display> fmt::Display for Storage<S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Storage({})", self.path_string)
+        write!(f, "Storage({})", self.store)
     }
 }
@@ -0,0 +1,270 @@
+//! Persistence backends for the [`Storage`](crate::Storage)
+
+mod memory;
+mod sqlite;
+
+pub use self::{memory::MemoryStore, sqlite::SqliteStore};
+use crate::{JournalEntry, Meal, MealRecord, Operation, Period, Result, Status, Timezone};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// Persistence backend of a [`Storage`](crate::Storage).
+///
+/// The storage converts date expressions into day timestamps and implements suggestions on top
+/// of these primitive operations. A day timestamp is the first moment of the day in the store's
+/// [`Timezone`]. Every mutating operation must either apply completely or not
+/// at all.
+///
+/// The operations which change records are given the `moment` in which they are performed. A store
+/// keeps each of them as a [`JournalEntry`], written together with the change, so that the
+/// [`Storage`](crate::Storage) can undo and redo it by [applying](MealStore::apply) the operation
+/// or its [inverse](Operation::inverse). Operations which change no records are not journaled. A
+/// new operation discards the undone entries of the journal.
+///
+/// libmrot ships two implementations: [`SqliteStore`], which is the default, and the
+/// [`MemoryStore`], which keeps the records in a [`Vec`] and is handy for testing.
+pub trait MealStore: fmt::Debug {
+    /// Adds records of a meal on the given days. The days are given as timestamps of their start.
+    /// Records on days after the day of the *moment* are [planned](Status::Planned), the others
+    /// [eaten](Status::Eaten).
+    fn add(&self, meal: &str, timestamps: &[i64], moment: DateTime<Utc>) -> Result<()>;
+
+    /// Adds the records in one go, adding their meals to the store if necessary. Either all
+    /// records are added or none. The records keep their status.
+    fn add_records(&self, records: &[MealRecord], moment: DateTime<Utc>) -> Result<()>;
+
+    /// Returns the records matching the filter, ordered by date.
+    fn select(&self, filter: &RecordFilter) -> Result<Vec<MealRecord>>;
+
+    /// Removes the records matching the filter. Returns the removed records, ordered by date.
+    ///
+    /// Meals which are left without records are deleted, unless they have a cooldown, tags or
+    /// aliases.
+    fn remove(&self, filter: &RecordFilter, moment: DateTime<Utc>) -> Result<Vec<MealRecord>>;
+
+    /// Removes one record of the meal on the day of each of the records, in one go. Meals left
+    /// without records are deleted like by [`remove`](MealStore::remove). Returns the removed
+    /// records; records which cannot be found are skipped.
+    fn remove_records(
+        &self,
+        records: &[MealRecord],
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>>;
+
+    /// Renames the records of the meal *old_name* to *new_name*, optionally only in the given
+    /// period. Returns the renamed records as they were before renaming, ordered by date.
+    ///
+    /// Renaming without a period keeps the [`MealId`](crate::MealId) of the meal. If a meal named
+    /// *new_name* is already known, the records are merged into it. Renaming in a period deletes
+    /// the meal *old_name* if it is left without records, like [`remove`](MealStore::remove).
+    fn rename(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        option_period: Option<&Period>,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>>;
+
+    /// Returns one record per meal, with the date of its latest record. The result is ordered by
+    /// date, then by meal.
+    fn unique(&self) -> Result<Vec<MealRecord>>;
+
+    /// Returns all meals known to the store, ordered by their ids.
+    fn meals(&self) -> Result<Vec<Meal>>;
+
+    /// Returns the names of all meals known to the store with their aliases, ordered by the ids
+    /// of the meals. Unlike [`meals`](MealStore::meals), this reads neither cooldowns nor tags.
+    fn meal_names(&self) -> Result<Vec<(String, Vec<String>)>>;
+
+    /// Looks up a meal by its name.
+    fn meal(&self, name: &str) -> Result<Option<Meal>>;
+
+    /// Sets or clears the cooldown of a meal, in days.
+    ///
+    /// Returns [`Error::UnknownMeal`](crate::Error::UnknownMeal) if the store does not know the
+    /// meal.
+    fn set_cooldown(&self, meal: &str, option_days: Option<u32>) -> Result<()>;
+
+    /// Replaces the tags of a meal.
+    ///
+    /// Returns [`Error::UnknownMeal`](crate::Error::UnknownMeal) if the store does not know the
+    /// meal.
+    fn set_tags(&self, meal: &str, tags: &[String]) -> Result<()>;
+
+    /// Replaces the aliases of a meal. The store does not check whether an alias is taken.
+    ///
+    /// Returns [`Error::UnknownMeal`](crate::Error::UnknownMeal) if the store does not know the
+    /// meal.
+    fn set_aliases(&self, meal: &str, aliases: &[String]) -> Result<()>;
+
+    /// Merges the meals named in *merged* into *meal*: their records are moved to *meal* and they
+    /// are deleted. Then the tags and the aliases of *meal* are replaced. Either all of it is done
+    /// or nothing. Like the other changes of tags and aliases, merging is not journaled, but the
+    /// journal entries of the merged meals are changed to refer to *meal* instead.
+    ///
+    /// Returns [`Error::UnknownMeal`](crate::Error::UnknownMeal) if the store does not know one of
+    /// the meals.
+    fn merge(
+        &self,
+        meal: &str,
+        merged: &[String],
+        tags: &[String],
+        aliases: &[String],
+    ) -> Result<()>;
+
+    /// Sets the status of the records matching the filter. Returns the records whose status
+    /// changed, with their new status, ordered by date.
+    fn set_status(
+        &self,
+        filter: &RecordFilter,
+        status: Status,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>>;
+
+    /// Returns the timezone in which the days of the store begin and end.
+    fn timezone(&self) -> Timezone;
+
+    /// Changes the timezone of the store. Each record keeps its date, its timestamp is moved to
+    /// the start of that date in the new timezone.
+    fn set_timezone(&self, timezone: Timezone) -> Result<()>;
+
+    /// Returns the journal of the operations, ordered from the oldest to the latest.
+    fn journal(&self) -> Result<Vec<JournalEntry>>;
+
+    /// Performs the *operation*, which is the operation of the journal *entry* or its
+    /// [inverse](Operation::inverse), and marks the entry as *undone* or not. Meals which the
+    /// operation creates again get their former ids. Either all of it is done or nothing.
+    ///
+    /// Returns [`Error::ChangedRecords`](crate::Error::ChangedRecords) if one of the records of
+    /// the operation cannot be found anymore.
+    fn apply(&self, entry: &JournalEntry, operation: &Operation, undone: bool) -> Result<()>;
+}
+
+impl<S: MealStore + ?Sized> MealStore for Box<S> {
+    fn add(&self, meal: &str, timestamps: &[i64], moment: DateTime<Utc>) -> Result<()> {
+        (**self).add(meal, timestamps, moment)
+    }
+
+    fn add_records(&self, records: &[MealRecord], moment: DateTime<Utc>) -> Result<()> {
+        (**self).add_records(records, moment)
+    }
+
+    fn select(&self, filter: &RecordFilter) -> Result<Vec<MealRecord>> {
+        (**self).select(filter)
+    }
+
+    fn remove(&self, filter: &RecordFilter, moment: DateTime<Utc>) -> Result<Vec<MealRecord>> {
+        (**self).remove(filter, moment)
+    }
+
+    fn remove_records(
+        &self,
+        records: &[MealRecord],
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        (**self).remove_records(records, moment)
+    }
+
+    fn rename(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        option_period: Option<&Period>,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        (**self).rename(old_name, new_name, option_period, moment)
+    }
+
+    fn unique(&self) -> Result<Vec<MealRecord>> {
+        (**self).unique()
+    }
+
+    fn meals(&self) -> Result<Vec<Meal>> {
+        (**self).meals()
+    }
+
+    fn meal_names(&self) -> Result<Vec<(String, Vec<String>)>> {
+        (**self).meal_names()
+    }
+
+    fn meal(&self, name: &str) -> Result<Option<Meal>> {
+        (**self).meal(name)
+    }
+
+    fn set_cooldown(&self, meal: &str, option_days: Option<u32>) -> Result<()> {
+        (**self).set_cooldown(meal, option_days)
+    }
+
+    fn set_tags(&self, meal: &str, tags: &[String]) -> Result<()> {
+        (**self).set_tags(meal, tags)
+    }
+
+    fn set_aliases(&self, meal: &str, aliases: &[String]) -> Result<()> {
+        (**self).set_aliases(meal, aliases)
+    }
+
+    fn merge(
+        &self,
+        meal: &str,
+        merged: &[String],
+        tags: &[String],
+        aliases: &[String],
+    ) -> Result<()> {
+        (**self).merge(meal, merged, tags, aliases)
+    }
+
+    fn set_status(
+        &self,
+        filter: &RecordFilter,
+        status: Status,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        (**self).set_status(filter, status, moment)
+    }
+
+    fn timezone(&self) -> Timezone {
+        (**self).timezone()
+    }
+
+    fn set_timezone(&self, timezone: Timezone) -> Result<()> {
+        (**self).set_timezone(timezone)
+    }
+
+    fn journal(&self) -> Result<Vec<JournalEntry>> {
+        (**self).journal()
+    }
+
+    fn apply(&self, entry: &JournalEntry, operation: &Operation, undone: bool) -> Result<()> {
+        (**self).apply(entry, operation, undone)
+    }
+}
+
+/// Selects meal records in a [`MealStore`]. An empty filter matches all records.
+#[derive(Clone, Debug, Default)]
+pub struct RecordFilter {
+    /// Match only the records of this meal.
+    pub meal: Option<String>,
+    /// Match only the records in this period.
+    pub period: Option<Period>,
+    /// Match only the records with this status.
+    pub status: Option<Status>,
+}
+
+impl RecordFilter {
+    /// Returns true if the record matches the filter.
+    pub fn matches(&self, record: &MealRecord) -> bool {
+        let meal_matches = match self.meal {
+            None => true,
+            Some(ref meal) => *meal == record.meal(),
+        };
+        let period_matches = match self.period {
+            None => true,
+            Some(ref period) => period.contains_timestamp(record.timestamp()),
+        };
+        let status_matches = match self.status {
+            None => true,
+            Some(status) => status == record.status(),
+        };
+        meal_matches && period_matches && status_matches
+    }
+}
@@ -0,0 +1,702 @@
+//! In-memory backend
+
+use super::{MealStore, RecordFilter};
+use crate::{
+    Error, JournalEntry, Meal, MealId, MealRecord, Operation, Period, Result, Status, Timezone,
+};
+use chrono::{DateTime, Utc};
+use std::{cell::RefCell, fmt};
+use tracing::instrument;
+
+/// A record of a meal on a day.
+#[derive(Clone, Debug)]
+struct Occurrence {
+    meal_id: MealId,
+    timestamp: i64,
+    status: Status,
+}
+
+/// The data of a [`MemoryStore`], laid out like the tables of the [`SqliteStore`](super::SqliteStore).
+#[derive(Clone, Debug, Default)]
+struct Tables {
+    meals: Vec<Meal>,
+    /// Occurrences in the order in which they were added.
+    occurrences: Vec<Occurrence>,
+    last_meal_id: i64,
+    timezone: Timezone,
+    journal: Vec<JournalEntry>,
+}
+
+impl Tables {
+    fn meal(&self, name: &str) -> Option<&Meal> {
+        self.meals.iter().find(|meal| meal.name() == name)
+    }
+
+    fn meal_by_id(&self, meal_id: MealId) -> &Meal {
+        self.meals
+            .iter()
+            .find(|meal| meal.id() == meal_id)
+            .expect("occurrence refers to an unknown meal")
+    }
+
+    fn get_or_create_meal_id(&mut self, name: &str, created: i64) -> Result<MealId> {
+        if let Some(meal) = self.meal(name) {
+            return Ok(meal.id());
+        }
+        self.last_meal_id += 1;
+        let meal_id = MealId::new(self.last_meal_id);
+        self.meals
+            .push(Meal::new(meal_id, name, created, &self.timezone)?);
+        Ok(meal_id)
+    }
+
+    fn record(&self, occurrence: &Occurrence) -> Result<MealRecord> {
+        let meal = self.meal_by_id(occurrence.meal_id);
+        MealRecord::from_meal_and_timestamp_in(&meal.name(), occurrence.timestamp, &self.timezone)
+            .map(|record| record.with_status(occurrence.status))
+    }
+
+    /// Returns the indices of the occurrences matching the filter, ordered by date.
+    fn matching(&self, filter: &RecordFilter) -> Result<Vec<usize>> {
+        let mut indices = Vec::new();
+        for (index, occurrence) in self.occurrences.iter().enumerate() {
+            if filter.matches(&self.record(occurrence)?) {
+                indices.push(index);
+            }
+        }
+        // stable sort keeps the insertion order of records on the same day
+        indices.sort_by_key(|&index| self.occurrences[index].timestamp);
+        Ok(indices)
+    }
+
+    fn records(&self, indices: &[usize]) -> Result<Vec<MealRecord>> {
+        indices
+            .iter()
+            .map(|&index| self.record(&self.occurrences[index]))
+            .collect()
+    }
+
+    /// Returns the index of an occurrence of the meal on the day.
+    fn find(&self, meal_id: MealId, timestamp: i64) -> Option<usize> {
+        self.occurrences.iter().position(|occurrence| {
+            occurrence.meal_id == meal_id && occurrence.timestamp == timestamp
+        })
+    }
+
+    /// Fails if one of the records has a timestamp which cannot be a date in the timezone. Called
+    /// before the records change anything, so that a failing operation leaves the tables as they
+    /// were.
+    fn validate(&self, records: &[MealRecord]) -> Result<()> {
+        for record in records {
+            self.timezone.date_of(record.timestamp())?;
+        }
+        Ok(())
+    }
+
+    /// Adds an occurrence for each of the records. Returns the meals which had to be added for
+    /// them.
+    fn insert(&mut self, records: &[MealRecord]) -> Result<Vec<Meal>> {
+        self.validate(records)?;
+        let mut by_date: Vec<&MealRecord> = records.iter().collect();
+        by_date.sort_by_key(|record| record.timestamp());
+        let mut created_meals = Vec::new();
+        for record in by_date {
+            let is_new = self.meal(&record.meal()).is_none();
+            let meal_id = self.get_or_create_meal_id(&record.meal(), record.timestamp())?;
+            if is_new {
+                created_meals.push(self.meal_by_id(meal_id).clone());
+            }
+            self.occurrences.push(Occurrence {
+                meal_id,
+                timestamp: record.timestamp(),
+                status: record.status(),
+            });
+        }
+        Ok(created_meals)
+    }
+
+    /// Deletes one occurrence for each of the records. Returns the number of deleted occurrences.
+    fn delete(&mut self, records: &[MealRecord]) -> usize {
+        let mut count = 0;
+        for record in records {
+            let option_meal_id = self.meal(&record.meal()).map(|meal| meal.id());
+            let option_index =
+                option_meal_id.and_then(|meal_id| self.find(meal_id, record.timestamp()));
+            if let Some(index) = option_index {
+                self.occurrences.remove(index);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Gives one occurrence with the status of each of the records the status at the same position
+    /// of *new*. Returns the number of changed occurrences.
+    fn change_status(&mut self, records: &[MealRecord], new: &[Status]) -> usize {
+        let mut count = 0;
+        for (record, &status) in records.iter().zip(new) {
+            let Some(meal_id) = self.meal(&record.meal()).map(|meal| meal.id()) else {
+                continue;
+            };
+            let option_occurrence = self.occurrences.iter_mut().find(|occurrence| {
+                occurrence.meal_id == meal_id
+                    && occurrence.timestamp == record.timestamp()
+                    && occurrence.status == record.status()
+            });
+            if let Some(occurrence) = option_occurrence {
+                occurrence.status = status;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Deletes those of the meals which have no occurrences left, unless they have a cooldown,
+    /// tags or aliases. Returns the deleted meals.
+    fn delete_meals_without_occurrences(&mut self, meal_ids: &[MealId]) -> Vec<Meal> {
+        let mut deleted_meals = Vec::new();
+        for &meal_id in meal_ids {
+            let meal = self.meal_by_id(meal_id).clone();
+            if self.delete_meal_without_occurrences(meal_id, false) {
+                deleted_meals.push(meal);
+            }
+        }
+        deleted_meals
+    }
+
+    /// Deletes the meal if it has no occurrences. Unless *settings_too*, a meal with a cooldown,
+    /// tags or aliases is kept as well. Returns true if the meal was deleted.
+    fn delete_meal_without_occurrences(&mut self, meal_id: MealId, settings_too: bool) -> bool {
+        let has_occurrences = self
+            .occurrences
+            .iter()
+            .any(|occurrence| occurrence.meal_id == meal_id);
+        let has_settings = self
+            .meals
+            .iter()
+            .any(|meal| meal.id() == meal_id && meal.has_settings());
+        if has_occurrences || (has_settings && !settings_too) {
+            return false;
+        }
+        self.meals.retain(|meal| meal.id() != meal_id);
+        true
+    }
+
+    /// Adds the meal with its id, unless a meal of its name is known. Ids are never reused, so the
+    /// id of a deleted meal is free.
+    fn restore_meal(&mut self, meal: &Meal) {
+        if self.meal(&meal.name()).is_some() {
+            return;
+        }
+        let index = self
+            .meals
+            .partition_point(|known_meal| known_meal.id() < meal.id());
+        self.meals.insert(index, meal.clone());
+    }
+
+    /// Moves one occurrence of the meal *from* to the meal *to* for each of the records. If these
+    /// are all the occurrences of *from* and *to* is not known, the meal is renamed instead, so
+    /// that it keeps its id. Returns the number of moved occurrences.
+    fn transfer(&mut self, from: &str, to: &str, records: &[MealRecord]) -> Result<usize> {
+        let Some(from_meal_id) = self.meal(from).map(|meal| meal.id()) else {
+            return Ok(0);
+        };
+        let count = self
+            .occurrences
+            .iter()
+            .filter(|occurrence| occurrence.meal_id == from_meal_id)
+            .count();
+        if self.meal(to).is_none() && count == records.len() {
+            if let Some(meal) = self.meals.iter_mut().find(|m| m.id() == from_meal_id) {
+                meal.set_name(to);
+            }
+            return Ok(count);
+        }
+        let Some(created) = records.iter().map(|record| record.timestamp()).min() else {
+            return Ok(0);
+        };
+        let to_meal_id = self.get_or_create_meal_id(to, created)?;
+        let mut moved = 0;
+        for record in records {
+            if let Some(index) = self.find(from_meal_id, record.timestamp()) {
+                self.occurrences[index].meal_id = to_meal_id;
+                moved += 1;
+            }
+        }
+        Ok(moved)
+    }
+
+    /// Performs the operation of a journal entry. Fails if one of its records cannot be found.
+    fn perform(&mut self, entry: &JournalEntry, operation: &Operation) -> Result<()> {
+        let changed = match operation {
+            Operation::Add {
+                records,
+                created_meals,
+            } => {
+                for meal in created_meals {
+                    self.restore_meal(meal);
+                }
+                self.insert(records)?;
+                records.len()
+            }
+            Operation::Remove {
+                records,
+                deleted_meals,
+            } => {
+                let count = self.delete(records);
+                for meal in deleted_meals {
+                    if let Some(meal_id) = self.meal(&meal.name()).map(|meal| meal.id()) {
+                        self.delete_meal_without_occurrences(meal_id, true);
+                    }
+                }
+                count
+            }
+            Operation::Rename {
+                old_name,
+                new_name,
+                records,
+                created_meal,
+                deleted_meal,
+            } => {
+                self.validate(records)?;
+                if let Some(meal) = created_meal {
+                    self.restore_meal(meal);
+                }
+                let count = self.transfer(old_name, new_name, records)?;
+                let option_old_meal_id = self.meal(old_name).map(|meal| meal.id());
+                if let (Some(_), Some(old_meal_id)) = (deleted_meal, option_old_meal_id) {
+                    self.delete_meal_without_occurrences(old_meal_id, true);
+                }
+                count
+            }
+            Operation::SetStatus { records, new } => self.change_status(records, new),
+        };
+        match changed < operation.records().len() {
+            true => Err(Error::ChangedRecords(entry.operation().to_string())),
+            false => Ok(()),
+        }
+    }
+
+    /// Appends the operation to the journal, discarding the undone entries.
+    fn journal(&mut self, moment: DateTime<Utc>, operation: Operation) {
+        if operation.records().is_empty() {
+            return;
+        }
+        self.journal.retain(|entry| !entry.is_undone());
+        let id = self.journal.last().map_or(1, |entry| entry.id() + 1);
+        self.journal
+            .push(JournalEntry::new(id, moment, operation, false));
+    }
+}
+
+/// Keeps meal records in memory for as long as it lives. Useful for testing.
+///
+/// Example:
+/// ```
+/// use libmrot::{MemoryStore, Storage};
+///
+/// let storage = Storage::with_store(MemoryStore::default());
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    tables: RefCell<Tables>,
+}
+
+impl MealStore for MemoryStore {
+    #[instrument]
+    fn add(&self, meal: &str, timestamps: &[i64], moment: DateTime<Utc>) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let today = tables.timezone.date_of(moment.timestamp())?;
+        let records = timestamps
+            .iter()
+            .map(|&timestamp| {
+                let record =
+                    MealRecord::from_meal_and_timestamp_in(meal, timestamp, &tables.timezone)?;
+                let status = Status::of_new_record(record.naive_date(), today);
+                Ok(record.with_status(status))
+            })
+            .collect::<Result<Vec<MealRecord>>>()?;
+        let created_meals = tables.insert(&records)?;
+        tables.journal(
+            moment,
+            Operation::Add {
+                records,
+                created_meals,
+            },
+        );
+        Ok(())
+    }
+
+    #[instrument(skip(records))]
+    fn add_records(&self, records: &[MealRecord], moment: DateTime<Utc>) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let created_meals = tables.insert(records)?;
+        tables.journal(
+            moment,
+            Operation::Add {
+                records: records.to_vec(),
+                created_meals,
+            },
+        );
+        Ok(())
+    }
+
+    #[instrument(level = "trace")]
+    fn select(&self, filter: &RecordFilter) -> Result<Vec<MealRecord>> {
+        let tables = self.tables.borrow();
+        let indices = tables.matching(filter)?;
+        tables.records(&indices)
+    }
+
+    #[instrument]
+    fn remove(&self, filter: &RecordFilter, moment: DateTime<Utc>) -> Result<Vec<MealRecord>> {
+        let mut tables = self.tables.borrow_mut();
+        let indices = tables.matching(filter)?;
+        let records = tables.records(&indices)?;
+        let mut meal_ids = Vec::new();
+        for &index in indices.iter() {
+            let meal_id = tables.occurrences[index].meal_id;
+            if !meal_ids.contains(&meal_id) {
+                meal_ids.push(meal_id);
+            }
+        }
+        let mut index = 0;
+        tables.occurrences.retain(|_| {
+            let keep = !indices.contains(&index);
+            index += 1;
+            keep
+        });
+        let deleted_meals = tables.delete_meals_without_occurrences(&meal_ids);
+        tables.journal(
+            moment,
+            Operation::Remove {
+                records: records.clone(),
+                deleted_meals,
+            },
+        );
+        Ok(records)
+    }
+
+    #[instrument]
+    fn remove_records(
+        &self,
+        records: &[MealRecord],
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        let mut tables = self.tables.borrow_mut();
+        let mut removed = Vec::new();
+        let mut meal_ids = Vec::new();
+        for record in records {
+            let Some(meal_id) = tables.meal(&record.meal()).map(|meal| meal.id()) else {
+                continue;
+            };
+            if tables.delete(std::slice::from_ref(record)) == 0 {
+                continue;
+            }
+            if !meal_ids.contains(&meal_id) {
+                meal_ids.push(meal_id);
+            }
+            removed.push(record.clone());
+        }
+        let deleted_meals = tables.delete_meals_without_occurrences(&meal_ids);
+        tables.journal(
+            moment,
+            Operation::Remove {
+                records: removed.clone(),
+                deleted_meals,
+            },
+        );
+        Ok(removed)
+    }
+
+    #[instrument]
+    fn rename(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        option_period: Option<&Period>,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        let mut tables = self.tables.borrow_mut();
+        let filter = RecordFilter {
+            meal: Some(old_name.to_string()),
+            period: option_period.cloned(),
+            status: None,
+        };
+        let indices = tables.matching(&filter)?;
+        let records = tables.records(&indices)?;
+        let Some(old_meal) = tables.meal(old_name).cloned() else {
+            return Ok(records);
+        };
+        let option_new_meal_id = tables.meal(new_name).map(|meal| meal.id());
+        let mut created_meal = None;
+        let mut deleted_meal = None;
+        match (option_period, option_new_meal_id) {
+            // the whole meal gets a new name, the records keep referring to it
+            (None, None) => {
+                if let Some(meal) = tables.meals.iter_mut().find(|m| m.id() == old_meal.id()) {
+                    meal.set_name(new_name);
+                }
+            }
+            // all records go to an already existing meal which replaces the old one
+            (None, Some(new_meal_id)) if new_meal_id != old_meal.id() => {
+                for &index in indices.iter() {
+                    tables.occurrences[index].meal_id = new_meal_id;
+                }
+                tables.meals.retain(|meal| meal.id() != old_meal.id());
+                deleted_meal = Some(Box::new(old_meal));
+            }
+            (None, Some(_)) => return Ok(records),
+            // only the records in the period are moved to the (possibly new) meal
+            (Some(_), _) => {
+                let Some(first_record) = records.first() else {
+                    return Ok(records);
+                };
+                let new_meal_id =
+                    tables.get_or_create_meal_id(new_name, first_record.timestamp())?;
+                if option_new_meal_id.is_none() {
+                    created_meal = tables.meal(new_name).cloned().map(Box::new);
+                }
+                for &index in indices.iter() {
+                    tables.occurrences[index].meal_id = new_meal_id;
+                }
+                if tables.delete_meal_without_occurrences(old_meal.id(), false) {
+                    deleted_meal = Some(Box::new(old_meal));
+                }
+            }
+        }
+        tables.journal(
+            moment,
+            Operation::Rename {
+                old_name: old_name.to_string(),
+                new_name: new_name.to_string(),
+                records: records.clone(),
+                created_meal,
+                deleted_meal,
+            },
+        );
+        Ok(records)
+    }
+
+    #[instrument]
+    fn unique(&self) -> Result<Vec<MealRecord>> {
+        let tables = self.tables.borrow();
+        let mut records = Vec::new();
+        for meal in tables.meals.iter() {
+            let last_occurrence = tables
+                .occurrences
+                .iter()
+                .filter(|occurrence| occurrence.meal_id == meal.id())
+                .max_by_key(|occurrence| occurrence.timestamp);
+            if let Some(occurrence) = last_occurrence {
+                records.push(tables.record(occurrence)?);
+            }
+        }
+        records.sort_by(|a, b| {
+            a.timestamp()
+                .cmp(&b.timestamp())
+                .then_with(|| a.meal().cmp(&b.meal()))
+        });
+        Ok(records)
+    }
+
+    #[instrument]
+    fn meals(&self) -> Result<Vec<Meal>> {
+        Ok(self.tables.borrow().meals.clone())
+    }
+
+    #[instrument]
+    fn meal_names(&self) -> Result<Vec<(String, Vec<String>)>> {
+        Ok(self
+            .tables
+            .borrow()
+            .meals
+            .iter()
+            .map(|meal| (meal.name(), meal.aliases()))
+            .collect())
+    }
+
+    #[instrument]
+    fn meal(&self, name: &str) -> Result<Option<Meal>> {
+        Ok(self.tables.borrow().meal(name).cloned())
+    }
+
+    #[instrument]
+    fn set_cooldown(&self, meal: &str, option_days: Option<u32>) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let known_meal = tables
+            .meals
+            .iter_mut()
+            .find(|known_meal| known_meal.name() == meal)
+            .ok_or(Error::UnknownMeal(meal.to_string()))?;
+        *known_meal = known_meal.clone().with_cooldown(option_days);
+        Ok(())
+    }
+
+    #[instrument]
+    fn set_tags(&self, meal: &str, tags: &[String]) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let known_meal = tables
+            .meals
+            .iter_mut()
+            .find(|known_meal| known_meal.name() == meal)
+            .ok_or(Error::UnknownMeal(meal.to_string()))?;
+        *known_meal = known_meal.clone().with_tags(tags.to_vec());
+        Ok(())
+    }
+
+    #[instrument]
+    fn set_aliases(&self, meal: &str, aliases: &[String]) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let known_meal = tables
+            .meals
+            .iter_mut()
+            .find(|known_meal| known_meal.name() == meal)
+            .ok_or(Error::UnknownMeal(meal.to_string()))?;
+        *known_meal = known_meal.clone().with_aliases(aliases.to_vec());
+        Ok(())
+    }
+
+    #[instrument]
+    fn merge(
+        &self,
+        meal: &str,
+        merged: &[String],
+        tags: &[String],
+        aliases: &[String],
+    ) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let meal_id = tables
+            .meal(meal)
+            .ok_or(Error::UnknownMeal(meal.to_string()))?
+            .id();
+        let merged_meal_ids = merged
+            .iter()
+            .map(|merged_meal| {
+                tables
+                    .meal(merged_meal)
+                    .map(|known_meal| known_meal.id())
+                    .ok_or(Error::UnknownMeal(merged_meal.to_string()))
+            })
+            .collect::<Result<Vec<MealId>>>()?;
+        for occurrence in tables.occurrences.iter_mut() {
+            if merged_meal_ids.contains(&occurrence.meal_id) {
+                occurrence.meal_id = meal_id;
+            }
+        }
+        tables
+            .meals
+            .retain(|known_meal| !merged_meal_ids.contains(&known_meal.id()));
+        if let Some(known_meal) = tables.meals.iter_mut().find(|m| m.id() == meal_id) {
+            *known_meal = known_meal
+                .clone()
+                .with_tags(tags.to_vec())
+                .with_aliases(aliases.to_vec());
+        }
+        let journal = tables
+            .journal
+            .iter()
+            .map(|entry| {
+                let operation = merged
+                    .iter()
+                    .fold(entry.operation().clone(), |operation, merged_meal| {
+                        operation.with_meal_merged(merged_meal, meal)
+                    });
+                JournalEntry::new(entry.id(), entry.performed(), operation, entry.is_undone())
+            })
+            .collect();
+        tables.journal = journal;
+        Ok(())
+    }
+
+    #[instrument]
+    fn set_status(
+        &self,
+        filter: &RecordFilter,
+        status: Status,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        let mut tables = self.tables.borrow_mut();
+        let mut records = Vec::new();
+        let mut changed = Vec::new();
+        for index in tables.matching(filter)? {
+            if tables.occurrences[index].status != status {
+                records.push(tables.record(&tables.occurrences[index])?);
+                tables.occurrences[index].status = status;
+                changed.push(tables.record(&tables.occurrences[index])?);
+            }
+        }
+        let new = vec![status; records.len()];
+        tables.journal(moment, Operation::SetStatus { records, new });
+        Ok(changed)
+    }
+
+    fn timezone(&self) -> Timezone {
+        self.tables.borrow().timezone
+    }
+
+    #[instrument]
+    fn set_timezone(&self, timezone: Timezone) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let old_timezone = tables.timezone;
+        let occurrences = tables
+            .occurrences
+            .iter()
+            .map(|occurrence| {
+                let date = old_timezone.date_of(occurrence.timestamp)?;
+                Ok(Occurrence {
+                    meal_id: occurrence.meal_id,
+                    timestamp: timezone.start_of_day(&date),
+                    status: occurrence.status,
+                })
+            })
+            .collect::<Result<Vec<Occurrence>>>()?;
+        let meals = tables
+            .meals
+            .iter()
+            .map(|meal| meal.reanchored(&timezone))
+            .collect::<Result<Vec<Meal>>>()?;
+        let journal = tables
+            .journal
+            .iter()
+            .map(|entry| {
+                Ok(JournalEntry::new(
+                    entry.id(),
+                    entry.performed(),
+                    entry.operation().reanchored(&timezone)?,
+                    entry.is_undone(),
+                ))
+            })
+            .collect::<Result<Vec<JournalEntry>>>()?;
+        tables.occurrences = occurrences;
+        tables.meals = meals;
+        tables.journal = journal;
+        tables.timezone = timezone;
+        Ok(())
+    }
+
+    #[instrument]
+    fn journal(&self) -> Result<Vec<JournalEntry>> {
+        Ok(self.tables.borrow().journal.clone())
+    }
+
+    #[instrument]
+    fn apply(&self, entry: &JournalEntry, operation: &Operation, undone: bool) -> Result<()> {
+        let mut tables = self.tables.borrow_mut();
+        let mut changed_tables = tables.clone();
+        changed_tables.perform(entry, operation)?;
+        for journal_entry in changed_tables.journal.iter_mut() {
+            if journal_entry.id() == entry.id() {
+                *journal_entry = journal_entry.with_undone(undone);
+            }
+        }
+        *tables = changed_tables;
+        Ok(())
+    }
+}
+
+impl fmt::Display for MemoryStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ":memory:")
+    }
+}
@@ -0,0 +1,1264 @@
+//! SQLite backend
+
+use super::{MealStore, RecordFilter};
+use crate::{
+    error::Error,
+    migration::{merge_journal_meal, migrate, schema_version, SCHEMA_VERSION},
+    JournalEntry, Meal, MealId, MealRecord, Operation, Period, Result, Status, Timezone,
+};
+use chrono::{DateTime, Utc};
+use sqlite::{Connection, OpenFlags, State, Statement, Value};
+use std::{cell::Cell, fmt, path::Path, str::FromStr};
+use tracing::{instrument, trace};
+
+/// Keeps meal records in an SQLite database.
+pub struct SqliteStore {
+    connection: Connection,
+    path_string: String,
+    /// The timezone from the `settings` table.
+    timezone: Cell<Timezone>,
+}
+
+impl SqliteStore {
+    /// Path to an in-memory database.
+    const MEMORY: &str = ":memory:";
+
+    /// Opens the database in the given path. Given a path to a non-existing file, this will try
+    /// to create a new database in that path and then open it. The schema of an existing database
+    /// is migrated to the current [`SCHEMA_VERSION`](crate::SCHEMA_VERSION).
+    ///
+    /// The special path `:memory:` opens a database which only lives in memory as long as the
+    /// returned struct.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::SqliteStore;
+    ///
+    /// let store = SqliteStore::open(":memory:").unwrap();
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::SchemaTooNew`] if the database was created by a newer version of libmrot.
+    #[instrument]
+    pub fn open(path: &str) -> Result<Self> {
+        Self::open_at(path, Utc::now())
+    }
+
+    /// Like [`SqliteStore::open`], but an existing database is migrated as of the moment *now*
+    /// instead of the current time, e.g. the moment of a [`FixedClock`](crate::FixedClock).
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::SqliteStore;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let now = Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap();
+    /// let store = SqliteStore::open_at(":memory:", now).unwrap();
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::SchemaTooNew`] if the database was created by a newer version of libmrot.
+    #[instrument]
+    pub fn open_at(path: &str, now: DateTime<Utc>) -> Result<Self> {
+        trace!(%path, "Open database");
+        let connection: Connection = match path {
+            Self::MEMORY => sqlite::open(path)?,
+            _ => {
+                trace!(%path, "Path is a real file");
+                if Path::new(path).try_exists()? {
+                    sqlite::open(path)?
+                } else {
+                    Self::new(path)?
+                }
+            }
+        };
+        migrate(&connection, now)?;
+        let timezone = Cell::new(Self::read_timezone(&connection)?);
+        let path_string = String::from(path);
+        Ok(Self {
+            connection,
+            path_string,
+            timezone,
+        })
+    }
+
+    /// Opens the existing database in the given path without creating, migrating or otherwise
+    /// changing it, e.g. to read meal names for shell completion. Every change through the
+    /// returned store fails.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use libmrot::SqliteStore;
+    ///
+    /// let store = SqliteStore::open_read_only("./path/to/my_storage").unwrap();
+    /// ```
+    ///
+    /// Error:
+    ///
+    /// Returns [`Error::SchemaOutdated`] if the database must be migrated first and
+    /// [`Error::SchemaTooNew`] if it was created by a newer version of libmrot.
+    #[instrument]
+    pub fn open_read_only(path: &str) -> Result<Self> {
+        trace!(%path, "Open database read-only");
+        let connection = Connection::open_with_flags(path, OpenFlags::new().with_read_only())?;
+        let version = schema_version(&connection)?;
+        if version < SCHEMA_VERSION {
+            return Err(Error::SchemaOutdated(version));
+        }
+        if version > SCHEMA_VERSION {
+            return Err(Error::SchemaTooNew(version));
+        }
+        let timezone = Cell::new(Self::read_timezone(&connection)?);
+        Ok(Self {
+            connection,
+            path_string: String::from(path),
+            timezone,
+        })
+    }
+
+    /// Returns the timezone of the database in the given path without creating, migrating or
+    /// otherwise changing it. This is the default timezone if there is no database in the path
+    /// yet or if the database was created before the timezone was configurable.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{SqliteStore, Timezone};
+    ///
+    /// assert_eq!(SqliteStore::timezone_of("./no/such/storage").unwrap(), Timezone::default());
+    /// ```
+    #[instrument]
+    pub fn timezone_of(path: &str) -> Result<Timezone> {
+        if path == Self::MEMORY || !Path::new(path).try_exists()? {
+            return Ok(Timezone::default());
+        }
+        let connection = Connection::open_with_flags(path, OpenFlags::new().with_read_only())?;
+        let query = "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'settings'";
+        let mut statement = connection.prepare(query)?;
+        match statement.next()? {
+            State::Row => Self::read_timezone(&connection),
+            State::Done => Ok(Timezone::default()),
+        }
+    }
+
+    fn read_timezone(connection: &Connection) -> Result<Timezone> {
+        let mut statement =
+            connection.prepare("SELECT value FROM settings WHERE key = 'timezone'")?;
+        match statement.next()? {
+            State::Row => Timezone::from_str(&statement.read::<String, _>("value")?),
+            State::Done => Ok(Timezone::default()),
+        }
+    }
+
+    /// Creates a new database.
+    #[instrument]
+    fn new(path: &str) -> Result<Connection> {
+        trace!(%path, "Creating new database");
+        std::fs::create_dir_all(Path::new(path).parent().ok_or(Error::NoParentDirectory)?)?;
+        let connection = sqlite::open(path)?;
+        Ok(connection)
+    }
+
+    /// Returns the schema version of the database.
+    #[instrument]
+    pub fn schema_version(&self) -> Result<i64> {
+        schema_version(&self.connection)
+    }
+
+    fn read_meal(&self, statement: &Statement) -> Result<Meal> {
+        let id = MealId::new(statement.read::<i64, _>("id")?);
+        let name = statement.read::<String, _>("name")?;
+        let created = statement.read::<i64, _>("created")?;
+        let cooldown = statement.read::<Option<i64>, _>("cooldown")?;
+        Ok(Meal::new(id, &name, created, &self.timezone.get())?
+            .with_cooldown(cooldown.map(|days| days as u32))
+            .with_tags(self.read_tags(id)?)
+            .with_aliases(self.read_aliases(id)?))
+    }
+
+    fn read_tags(&self, meal_id: MealId) -> Result<Vec<String>> {
+        let query = "SELECT tag FROM dish_tags WHERE dish_id = :dish_id ORDER BY tag ASC";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((":dish_id", meal_id.get()))?;
+        let mut tags = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            tags.push(statement.read::<String, _>("tag")?);
+        }
+        Ok(tags)
+    }
+
+    fn read_aliases(&self, meal_id: MealId) -> Result<Vec<String>> {
+        let query = "SELECT alias FROM dish_aliases WHERE dish_id = :dish_id ORDER BY alias ASC";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((":dish_id", meal_id.get()))?;
+        let mut aliases = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            aliases.push(statement.read::<String, _>("alias")?);
+        }
+        Ok(aliases)
+    }
+
+    /// Replaces the tags of the dish. Must be called within a transaction.
+    fn write_tags(&self, meal_id: MealId, tags: &[String]) -> Result<()> {
+        let mut statement = self
+            .connection
+            .prepare("DELETE FROM dish_tags WHERE dish_id = :dish_id")?;
+        statement.bind((":dish_id", meal_id.get()))?;
+        statement.next()?;
+        let query = "INSERT OR IGNORE INTO dish_tags (dish_id, tag) VALUES (:dish_id, :tag)";
+        for tag in tags {
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":dish_id", meal_id.get().into()),
+                (":tag", tag.as_str().into()),
+            ])?;
+            statement.next()?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the aliases of the dish. Must be called within a transaction.
+    fn write_aliases(&self, meal_id: MealId, aliases: &[String]) -> Result<()> {
+        let mut statement = self
+            .connection
+            .prepare("DELETE FROM dish_aliases WHERE dish_id = :dish_id")?;
+        statement.bind((":dish_id", meal_id.get()))?;
+        statement.next()?;
+        let query =
+            "INSERT OR REPLACE INTO dish_aliases (alias, dish_id) VALUES (:alias, :dish_id)";
+        for alias in aliases {
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":alias", alias.as_str().into()),
+                (":dish_id", meal_id.get().into()),
+            ])?;
+            statement.next()?;
+        }
+        Ok(())
+    }
+
+    fn record(&self, meal: &str, timestamp: i64) -> Result<MealRecord> {
+        MealRecord::from_meal_and_timestamp_in(meal, timestamp, &self.timezone.get())
+    }
+
+    /// Reads the record from the `meal`, `date` and `status` columns of the statement's row.
+    fn read_record(&self, statement: &Statement) -> Result<MealRecord> {
+        let timestamp = statement.read::<i64, _>("date")?;
+        let meal = statement.read::<String, _>("meal")?;
+        let status = statement.read::<String, _>("status")?.parse()?;
+        Ok(self.record(&meal, timestamp)?.with_status(status))
+    }
+
+    /// Moves the day timestamps in the column of the table from the start of the day in the old
+    /// timezone to the start of the same day in the new timezone.
+    fn reanchor_dates(
+        &self,
+        table: &str,
+        column: &str,
+        old_timezone: &Timezone,
+        new_timezone: &Timezone,
+    ) -> Result<()> {
+        let select_query = format!("SELECT id, {column} FROM {table}");
+        let mut select_statement = self.connection.prepare(select_query)?;
+        let mut updates = Vec::new();
+        while let State::Row = select_statement.next()? {
+            let id = select_statement.read::<i64, _>("id")?;
+            let timestamp = select_statement.read::<i64, _>(column)?;
+            let date = old_timezone.date_of(timestamp)?;
+            updates.push((id, new_timezone.start_of_day(&date)));
+        }
+        let update_query = format!("UPDATE {table} SET {column} = :timestamp WHERE id = :id");
+        let mut update_statement = self.connection.prepare(update_query)?;
+        for (id, timestamp) in updates {
+            update_statement.reset()?;
+            update_statement.bind_iter::<_, (_, Value)>([
+                (":timestamp", timestamp.into()),
+                (":id", id.into()),
+            ])?;
+            update_statement.next()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the id of the meal, adding the meal to the database if it is not known yet.
+    fn get_or_create_meal_id(&self, name: &str, created: i64) -> Result<MealId> {
+        match self.meal(name)? {
+            Some(meal) => Ok(meal.id()),
+            None => Ok(self.create_meal(name, created)?.id()),
+        }
+    }
+
+    /// Adds a meal to the database and returns it.
+    fn create_meal(&self, name: &str, created: i64) -> Result<Meal> {
+        let query = "INSERT INTO dishes (name, created) VALUES (:name, :created)";
+        let mut statement = self.connection.prepare(query)?;
+        statement
+            .bind_iter::<_, (_, Value)>([(":name", name.into()), (":created", created.into())])?;
+        statement.next()?;
+        Meal::new(
+            MealId::new(self.last_insert_rowid()?),
+            name,
+            created,
+            &self.timezone.get(),
+        )
+    }
+
+    fn update_meal_name(&self, meal_id: MealId, name: &str) -> Result<()> {
+        let query = "UPDATE dishes SET name = :name WHERE id = :id";
+        let mut statement = self.connection.prepare(query)?;
+        statement
+            .bind_iter::<_, (_, Value)>([(":name", name.into()), (":id", meal_id.get().into())])?;
+        statement.next()?;
+        Ok(())
+    }
+
+    fn delete_meal(&self, meal_id: MealId) -> Result<()> {
+        let mut statement = self
+            .connection
+            .prepare("DELETE FROM dish_tags WHERE dish_id = :id")?;
+        statement.bind((":id", meal_id.get()))?;
+        statement.next()?;
+        let mut statement = self
+            .connection
+            .prepare("DELETE FROM dish_aliases WHERE dish_id = :id")?;
+        statement.bind((":id", meal_id.get()))?;
+        statement.next()?;
+        let mut statement = self
+            .connection
+            .prepare("DELETE FROM dishes WHERE id = :id")?;
+        statement.bind((":id", meal_id.get()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Deletes the dish if it has no occurrences. Unless *settings_too*, a dish with a cooldown,
+    /// tags or aliases is kept as well. Returns true if the dish was deleted.
+    fn delete_meal_without_occurrences(&self, meal: &Meal, settings_too: bool) -> Result<bool> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT COUNT(*) FROM occurrences WHERE dish_id = :dish_id")?;
+        statement.bind((":dish_id", meal.id().get()))?;
+        statement.next()?;
+        if statement.read::<i64, _>(0)? > 0 || (meal.has_settings() && !settings_too) {
+            return Ok(false);
+        }
+        self.delete_meal(meal.id())?;
+        Ok(true)
+    }
+
+    /// Adds the dish with its id, settings and aliases, unless a dish of its name is known. If
+    /// another dish has taken its id meanwhile, the dish gets a new id.
+    fn restore_meal(&self, meal: &Meal) -> Result<()> {
+        if self.meal(&meal.name())?.is_some() {
+            return Ok(());
+        }
+        let mut statement = self
+            .connection
+            .prepare("SELECT COUNT(*) FROM dishes WHERE id = :id")?;
+        statement.bind((":id", meal.id().get()))?;
+        statement.next()?;
+        let id = match statement.read::<i64, _>(0)? {
+            0 => Value::from(meal.id().get()),
+            _ => Value::Null,
+        };
+        let cooldown = match meal.cooldown() {
+            Some(days) => Value::Integer(i64::from(days)),
+            None => Value::Null,
+        };
+        let query = "INSERT INTO dishes (id, name, created, cooldown) \
+            VALUES (:id, :name, :created, :cooldown)";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind_iter::<_, (_, Value)>([
+            (":id", id),
+            (":name", meal.name().into()),
+            (":created", meal.created_timestamp().into()),
+            (":cooldown", cooldown),
+        ])?;
+        statement.next()?;
+        let meal_id = MealId::new(self.last_insert_rowid()?);
+        self.write_tags(meal_id, &meal.tags())?;
+        self.write_aliases(meal_id, &meal.aliases())
+    }
+
+    fn move_records(
+        &self,
+        meal_id: MealId,
+        condition: &str,
+        condition_params: &[(&str, Value)],
+    ) -> Result<()> {
+        let action_clause = "UPDATE occurrences SET dish_id = :dish_id";
+        let action_params: Vec<(&str, Value)> = vec![(":dish_id", meal_id.get().into())];
+        self.manipulate_records(action_clause, &action_params, condition, condition_params)
+    }
+
+    /// Translates the filter into a `WHERE` clause on the `records` view and its parameters.
+    fn condition(filter: &RecordFilter) -> (String, Vec<(&'static str, Value)>) {
+        let mut clauses = Vec::new();
+        let mut params: Vec<(&str, Value)> = Vec::new();
+        if let Some(ref meal) = filter.meal {
+            clauses.push("meal = :meal");
+            params.push((":meal", meal.as_str().into()));
+        }
+        if let Some(ref period) = filter.period {
+            clauses.push("date >= :start AND date <= :end");
+            params.push((":start", period.first_day_timestamp().into()));
+            params.push((":end", period.last_day_timestamp().into()));
+        }
+        if let Some(status) = filter.status {
+            clauses.push("status = :status");
+            params.push((":status", status.name().into()));
+        }
+        let condition = match clauses.is_empty() {
+            true => String::new(),
+            false => format!("WHERE {}", clauses.join(" AND ")),
+        };
+        (condition, params)
+    }
+
+    fn select_records(
+        &self,
+        condition: &str,
+        selection_params: &[(&str, Value)],
+    ) -> Result<Vec<MealRecord>> {
+        let select_query = format!(
+            "SELECT date, meal, status FROM records {} ORDER BY date ASC, id ASC",
+            condition
+        );
+        let mut select_statement = self.connection.prepare(select_query)?;
+        for &(name, ref value) in selection_params {
+            select_statement.bind((name, value))?;
+        }
+
+        let mut records = Vec::new();
+        while let Ok(State::Row) = select_statement.next() {
+            records.push(self.read_record(&select_statement)?);
+        }
+
+        Ok(records)
+    }
+
+    fn manipulate_records(
+        &self,
+        action_clause: &str,
+        action_params: &[(&str, Value)],
+        condition: &str,
+        condition_params: &[(&str, Value)],
+    ) -> Result<()> {
+        let query = format!(
+            "{} WHERE id IN (SELECT id FROM records {})",
+            action_clause, condition
+        );
+        let mut statement = self.connection.prepare(query)?;
+        for &(name, ref value) in action_params.iter().chain(condition_params) {
+            statement.bind((name, value))?;
+        }
+        statement.next()?;
+        Ok(())
+    }
+
+    fn last_insert_rowid(&self) -> Result<i64> {
+        let mut statement = self.connection.prepare("SELECT last_insert_rowid()")?;
+        statement.next()?;
+        Ok(statement.read::<i64, _>(0)?)
+    }
+
+    /// Adds an occurrence for each of the records. Returns the meals which had to be added for
+    /// them.
+    fn insert_records(&self, records: &[MealRecord]) -> Result<Vec<Meal>> {
+        let mut by_date: Vec<&MealRecord> = records.iter().collect();
+        by_date.sort_by_key(|record| record.timestamp());
+        let query = "INSERT INTO occurrences (dish_id, date, status) \
+            VALUES (:dish_id, :date, :status)";
+        let mut created_meals = Vec::new();
+        for record in by_date {
+            let meal_id = match self.meal(&record.meal())? {
+                Some(meal) => meal.id(),
+                None => {
+                    let meal = self.create_meal(&record.meal(), record.timestamp())?;
+                    let meal_id = meal.id();
+                    created_meals.push(meal);
+                    meal_id
+                }
+            };
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":dish_id", meal_id.get().into()),
+                (":date", record.timestamp().into()),
+                (":status", record.status().name().into()),
+            ])?;
+            statement.next()?;
+        }
+        Ok(created_meals)
+    }
+
+    /// Deletes one occurrence for each of the records. Returns the number of deleted occurrences.
+    fn delete_records(&self, records: &[MealRecord]) -> Result<usize> {
+        let query = "DELETE FROM occurrences WHERE id = \
+            (SELECT id FROM records WHERE meal = :meal AND date = :date LIMIT 1)";
+        let mut statement = self.connection.prepare(query)?;
+        let mut count = 0;
+        for record in records {
+            statement.reset()?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":meal", record.meal().into()),
+                (":date", record.timestamp().into()),
+            ])?;
+            statement.next()?;
+            count += self.connection.change_count();
+        }
+        Ok(count)
+    }
+
+    /// Gives one occurrence with the status of each of the records the status at the same position
+    /// of *new*. Returns the number of changed occurrences.
+    fn change_status(&self, records: &[MealRecord], new: &[Status]) -> Result<usize> {
+        let query = "UPDATE occurrences SET status = :new_status WHERE id = \
+            (SELECT id FROM records WHERE meal = :meal AND date = :date AND status = :status \
+            LIMIT 1)";
+        let mut statement = self.connection.prepare(query)?;
+        let mut count = 0;
+        for (record, status) in records.iter().zip(new) {
+            statement.reset()?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":new_status", status.name().into()),
+                (":meal", record.meal().into()),
+                (":date", record.timestamp().into()),
+                (":status", record.status().name().into()),
+            ])?;
+            statement.next()?;
+            count += self.connection.change_count();
+        }
+        Ok(count)
+    }
+
+    /// Deletes those of the meals which have no occurrences left, unless they have a cooldown,
+    /// tags or aliases. Returns the deleted meals.
+    fn delete_meals_without_occurrences(&self, meal_names: &[String]) -> Result<Vec<Meal>> {
+        let mut deleted_meals = Vec::new();
+        for meal_name in meal_names {
+            if let Some(meal) = self.meal(meal_name)? {
+                if self.delete_meal_without_occurrences(&meal, false)? {
+                    deleted_meals.push(meal);
+                }
+            }
+        }
+        Ok(deleted_meals)
+    }
+
+    /// Moves one occurrence of the meal *from* to the meal *to* for each of the records. If these
+    /// are all the occurrences of *from* and *to* is not known, the meal is renamed instead, so
+    /// that it keeps its id. Returns the number of moved occurrences.
+    fn transfer_records(&self, from: &str, to: &str, records: &[MealRecord]) -> Result<usize> {
+        let Some(from_meal) = self.meal(from)? else {
+            return Ok(0);
+        };
+        let mut statement = self
+            .connection
+            .prepare("SELECT COUNT(*) FROM occurrences WHERE dish_id = :dish_id")?;
+        statement.bind((":dish_id", from_meal.id().get()))?;
+        statement.next()?;
+        let count = statement.read::<i64, _>(0)?;
+        if self.meal(to)?.is_none() && count == records.len() as i64 {
+            self.update_meal_name(from_meal.id(), to)?;
+            return Ok(records.len());
+        }
+        let Some(created) = records.iter().map(|record| record.timestamp()).min() else {
+            return Ok(0);
+        };
+        let to_meal_id = self.get_or_create_meal_id(to, created)?;
+        let query = "UPDATE occurrences SET dish_id = :to WHERE id = \
+            (SELECT id FROM occurrences WHERE dish_id = :from AND date = :date LIMIT 1)";
+        let mut statement = self.connection.prepare(query)?;
+        let mut moved = 0;
+        for record in records {
+            statement.reset()?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":to", to_meal_id.get().into()),
+                (":from", from_meal.id().get().into()),
+                (":date", record.timestamp().into()),
+            ])?;
+            statement.next()?;
+            moved += self.connection.change_count();
+        }
+        Ok(moved)
+    }
+
+    /// Performs the operation of a journal entry. Fails if one of its records cannot be found.
+    /// Must be called within a transaction.
+    fn perform(&self, entry: &JournalEntry, operation: &Operation) -> Result<()> {
+        let changed = match operation {
+            Operation::Add {
+                records,
+                created_meals,
+            } => {
+                for meal in created_meals {
+                    self.restore_meal(meal)?;
+                }
+                self.insert_records(records)?;
+                records.len()
+            }
+            Operation::Remove {
+                records,
+                deleted_meals,
+            } => {
+                let count = self.delete_records(records)?;
+                for meal in deleted_meals {
+                    if let Some(known_meal) = self.meal(&meal.name())? {
+                        self.delete_meal_without_occurrences(&known_meal, true)?;
+                    }
+                }
+                count
+            }
+            Operation::Rename {
+                old_name,
+                new_name,
+                records,
+                created_meal,
+                deleted_meal,
+            } => {
+                if let Some(meal) = created_meal {
+                    self.restore_meal(meal)?;
+                }
+                let count = self.transfer_records(old_name, new_name, records)?;
+                if let (Some(_), Some(old_meal)) = (deleted_meal, self.meal(old_name)?) {
+                    self.delete_meal_without_occurrences(&old_meal, true)?;
+                }
+                count
+            }
+            Operation::SetStatus { records, new } => self.change_status(records, new)?,
+        };
+        match changed < operation.records().len() {
+            true => Err(Error::ChangedRecords(entry.operation().to_string())),
+            false => Ok(()),
+        }
+    }
+
+    /// Appends the operation to the journal, discarding the undone entries. Must be called within
+    /// the transaction of the operation.
+    fn write_journal(&self, moment: DateTime<Utc>, operation: &Operation) -> Result<()> {
+        if operation.records().is_empty() {
+            return Ok(());
+        }
+        self.connection.execute(
+            "
+            DELETE FROM journal_records
+                WHERE journal_id IN (SELECT id FROM journal WHERE undone = 1);
+            DELETE FROM journal_dish_tags WHERE journal_dish_id IN (
+                SELECT journal_dishes.id FROM journal_dishes
+                JOIN journal ON journal.id = journal_dishes.journal_id WHERE undone = 1
+            );
+            DELETE FROM journal_dish_aliases WHERE journal_dish_id IN (
+                SELECT journal_dishes.id FROM journal_dishes
+                JOIN journal ON journal.id = journal_dishes.journal_id WHERE undone = 1
+            );
+            DELETE FROM journal_dishes
+                WHERE journal_id IN (SELECT id FROM journal WHERE undone = 1);
+            DELETE FROM journal WHERE undone = 1;
+            ",
+        )?;
+        let (old_name, new_name) = match operation {
+            Operation::Rename {
+                old_name, new_name, ..
+            } => (
+                Value::from(old_name.as_str()),
+                Value::from(new_name.as_str()),
+            ),
+            _ => (Value::Null, Value::Null),
+        };
+        let query = "INSERT INTO journal (performed, operation, old_name, new_name) \
+            VALUES (:performed, :operation, :old_name, :new_name)";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind_iter::<_, (_, Value)>([
+            (":performed", moment.timestamp().into()),
+            (":operation", operation.kind().into()),
+            (":old_name", old_name),
+            (":new_name", new_name),
+        ])?;
+        statement.next()?;
+        let journal_id = self.last_insert_rowid()?;
+        let new_statuses: Vec<Value> = match operation {
+            Operation::SetStatus { new, .. } => {
+                new.iter().map(|status| status.name().into()).collect()
+            }
+            _ => vec![Value::Null; operation.records().len()],
+        };
+        let query = "INSERT INTO journal_records (journal_id, meal, date, status, new_status) \
+            VALUES (:journal_id, :meal, :date, :status, :new_status)";
+        let mut statement = self.connection.prepare(query)?;
+        for (record, new_status) in operation.records().iter().zip(new_statuses) {
+            statement.reset()?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":journal_id", journal_id.into()),
+                (":meal", record.meal().into()),
+                (":date", record.timestamp().into()),
+                (":status", record.status().name().into()),
+                (":new_status", new_status),
+            ])?;
+            statement.next()?;
+        }
+        let (created_meals, deleted_meals): (Vec<&Meal>, Vec<&Meal>) = match operation {
+            Operation::Add { created_meals, .. } => (created_meals.iter().collect(), Vec::new()),
+            Operation::Remove { deleted_meals, .. } => (Vec::new(), deleted_meals.iter().collect()),
+            Operation::Rename {
+                created_meal,
+                deleted_meal,
+                ..
+            } => (
+                created_meal.as_deref().into_iter().collect(),
+                deleted_meal.as_deref().into_iter().collect(),
+            ),
+            Operation::SetStatus { .. } => (Vec::new(), Vec::new()),
+        };
+        for meal in created_meals {
+            self.write_journal_meal(journal_id, "created", meal)?;
+        }
+        for meal in deleted_meals {
+            self.write_journal_meal(journal_id, "deleted", meal)?;
+        }
+        Ok(())
+    }
+
+    /// Keeps the dish in the role `created` or `deleted` for the journal entry.
+    fn write_journal_meal(&self, journal_id: i64, role: &str, meal: &Meal) -> Result<()> {
+        let cooldown = match meal.cooldown() {
+            Some(days) => Value::Integer(i64::from(days)),
+            None => Value::Null,
+        };
+        let query = "INSERT INTO journal_dishes \
+            (journal_id, role, dish_id, name, created, cooldown) \
+            VALUES (:journal_id, :role, :dish_id, :name, :created, :cooldown)";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind_iter::<_, (_, Value)>([
+            (":journal_id", journal_id.into()),
+            (":role", role.into()),
+            (":dish_id", meal.id().get().into()),
+            (":name", meal.name().into()),
+            (":created", meal.created_timestamp().into()),
+            (":cooldown", cooldown),
+        ])?;
+        statement.next()?;
+        let journal_dish_id = self.last_insert_rowid()?;
+        let query = "INSERT INTO journal_dish_tags (journal_dish_id, tag) \
+            VALUES (:journal_dish_id, :tag)";
+        for tag in meal.tags() {
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":journal_dish_id", journal_dish_id.into()),
+                (":tag", tag.into()),
+            ])?;
+            statement.next()?;
+        }
+        let query = "INSERT INTO journal_dish_aliases (journal_dish_id, alias) \
+            VALUES (:journal_dish_id, :alias)";
+        for alias in meal.aliases() {
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind_iter::<_, (_, Value)>([
+                (":journal_dish_id", journal_dish_id.into()),
+                (":alias", alias.into()),
+            ])?;
+            statement.next()?;
+        }
+        Ok(())
+    }
+
+    /// Reads the dishes in the role `created` or `deleted` of the journal entry.
+    fn read_journal_meals(&self, journal_id: i64, role: &str) -> Result<Vec<Meal>> {
+        let query = "SELECT id, dish_id, name, created, cooldown FROM journal_dishes \
+            WHERE journal_id = :journal_id AND role = :role ORDER BY id ASC";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind_iter::<_, (_, Value)>([
+            (":journal_id", journal_id.into()),
+            (":role", role.into()),
+        ])?;
+        let mut meals = Vec::new();
+        while let State::Row = statement.next()? {
+            meals.push(self.read_journal_meal(&statement)?);
+        }
+        Ok(meals)
+    }
+
+    /// Reads the dish from the statement's row of `journal_dishes`.
+    fn read_journal_meal(&self, statement: &Statement) -> Result<Meal> {
+        let journal_dish_id = statement.read::<i64, _>("id")?;
+        let id = MealId::new(statement.read::<i64, _>("dish_id")?);
+        let name = statement.read::<String, _>("name")?;
+        let created = statement.read::<i64, _>("created")?;
+        let cooldown = statement.read::<Option<i64>, _>("cooldown")?;
+        let read_column = |table: &str, column: &str| -> Result<Vec<String>> {
+            let query =
+                format!("SELECT {column} FROM {table} WHERE journal_dish_id = :journal_dish_id");
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind((":journal_dish_id", journal_dish_id))?;
+            let mut values = Vec::new();
+            while let State::Row = statement.next()? {
+                values.push(statement.read::<String, _>(column)?);
+            }
+            Ok(values)
+        };
+        Ok(Meal::new(id, &name, created, &self.timezone.get())?
+            .with_cooldown(cooldown.map(|days| days as u32))
+            .with_tags(read_column("journal_dish_tags", "tag")?)
+            .with_aliases(read_column("journal_dish_aliases", "alias")?))
+    }
+
+    /// Reads the journal entries, ordered by id.
+    fn read_journal(&self) -> Result<Vec<JournalEntry>> {
+        let query = "SELECT id, performed, operation, old_name, new_name, undone FROM journal \
+            ORDER BY id ASC";
+        let mut statement = self.connection.prepare(query)?;
+        let mut entries = Vec::new();
+        while let State::Row = statement.next()? {
+            let id = statement.read::<i64, _>("id")?;
+            let performed = statement.read::<i64, _>("performed")?;
+            let performed =
+                DateTime::from_timestamp(performed, 0).ok_or(Error::InvalidTimestamp(performed))?;
+            let records = self.read_journal_records(id)?;
+            let mut created_meals = self.read_journal_meals(id, "created")?;
+            let mut deleted_meals = self.read_journal_meals(id, "deleted")?;
+            let operation = match statement.read::<String, _>("operation")?.as_str() {
+                "add" => Operation::Add {
+                    records,
+                    created_meals,
+                },
+                "remove" => Operation::Remove {
+                    records,
+                    deleted_meals,
+                },
+                "status" => Operation::SetStatus {
+                    records,
+                    new: self.read_journal_new_statuses(id)?,
+                },
+                _ => Operation::Rename {
+                    old_name: statement.read::<String, _>("old_name")?,
+                    new_name: statement.read::<String, _>("new_name")?,
+                    records,
+                    created_meal: created_meals.pop().map(Box::new),
+                    deleted_meal: deleted_meals.pop().map(Box::new),
+                },
+            };
+            let undone = statement.read::<i64, _>("undone")? != 0;
+            entries.push(JournalEntry::new(id, performed, operation, undone));
+        }
+        Ok(entries)
+    }
+
+    fn read_journal_records(&self, journal_id: i64) -> Result<Vec<MealRecord>> {
+        let query = "SELECT meal, date, status FROM journal_records \
+            WHERE journal_id = :journal_id ORDER BY id ASC";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((":journal_id", journal_id))?;
+        let mut records = Vec::new();
+        while let State::Row = statement.next()? {
+            records.push(self.read_record(&statement)?);
+        }
+        Ok(records)
+    }
+
+    fn read_journal_new_statuses(&self, journal_id: i64) -> Result<Vec<Status>> {
+        let query = "SELECT new_status FROM journal_records \
+            WHERE journal_id = :journal_id ORDER BY id ASC";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((":journal_id", journal_id))?;
+        let mut statuses = Vec::new();
+        while let State::Row = statement.next()? {
+            statuses.push(statement.read::<String, _>("new_status")?.parse()?);
+        }
+        Ok(statuses)
+    }
+
+    fn set_undone(&self, journal_id: i64, undone: bool) -> Result<()> {
+        let query = "UPDATE journal SET undone = :undone WHERE id = :id";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind_iter::<_, (_, Value)>([
+            (":undone", i64::from(undone).into()),
+            (":id", journal_id.into()),
+        ])?;
+        statement.next()?;
+        Ok(())
+    }
+
+    fn sql_transaction<T, F>(&self, func: F) -> Result<T>
+    where
+        F: Fn() -> Result<T>,
+    {
+        self.connection.execute("BEGIN TRANSACTION")?;
+
+        let result = func().inspect_err(|_| {
+            let _ = self.connection.execute("ROLLBACK");
+        })?;
+
+        self.connection.execute("COMMIT").inspect_err(|_| {
+            let _ = self.connection.execute("ROLLBACK");
+        })?;
+        Ok(result)
+    }
+}
+
+impl MealStore for SqliteStore {
+    #[instrument]
+    fn add(&self, meal: &str, timestamps: &[i64], moment: DateTime<Utc>) -> Result<()> {
+        let today = self.timezone.get().date_of(moment.timestamp())?;
+        let records = timestamps
+            .iter()
+            .map(|&timestamp| {
+                let record = self.record(meal, timestamp)?;
+                let status = Status::of_new_record(record.naive_date(), today);
+                Ok(record.with_status(status))
+            })
+            .collect::<Result<Vec<MealRecord>>>()?;
+        let insert_func = || {
+            let created_meals = self.insert_records(&records)?;
+            self.write_journal(
+                moment,
+                &Operation::Add {
+                    records: records.clone(),
+                    created_meals,
+                },
+            )
+        };
+
+        self.sql_transaction(insert_func)
+    }
+
+    #[instrument(skip(records))]
+    fn add_records(&self, records: &[MealRecord], moment: DateTime<Utc>) -> Result<()> {
+        let insert_func = || {
+            let created_meals = self.insert_records(records)?;
+            let operation = Operation::Add {
+                records: records.to_vec(),
+                created_meals,
+            };
+            self.write_journal(moment, &operation)
+        };
+
+        self.sql_transaction(insert_func)
+    }
+
+    #[instrument(level = "trace")]
+    fn select(&self, filter: &RecordFilter) -> Result<Vec<MealRecord>> {
+        let (condition, condition_params) = Self::condition(filter);
+        self.select_records(&condition, &condition_params)
+    }
+
+    #[instrument]
+    fn remove(&self, filter: &RecordFilter, moment: DateTime<Utc>) -> Result<Vec<MealRecord>> {
+        let action_clause = "DELETE FROM occurrences";
+        let action_params = Vec::new();
+        let (condition, condition_params) = Self::condition(filter);
+
+        let delete_func = || {
+            let records = self.select_records(&condition, &condition_params)?;
+            self.manipulate_records(action_clause, &action_params, &condition, &condition_params)?;
+            let mut meal_names: Vec<String> = Vec::new();
+            for record in records.iter() {
+                if !meal_names.contains(&record.meal()) {
+                    meal_names.push(record.meal());
+                }
+            }
+            let deleted_meals = self.delete_meals_without_occurrences(&meal_names)?;
+            let operation = Operation::Remove {
+                records,
+                deleted_meals,
+            };
+            self.write_journal(moment, &operation)?;
+            Ok(operation.records().to_vec())
+        };
+
+        self.sql_transaction(delete_func)
+    }
+
+    #[instrument]
+    fn remove_records(
+        &self,
+        records: &[MealRecord],
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        let delete_func = || {
+            let mut removed = Vec::new();
+            let mut meal_names: Vec<String> = Vec::new();
+            for record in records {
+                if self.delete_records(std::slice::from_ref(record))? == 0 {
+                    continue;
+                }
+                if !meal_names.contains(&record.meal()) {
+                    meal_names.push(record.meal());
+                }
+                removed.push(record.clone());
+            }
+            let deleted_meals = self.delete_meals_without_occurrences(&meal_names)?;
+            let operation = Operation::Remove {
+                records: removed,
+                deleted_meals,
+            };
+            self.write_journal(moment, &operation)?;
+            Ok(operation.records().to_vec())
+        };
+
+        self.sql_transaction(delete_func)
+    }
+
+    #[instrument]
+    fn rename(
+        &self,
+        old_name: &str,
+        new_name: &str,
+        option_period: Option<&Period>,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        let filter = RecordFilter {
+            meal: Some(old_name.to_string()),
+            period: option_period.cloned(),
+            status: None,
+        };
+        let (condition, condition_params) = Self::condition(&filter);
+
+        let update_func = || {
+            let records = self.select_records(&condition, &condition_params)?;
+            let Some(old_meal) = self.meal(old_name)? else {
+                return Ok(records);
+            };
+            let option_new_meal = self.meal(new_name)?;
+            let mut created_meal = None;
+            let mut deleted_meal = None;
+            match (option_period, option_new_meal) {
+                // the whole meal gets a new name, the records keep referring to it
+                (None, None) => {
+                    self.update_meal_name(old_meal.id(), new_name)?;
+                }
+                // all records go to an already existing meal which replaces the old one
+                (None, Some(new_meal)) if new_meal.id() != old_meal.id() => {
+                    self.move_records(new_meal.id(), &condition, &condition_params)?;
+                    self.delete_meal(old_meal.id())?;
+                    deleted_meal = Some(Box::new(old_meal));
+                }
+                (None, Some(_)) => return Ok(records),
+                // only the records in the period are moved to the (possibly new) meal
+                (Some(_), option_new_meal) => {
+                    let Some(first_record) = records.first() else {
+                        return Ok(records);
+                    };
+                    let new_meal_id =
+                        self.get_or_create_meal_id(new_name, first_record.timestamp())?;
+                    if option_new_meal.is_none() {
+                        created_meal = self.meal(new_name)?.map(Box::new);
+                    }
+                    self.move_records(new_meal_id, &condition, &condition_params)?;
+                    if self.delete_meal_without_occurrences(&old_meal, false)? {
+                        deleted_meal = Some(Box::new(old_meal));
+                    }
+                }
+            }
+            let operation = Operation::Rename {
+                old_name: old_name.to_string(),
+                new_name: new_name.to_string(),
+                records,
+                created_meal,
+                deleted_meal,
+            };
+            self.write_journal(moment, &operation)?;
+            Ok(operation.records().to_vec())
+        };
+
+        self.sql_transaction(update_func)
+    }
+
+    #[instrument]
+    fn unique(&self) -> Result<Vec<MealRecord>> {
+        // SQLite takes the status from the row with the maximal date
+        let query = "SELECT meal, MAX(date) AS date, status FROM records GROUP BY dish_id ORDER BY date ASC, meal ASC";
+        let mut statement = self.connection.prepare(query)?;
+        let mut records = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            records.push(self.read_record(&statement)?);
+        }
+        Ok(records)
+    }
+
+    #[instrument]
+    fn meals(&self) -> Result<Vec<Meal>> {
+        let query = "SELECT id, name, created, cooldown FROM dishes ORDER BY id ASC";
+        let mut statement = self.connection.prepare(query)?;
+        let mut meals = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            meals.push(self.read_meal(&statement)?);
+        }
+        Ok(meals)
+    }
+
+    #[instrument]
+    fn meal_names(&self) -> Result<Vec<(String, Vec<String>)>> {
+        let query = "SELECT dishes.name AS name, dish_aliases.alias AS alias FROM dishes \
+            LEFT JOIN dish_aliases ON dish_aliases.dish_id = dishes.id \
+            ORDER BY dishes.id ASC, dish_aliases.alias ASC";
+        let mut statement = self.connection.prepare(query)?;
+        let mut names: Vec<(String, Vec<String>)> = Vec::new();
+        while let Ok(State::Row) = statement.next() {
+            let name = statement.read::<String, _>("name")?;
+            let option_alias = statement.read::<Option<String>, _>("alias")?;
+            if names.last().is_none_or(|(last_name, _)| *last_name != name) {
+                names.push((name, Vec::new()));
+            }
+            if let (Some(alias), Some((_, aliases))) = (option_alias, names.last_mut()) {
+                aliases.push(alias);
+            }
+        }
+        Ok(names)
+    }
+
+    #[instrument]
+    fn meal(&self, name: &str) -> Result<Option<Meal>> {
+        let query = "SELECT id, name, created, cooldown FROM dishes WHERE name = :name";
+        let mut statement = self.connection.prepare(query)?;
+        statement.bind((":name", name))?;
+        match statement.next()? {
+            State::Row => Ok(Some(self.read_meal(&statement)?)),
+            State::Done => Ok(None),
+        }
+    }
+
+    #[instrument]
+    fn set_cooldown(&self, meal: &str, option_days: Option<u32>) -> Result<()> {
+        if self.meal(meal)?.is_none() {
+            return Err(Error::UnknownMeal(meal.to_string()));
+        }
+        let query = "UPDATE dishes SET cooldown = :cooldown WHERE name = :name";
+        let mut statement = self.connection.prepare(query)?;
+        let cooldown = match option_days {
+            Some(days) => Value::Integer(i64::from(days)),
+            None => Value::Null,
+        };
+        statement.bind((":cooldown", cooldown))?;
+        statement.bind((":name", meal))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    #[instrument]
+    fn set_tags(&self, meal: &str, tags: &[String]) -> Result<()> {
+        let Some(known_meal) = self.meal(meal)? else {
+            return Err(Error::UnknownMeal(meal.to_string()));
+        };
+        self.sql_transaction(|| self.write_tags(known_meal.id(), tags))
+    }
+
+    #[instrument]
+    fn set_aliases(&self, meal: &str, aliases: &[String]) -> Result<()> {
+        let Some(known_meal) = self.meal(meal)? else {
+            return Err(Error::UnknownMeal(meal.to_string()));
+        };
+        self.sql_transaction(|| self.write_aliases(known_meal.id(), aliases))
+    }
+
+    #[instrument]
+    fn merge(
+        &self,
+        meal: &str,
+        merged: &[String],
+        tags: &[String],
+        aliases: &[String],
+    ) -> Result<()> {
+        let Some(known_meal) = self.meal(meal)? else {
+            return Err(Error::UnknownMeal(meal.to_string()));
+        };
+        let merge_func = || {
+            for merged_meal in merged {
+                let Some(merged_meal) = self.meal(merged_meal)? else {
+                    return Err(Error::UnknownMeal(merged_meal.to_string()));
+                };
+                let query = "UPDATE occurrences SET dish_id = :to WHERE dish_id = :from";
+                let mut statement = self.connection.prepare(query)?;
+                statement.bind_iter::<_, (_, Value)>([
+                    (":to", known_meal.id().get().into()),
+                    (":from", merged_meal.id().get().into()),
+                ])?;
+                statement.next()?;
+                self.delete_meal(merged_meal.id())?;
+                merge_journal_meal(&self.connection, &merged_meal.name(), &known_meal.name())?;
+            }
+            self.write_tags(known_meal.id(), tags)?;
+            self.write_aliases(known_meal.id(), aliases)
+        };
+        self.sql_transaction(merge_func)
+    }
+
+    #[instrument]
+    fn set_status(
+        &self,
+        filter: &RecordFilter,
+        status: Status,
+        moment: DateTime<Utc>,
+    ) -> Result<Vec<MealRecord>> {
+        let (condition, condition_params) = Self::condition(filter);
+        let update_func = || {
+            let records: Vec<MealRecord> = self
+                .select_records(&condition, &condition_params)?
+                .into_iter()
+                .filter(|record| record.status() != status)
+                .collect();
+            let action_clause = "UPDATE occurrences SET status = :new_status";
+            let action_params: Vec<(&str, Value)> = vec![(":new_status", status.name().into())];
+            self.manipulate_records(action_clause, &action_params, &condition, &condition_params)?;
+            let changed = records
+                .iter()
+                .map(|record| record.clone().with_status(status))
+                .collect();
+            let new = vec![status; records.len()];
+            self.write_journal(moment, &Operation::SetStatus { records, new })?;
+            Ok(changed)
+        };
+        self.sql_transaction(update_func)
+    }
+
+    fn timezone(&self) -> Timezone {
+        self.timezone.get()
+    }
+
+    #[instrument]
+    fn set_timezone(&self, timezone: Timezone) -> Result<()> {
+        let old_timezone = self.timezone.get();
+        let update_func = || {
+            self.reanchor_dates("occurrences", "date", &old_timezone, &timezone)?;
+            self.reanchor_dates("dishes", "created", &old_timezone, &timezone)?;
+            self.reanchor_dates("journal_records", "date", &old_timezone, &timezone)?;
+            self.reanchor_dates("journal_dishes", "created", &old_timezone, &timezone)?;
+            let query = "INSERT OR REPLACE INTO settings (key, value) VALUES ('timezone', :value)";
+            let mut statement = self.connection.prepare(query)?;
+            statement.bind((":value", timezone.name()))?;
+            statement.next()?;
+            Ok(())
+        };
+        self.sql_transaction(update_func)?;
+        self.timezone.set(timezone);
+        Ok(())
+    }
+
+    #[instrument]
+    fn journal(&self) -> Result<Vec<JournalEntry>> {
+        self.read_journal()
+    }
+
+    #[instrument]
+    fn apply(&self, entry: &JournalEntry, operation: &Operation, undone: bool) -> Result<()> {
+        self.sql_transaction(|| {
+            self.perform(entry, operation)?;
+            self.set_undone(entry.id(), undone)
+        })
+    }
+}
+
+impl fmt::Debug for SqliteStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SqliteStore(chg: {})",
+            self.connection.total_change_count()
+        )
+    }
+}
+
+impl fmt::Display for SqliteStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path_string)
+    }
+}
@@ -0,0 +1,347 @@
+//! Strategies which rank the meals to suggest
+
+use crate::{MealRecord, Period};
+use chrono::NaiveDate;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{cmp::Ordering, fmt};
+
+/// Statistics of a meal which can be suggested, computed from all of its records.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MealStatistics {
+    last_record: MealRecord,
+    count: usize,
+    average_interval: Option<f64>,
+    median_interval: Option<f64>,
+    planned: usize,
+}
+
+impl MealStatistics {
+    /// Constructs the statistics of the meal of the *last_record*.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{MealRecord, MealStatistics};
+    ///
+    /// let last_record = MealRecord::new("pizza", "April 14, 2025").unwrap();
+    /// let statistics = MealStatistics::new(last_record, 3, Some(7.5), Some(7.0), 0);
+    ///
+    /// assert_eq!(statistics.meal(), "pizza");
+    /// assert_eq!(statistics.count(), 3);
+    /// ```
+    pub fn new(
+        last_record: MealRecord,
+        count: usize,
+        average_interval: Option<f64>,
+        median_interval: Option<f64>,
+        planned: usize,
+    ) -> Self {
+        MealStatistics {
+            last_record,
+            count,
+            average_interval,
+            median_interval,
+            planned,
+        }
+    }
+
+    /// Computes the statistics of the meal of the *last_record* from the dates of all its records.
+    pub(crate) fn from_dates(
+        last_record: MealRecord,
+        dates: &[NaiveDate],
+        today: NaiveDate,
+    ) -> Self {
+        let mut distinct_dates = dates.to_vec();
+        distinct_dates.sort();
+        distinct_dates.dedup();
+        let average_interval = match (distinct_dates.first(), distinct_dates.last()) {
+            (Some(first), Some(last)) if distinct_dates.len() > 1 => {
+                Some((*last - *first).num_days() as f64 / (distinct_dates.len() - 1) as f64)
+            }
+            _ => None,
+        };
+        let mut intervals: Vec<i64> = distinct_dates
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).num_days())
+            .collect();
+        intervals.sort();
+        let middle = intervals.len() / 2;
+        let median_interval = match intervals.len() {
+            0 => None,
+            length if length % 2 == 0 => {
+                Some((intervals[middle - 1] + intervals[middle]) as f64 / 2.0)
+            }
+            _ => Some(intervals[middle] as f64),
+        };
+        let planned = dates.iter().filter(|&&date| date > today).count();
+        MealStatistics::new(
+            last_record,
+            dates.len(),
+            average_interval,
+            median_interval,
+            planned,
+        )
+    }
+
+    /// Get the latest record of the meal, which may be planned in the future.
+    pub fn last_record(&self) -> &MealRecord {
+        &self.last_record
+    }
+
+    /// Get the name of the meal.
+    pub fn meal(&self) -> String {
+        self.last_record.meal()
+    }
+
+    /// Get the date of the latest record of the meal.
+    pub fn last_date(&self) -> NaiveDate {
+        self.last_record.naive_date()
+    }
+
+    /// Get the number of records of the meal.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Get the average number of days between the dates of the meal's records, if it was recorded
+    /// on more than one date.
+    pub fn average_interval(&self) -> Option<f64> {
+        self.average_interval
+    }
+
+    /// Get the median number of days between the dates of the meal's records, if it was recorded
+    /// on more than one date. Unlike the average, a single long break does not stretch it.
+    pub fn median_interval(&self) -> Option<f64> {
+        self.median_interval
+    }
+
+    /// Returns the date on which the meal is due again in its own rhythm: the median interval
+    /// after its latest record. Meals recorded on only one date have no rhythm and no expected
+    /// date.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::{MealRecord, MealStatistics};
+    /// use chrono::NaiveDate;
+    ///
+    /// let last_record = MealRecord::new("pizza", "April 14, 2025").unwrap();
+    /// let statistics = MealStatistics::new(last_record, 3, Some(7.5), Some(7.0), 0);
+    ///
+    /// assert_eq!(statistics.expected_next(), NaiveDate::from_ymd_opt(2025, 4, 21));
+    /// ```
+    pub fn expected_next(&self) -> Option<NaiveDate> {
+        self.median_interval
+            .map(|interval| self.last_date() + chrono::Duration::days(interval.round() as i64))
+    }
+
+    /// Get the number of records of the meal planned after today.
+    pub fn planned(&self) -> usize {
+        self.planned
+    }
+
+    /// Returns the number of days from the latest record until *today*, zero if the latest record
+    /// is today or later.
+    pub fn days_since_last(&self, today: NaiveDate) -> u64 {
+        (today - self.last_date()).num_days().max(0) as u64
+    }
+}
+
+/// Keeps [suggestions](crate::Storage::suggest) and [plans](crate::Storage::plan) from piling up
+/// meals of the same category. The categories of a meal are its [tags](crate::Meal::tags); meals
+/// without tags are not affected. The rule is applied to the candidates after they were ranked, so
+/// a left out meal makes room for the next one in the ranking. The default rule leaves all
+/// candidates in.
+///
+/// Example:
+/// ```
+/// use libmrot::{Diversity, FixedClock, OldestFirst, Storage, TagFilter};
+/// use chrono::{TimeZone, Utc};
+///
+/// // open in-memory storage, pretending that today is April 15th, 2025
+/// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+/// let storage = Storage::in_memory().with_clock(clock);
+/// for (meal, date) in [("spaghetti", "March 1"), ("lasagne", "March 2"), ("curry", "March 3")] {
+///     storage.add_meal_on_dates(meal, &vec![String::from(date)]).unwrap();
+/// }
+/// storage.add_tags("spaghetti", &[String::from("pasta")]).unwrap();
+/// storage.add_tags("lasagne", &[String::from("pasta")]).unwrap();
+///
+/// let diversity = Diversity::default().with_one_per_tag(true);
+/// let suggestions = storage
+///     .suggest(2, None, Vec::new(), &TagFilter::default(), &diversity, &OldestFirst)
+///     .unwrap();
+/// let meals: Vec<String> = suggestions.iter().map(|record| record.meal()).collect();
+/// assert_eq!(meals, vec!["spaghetti", "curry"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Diversity {
+    /// Leave out the candidates which share a tag with a better ranked candidate.
+    pub one_per_tag: bool,
+    /// Leave out the candidates which share a tag with a meal recorded in this period.
+    pub look_back: Option<Period>,
+}
+
+impl Diversity {
+    /// Returns the rule which allows at most one candidate per tag, or not.
+    pub fn with_one_per_tag(self, one_per_tag: bool) -> Self {
+        Diversity {
+            one_per_tag,
+            ..self
+        }
+    }
+
+    /// Returns the rule which leaves out the candidates sharing a tag with a meal recorded in the
+    /// *look_back* period, or not.
+    pub fn with_look_back(self, look_back: Option<Period>) -> Self {
+        Diversity { look_back, ..self }
+    }
+
+    /// Returns true if the rule leaves all candidates in.
+    pub fn is_empty(&self) -> bool {
+        !self.one_per_tag && self.look_back.is_none()
+    }
+}
+
+/// Ranks the candidates for [suggestions](crate::Storage::suggest).
+///
+/// Implementations receive the statistics of the meals which are not ignored and return them
+/// ordered by preference, the best suggestion first. They may leave out meals which should not be
+/// suggested at all.
+///
+/// Example:
+/// ```
+/// use libmrot::{Diversity, FixedClock, MealStatistics, Storage, SuggestionStrategy, TagFilter};
+/// use chrono::{NaiveDate, TimeZone, Utc};
+///
+/// /// Suggests the meals in alphabetical order.
+/// #[derive(Debug)]
+/// struct Alphabetical;
+///
+/// impl SuggestionStrategy for Alphabetical {
+///     fn rank(&self, mut candidates: Vec<MealStatistics>, _today: NaiveDate) -> Vec<MealStatistics> {
+///         candidates.sort_by_key(|candidate| candidate.meal());
+///         candidates
+///     }
+/// }
+///
+/// let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap());
+/// let storage = Storage::in_memory().with_clock(clock);
+/// storage.add_meal_on_dates("spaghetti", &vec![String::from("March 1")]).unwrap();
+/// storage.add_meal_on_dates("curry", &vec![String::from("March 2")]).unwrap();
+///
+/// let suggestions = storage
+///     .suggest(1, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &Alphabetical)
+///     .unwrap();
+/// assert_eq!(suggestions[0].meal(), "curry");
+/// ```
+pub trait SuggestionStrategy: fmt::Debug {
+    /// Returns the candidates ordered by preference, the best suggestion first.
+    fn rank(&self, candidates: Vec<MealStatistics>, today: NaiveDate) -> Vec<MealStatistics>;
+}
+
+/// Suggests the meals not consumed for the longest time first. This is what
+/// [`Storage::what`](crate::Storage::what) does.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OldestFirst;
+
+impl SuggestionStrategy for OldestFirst {
+    fn rank(&self, mut candidates: Vec<MealStatistics>, _today: NaiveDate) -> Vec<MealStatistics> {
+        candidates.sort_by_key(|candidate| candidate.last_date());
+        candidates
+    }
+}
+
+/// Suggests the meals with the fewest records first. Among meals with as many records, the ones
+/// not consumed for the longest time come first.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LeastFrequent;
+
+impl SuggestionStrategy for LeastFrequent {
+    fn rank(&self, mut candidates: Vec<MealStatistics>, _today: NaiveDate) -> Vec<MealStatistics> {
+        candidates.sort_by_key(|candidate| (candidate.count(), candidate.last_date()));
+        candidates
+    }
+}
+
+/// Suggests random meals, preferring the ones not consumed for a long time: each meal is weighted
+/// by the number of days since it was last consumed plus one. Meals recorded only for today or
+/// later have the weight one.
+///
+/// The same seed ranks the same candidates in the same order.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WeightedRandom {
+    seed: Option<u64>,
+}
+
+impl WeightedRandom {
+    /// Constructs the strategy, optionally with a seed for reproducible suggestions.
+    pub fn new(seed: Option<u64>) -> Self {
+        WeightedRandom { seed }
+    }
+}
+
+impl SuggestionStrategy for WeightedRandom {
+    fn rank(&self, candidates: Vec<MealStatistics>, today: NaiveDate) -> Vec<MealStatistics> {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
+        };
+        let mut weighted: Vec<(MealStatistics, u64)> = candidates
+            .into_iter()
+            .map(|candidate| {
+                let weight = candidate.days_since_last(today) + 1;
+                (candidate, weight)
+            })
+            .collect();
+        let mut ranked = Vec::with_capacity(weighted.len());
+        while !weighted.is_empty() {
+            let total: u64 = weighted.iter().map(|(_, weight)| weight).sum();
+            let mut target = rng.random_range(0..total);
+            let index = weighted
+                .iter()
+                .position(|(_, weight)| match target < *weight {
+                    true => true,
+                    false => {
+                        target -= weight;
+                        false
+                    }
+                })
+                .unwrap_or(weighted.len() - 1);
+            ranked.push(weighted.remove(index).0);
+        }
+        ranked
+    }
+}
+
+/// Suggests the meals which are most overdue in their own rhythm first: the days since a meal was
+/// last consumed are divided by its [median interval](MealStatistics::median_interval), so a weekly
+/// favourite two weeks late comes before a dish eaten twice a year and last had eight months ago.
+/// Meals recorded on only one date have no rhythm; they follow the others, the ones not consumed
+/// for the longest time first.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Overdue;
+
+impl Overdue {
+    /// Returns how many of its median intervals have passed since the meal was last consumed.
+    fn overdue(candidate: &MealStatistics, today: NaiveDate) -> Option<f64> {
+        candidate
+            .median_interval()
+            .filter(|&interval| interval > 0.0)
+            .map(|interval| (today - candidate.last_date()).num_days() as f64 / interval)
+    }
+}
+
+impl SuggestionStrategy for Overdue {
+    fn rank(&self, mut candidates: Vec<MealStatistics>, today: NaiveDate) -> Vec<MealStatistics> {
+        candidates.sort_by(
+            |a, b| match (Self::overdue(a, today), Self::overdue(b, today)) {
+                (Some(a_overdue), Some(b_overdue)) => {
+                    b_overdue.partial_cmp(&a_overdue).unwrap_or(Ordering::Equal)
+                }
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => a.last_date().cmp(&b.last_date()),
+            },
+        );
+        candidates
+    }
+}
@@ -0,0 +1,127 @@
+//! Timezone in which days begin and end
+
+use crate::{Error, Result};
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::{fmt, str::FromStr};
+
+/// The timezone whose midnights delimit the days of a [`Storage`](crate::Storage).
+///
+/// Each record is kept as the timestamp of the first moment of its day in this timezone. Storages
+/// created before the timezone was configurable use UTC, which is also the default.
+///
+/// Example:
+/// ```
+/// use libmrot::Timezone;
+/// use chrono::NaiveDate;
+///
+/// let sydney: Timezone = "Australia/Sydney".parse().unwrap();
+/// let date = NaiveDate::from_ymd_opt(2025, 4, 15).unwrap();
+///
+/// // midnight in Sydney is 14:00 UTC of the previous day
+/// assert_eq!(sydney.start_of_day(&date), 1744639200);
+/// assert_eq!(sydney.date_of(1744639200).unwrap(), date);
+/// assert_eq!(Timezone::UTC.date_of(1744639200).unwrap(), date.pred_opt().unwrap());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timezone(Tz);
+
+impl Timezone {
+    /// Coordinated Universal Time.
+    pub const UTC: Timezone = Timezone(Tz::UTC);
+
+    /// Returns the timezone the system is configured to use.
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the system timezone cannot be determined or is not a known IANA
+    /// timezone.
+    pub fn local() -> Result<Self> {
+        let name = iana_time_zone::get_timezone()?;
+        Self::from_str(&name)
+    }
+
+    /// Returns the IANA name of the timezone.
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    /// Returns the date of the day in which the given moment lies.
+    ///
+    /// Error:
+    ///
+    /// Returns an error if the timestamp cannot be converted to a [`chrono::DateTime`].
+    pub fn date_of(&self, timestamp: i64) -> Result<NaiveDate> {
+        let datetime =
+            DateTime::from_timestamp(timestamp, 0).ok_or(Error::InvalidTimestamp(timestamp))?;
+        Ok(datetime.with_timezone(&self.0).date_naive())
+    }
+
+    /// Returns the timestamp of the first moment of the given date. This is midnight, unless the
+    /// clocks skip midnight on that date.
+    pub fn start_of_day(&self, date: &NaiveDate) -> i64 {
+        let mut start = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+        loop {
+            match self.0.from_local_datetime(&start) {
+                LocalResult::Single(datetime) => return datetime.timestamp(),
+                LocalResult::Ambiguous(earliest, _) => return earliest.timestamp(),
+                LocalResult::None => start += TimeDelta::minutes(15),
+            }
+        }
+    }
+
+    /// Returns the wall-clock time in this timezone at the given moment.
+    pub fn naive_local(&self, moment: DateTime<Utc>) -> NaiveDateTime {
+        moment.with_timezone(&self.0).naive_local()
+    }
+
+    /// Returns the moment at which the clocks in this timezone show the given wall-clock time, or
+    /// `None` if they skip it or show it twice.
+    ///
+    /// Example:
+    /// ```
+    /// use libmrot::Timezone;
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    ///
+    /// let prague: Timezone = "Europe/Prague".parse().unwrap();
+    /// let noon = NaiveDate::from_ymd_opt(2025, 4, 15).unwrap().and_hms_opt(12, 0, 0).unwrap();
+    /// let two_am = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(2, 30, 0).unwrap();
+    ///
+    /// assert_eq!(prague.moment_of(&noon), Utc.with_ymd_and_hms(2025, 4, 15, 10, 0, 0).single());
+    /// // the clocks in Prague skip from 2:00 to 3:00 when daylight saving time begins
+    /// assert_eq!(prague.moment_of(&two_am), None);
+    /// ```
+    pub fn moment_of(&self, datetime: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        self.0
+            .from_local_datetime(datetime)
+            .single()
+            .map(|moment| moment.with_timezone(&Utc))
+    }
+}
+
+impl Default for Timezone {
+    fn default() -> Self {
+        Self::UTC
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = Error;
+
+    /// Parses an IANA timezone name like `"Europe/Prague"`. The special value `"local"` stands for
+    /// the [system timezone](Timezone::local).
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "local" => Self::local(),
+            _ => Tz::from_str(s)
+                .map(Timezone)
+                .map_err(|_| Error::UnknownTimezone(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Timezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.name(), f)
+    }
+}
@@ -1,25 +1,8 @@
 //! Implementation of tests for libmrot
 
-use cucumber::{when, given};
-use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::TextDates};
-use libmrot::Storage;
+use mrot_test_utils::normal_world as construct_world;
 #[allow(unused_imports)]
-use mrot_test_utils::common_steps::{a_storage_with_records, storage_show_meal_records, storage_when_meal};
-
-#[given(regex = r"^an empty in-memory storage$")]
-async fn a_storage(world: &mut World) -> Result<()> {
-    let storage = Storage::open(":memory:")?;
-    world.storage = Some(storage);
-    Ok(())
-}
-
-#[when(regex = r"^I add the meal (?P<meal>.*) on the dates? (?P<text_dates>.*) to the storage$")]
-async fn add_meal_on_dates(world: &mut World, meal: String, text_dates: TextDates) -> Result<()> {
-    let dates = text_dates.to_vec_string();
-    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
-    storage.add_meal_on_dates(&meal, &dates)?;
-    Ok(())
-}
+use mrot_test_utils::common_steps::{the_current_date_is, an_empty_storage, a_storage_with_records, storage_show_meal_records, storage_when_meal, add_meal_on_dates};
 
 #[tokio::main]
 async fn main() {
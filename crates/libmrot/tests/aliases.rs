@@ -0,0 +1,63 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{when, then};
+use libmrot::Error as LibMrotError;
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::Meals};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, an_empty_storage, a_storage_with_records, check_result_vec_mealrecord, storage_when_meal, storage_knows_meals, reopen_storage, add_meal_on_dates, add_aliases, ask_for_suggestions_ignoring, ask_for_unique_meals, remove_meal_in_period, storage_does_not_know_meal, rename_meal};
+
+#[when(regex = r"^I remove the aliases (?P<aliases>.*)$")]
+async fn remove_aliases(world: &mut World, aliases: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.remove_aliases(&aliases.to_vec_string())?;
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) has the aliases (?P<aliases>.*)$")]
+async fn meal_has_aliases(world: &mut World, meal: String, expected_aliases: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert_eq!(known_meal.aliases(), expected_aliases.to_vec_string(), "{} has the aliases {:?} but we expected {:?}", meal, known_meal.aliases(), expected_aliases);
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) has no aliases$")]
+async fn meal_has_no_aliases(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert!(known_meal.aliases().is_empty(), "{} has the aliases {:?} but we expected none", meal, known_meal.aliases());
+    Ok(())
+}
+
+#[then(regex = r"^the journal has (?P<count>\d+) entries$")]
+async fn journal_has_entries(world: &mut World, expected_count: usize) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let journal = storage.journal()?;
+    assert_eq!(journal.len(), expected_count, "the journal has the entries {:?} but we expected {} entries", journal, expected_count);
+    Ok(())
+}
+
+#[then(regex = r"^adding the alias (?P<alias>.*) to (?P<meal>.*) fails because the alias is (?P<reason>invalid|taken)$")]
+async fn adding_alias_fails(world: &mut World, alias: String, meal: String, reason: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.add_aliases(&meal, &[alias]);
+    match reason.as_str() {
+        "invalid" => assert!(matches!(result, Err(LibMrotError::InvalidAlias(_))), "add_aliases returned {:?} but we expected InvalidAlias", result),
+        _ => assert!(matches!(result, Err(LibMrotError::AliasTaken(_))), "add_aliases returned {:?} but we expected AliasTaken", result),
+    }
+    Ok(())
+}
+
+#[then(regex = r"^removing the alias (?P<alias>.*) fails because the alias is unknown$")]
+async fn removing_alias_fails(world: &mut World, alias: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.remove_aliases(std::slice::from_ref(&alias));
+    assert!(matches!(result, Err(LibMrotError::UnknownAlias(ref name)) if *name == alias), "remove_aliases returned {:?} but we expected UnknownAlias({})", result, alias);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/aliases.feature").await;
+}
@@ -0,0 +1,72 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{when, then};
+use libmrot::{parse_duration, Error as LibMrotError, TagFilter};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records, check_result_vec_mealrecord, reopen_storage, set_cooldown, ask_for_suggestions, random_meal_is, rename_meal};
+
+#[when(regex = r"^I clear the cooldown of (?P<meal>.*)$")]
+async fn clear_cooldown(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.set_cooldown(&meal, None)?;
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) weighted random meals$")]
+async fn ask_for_weighted_random_meals(world: &mut World, number: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.weighted_random(number, None, Vec::new(), &TagFilter::default(), None));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for a random meal$")]
+async fn ask_for_random_meal(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_option_mealrecord = Some(storage.random(&TagFilter::default()));
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) has a cooldown of (?P<days>\d+) days$")]
+async fn meal_has_cooldown(world: &mut World, meal: String, expected_days: u32) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert_eq!(known_meal.cooldown(), Some(expected_days), "{} has the cooldown {:?} but we expected {} days", meal, known_meal.cooldown(), expected_days);
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) has no cooldown$")]
+async fn meal_has_no_cooldown(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert_eq!(known_meal.cooldown(), None, "{} has the cooldown {:?} but we expected none", meal, known_meal.cooldown());
+    Ok(())
+}
+
+#[then(regex = r"^setting the cooldown of (?P<meal>.*) fails because the meal is unknown$")]
+async fn setting_cooldown_fails(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.set_cooldown(&meal, Some(7));
+    assert!(matches!(result, Err(LibMrotError::UnknownMeal(ref name)) if *name == meal), "set_cooldown returned {:?} but we expected UnknownMeal({})", result, meal);
+    Ok(())
+}
+
+#[then(regex = r"^the duration (?P<duration>.*) has (?P<days>\d+) days$")]
+async fn duration_has_days(_world: &mut World, duration: String, expected_days: u32) -> Result<()> {
+    let days = parse_duration(&duration)?;
+    assert_eq!(days, expected_days, "the duration {} has {} days but we expected {}", duration, days, expected_days);
+    Ok(())
+}
+
+#[then(regex = r"^the duration (?P<duration>.*) cannot be parsed$")]
+async fn duration_cannot_be_parsed(_world: &mut World, duration: String) -> Result<()> {
+    let result = parse_duration(&duration);
+    assert!(matches!(result, Err(LibMrotError::InvalidDuration(_))), "parse_duration returned {:?} but we expected InvalidDuration", result);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/cooldown.feature").await;
+}
@@ -0,0 +1,37 @@
+//! Implementation of tests for libmrot
+
+use cucumber::then;
+use libmrot::{Error as LibMrotError, TagFilter, Timezone};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_moment_is, an_empty_storage, a_storage_with_records, storage_when_meal, storage_has_timezone, reopen_storage, add_meal_on_dates, set_timezone};
+
+#[then(regex = r"^the storage, asked for today's date, returns (?P<date>.*)$")]
+async fn storage_today(world: &mut World, expected_date: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let actual_date = storage.today().to_string();
+    assert_eq!(actual_date, expected_date, "storage.today returned {} but we expected {}", actual_date, expected_date);
+    Ok(())
+}
+
+#[then(regex = r"^the record of (?P<meal>.*) starts at the timestamp (?P<timestamp>\d+)$")]
+async fn record_starts_at(world: &mut World, meal: String, expected_timestamp: i64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let records = storage.get_last_cooked_unique(&TagFilter::default())?;
+    let record = records.iter().find(|record| record.meal() == meal).ok_or(Error::UndefinedValue(format!("record of {}", meal)))?;
+    assert_eq!(record.timestamp(), expected_timestamp, "record of {} starts at {} but we expected {}", meal, record.timestamp(), expected_timestamp);
+    Ok(())
+}
+
+#[then(regex = r"^the timezone (?P<timezone>.*) is unknown$")]
+async fn timezone_is_unknown(_world: &mut World, timezone: String) -> Result<()> {
+    let result = timezone.parse::<Timezone>();
+    assert!(matches!(result, Err(LibMrotError::UnknownTimezone(ref name)) if *name == timezone), "parsing the timezone returned {:?} but we expected UnknownTimezone({})", result, timezone);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/day_boundaries.feature").await;
+}
@@ -0,0 +1,53 @@
+//! Implementation of tests for libmrot
+
+use cucumber::when;
+use libmrot::{Diversity, OldestFirst, TagFilter};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records, check_result_vec_mealrecord, set_cooldown, tag_meal};
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions with the default diversity$")]
+async fn ask_for_suggestions(world: &mut World, number: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.suggest(number, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &OldestFirst));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions with one meal per tag$")]
+async fn ask_for_suggestions_one_per_tag(world: &mut World, number: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let diversity = Diversity::default().with_one_per_tag(true);
+    world.result_vec_mealrecord = Some(storage.suggest(number, None, Vec::new(), &TagFilter::default(), &diversity, &OldestFirst));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions without the tags eaten (?P<look_back>.*)$")]
+async fn ask_for_suggestions_looking_back(world: &mut World, number: u64, look_back: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let diversity = Diversity::default().with_look_back(Some(storage.period(&look_back)?));
+    world.result_vec_mealrecord = Some(storage.suggest(number, None, Vec::new(), &TagFilter::default(), &diversity, &OldestFirst));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions with one meal per tag and without the tags eaten (?P<look_back>.*)$")]
+async fn ask_for_diverse_suggestions(world: &mut World, number: u64, look_back: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let diversity = Diversity::default().with_one_per_tag(true).with_look_back(Some(storage.period(&look_back)?));
+    world.result_vec_mealrecord = Some(storage.suggest(number, None, Vec::new(), &TagFilter::default(), &diversity, &OldestFirst));
+    Ok(())
+}
+
+#[when(regex = r"^I plan the period (?P<period>.*) with one meal per tag$")]
+async fn plan_period_one_per_tag(world: &mut World, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    let diversity = Diversity::default().with_one_per_tag(true);
+    world.result_vec_mealrecord = Some(storage.plan(period, None, Vec::new(), &TagFilter::default(), &diversity, &OldestFirst));
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/diversity.feature").await;
+}
@@ -1,17 +1,22 @@
 Feature: Add meal, When meal, Show date range
 
+    Background:
+        Given the current date is 2025-04-15
+
     Scenario Outline: Add meal, show when meal
-        Given an empty in-memory storage
+        Given an empty <backend> storage
         When I add the meal <meal> on the date <text_date> to the storage
         Then the storage, asked when <meal> was consumed, returns <naive_dates>
 
         Examples:
-            | meal      | text_date                                                            | naive_dates                                                |
-            | chicken   | 2025-02-23                                                           | 2025-02-23                                                 |
-            | weasel    | 12 hours before and after noon on February 26th, 2025                | 2025-02-26                                                 |
-            | tuna      | 1 second before February 26th, 2025                                  | 2025-02-25                                                 |
-            | ravioli   | from 2025-03-10 through 2025-03-11; 2025-03-03                       | 2025-03-03, 2025-03-10, 2025-03-11                         |
-            | spaghetti | from 2025-03-10 through 2025-03-11; from 2025-02-01 until 2025-02-04 | 2025-02-01, 2025-02-02, 2025-02-03, 2025-03-10, 2025-03-11 |
+            | backend   | meal      | text_date                                                            | naive_dates                                                |
+            | in-memory | chicken   | 2025-02-23                                                           | 2025-02-23                                                 |
+            | in-memory | weasel    | 12 hours before and after noon on February 26th, 2025                | 2025-02-26                                                 |
+            | in-memory | tuna      | 1 second before February 26th, 2025                                  | 2025-02-25                                                 |
+            | in-memory | ravioli   | from 2025-03-10 through 2025-03-11; 2025-03-03                       | 2025-03-03, 2025-03-10, 2025-03-11                         |
+            | in-memory | spaghetti | from 2025-03-10 through 2025-03-11; from 2025-02-01 until 2025-02-04 | 2025-02-01, 2025-02-02, 2025-02-03, 2025-03-10, 2025-03-11 |
+            | SQLite    | ravioli   | from 2025-03-10 through 2025-03-11; 2025-03-03                       | 2025-03-03, 2025-03-10, 2025-03-11                         |
+            | SQLite    | spaghetti | from 2025-03-10 through 2025-03-11; from 2025-02-01 until 2025-02-04 | 2025-02-01, 2025-02-02, 2025-02-03, 2025-03-10, 2025-03-11 |
 
      Scenario Outline: Add meal on several dates, show when meal
         Given an empty in-memory storage
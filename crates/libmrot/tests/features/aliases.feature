@@ -0,0 +1,133 @@
+Feature: Aliases
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Meals are added and looked up by their aliases
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+        When I add the aliases spag bol, bolognese to spaghetti bolognese
+        When I add the meal spag bol on the date 2025-03-05 to the storage
+        When I add the meal bolognese on the date 2025-03-09 to the storage
+        Then the storage knows the meals spaghetti bolognese
+        And the storage, asked when bolognese was consumed, returns 2025-03-01, 2025-03-05, 2025-03-09
+        And the meal spaghetti bolognese has the aliases bolognese, spag bol
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: An alias merges the meal of the same name
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+            | 1740873600 | bolognese           |
+            | 1741046400 | spag bol            |
+            | 1740960000 | pizza               |
+        When I add the aliases spag bol, bolognese to spaghetti bolognese
+        And I ask for unique meals
+        Then I get the meal records 1740960000, pizza; 1741046400, spaghetti bolognese
+        And the storage knows the meals spaghetti bolognese, pizza
+        And the storage, asked when spaghetti bolognese was consumed, returns 2025-03-01, 2025-03-02, 2025-03-04
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Merging a meal into an alias is not journaled
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+            | 1740873600 | bolognese           |
+        When I add the aliases bolognese to spaghetti bolognese
+        Then the journal has 2 entries
+        And the storage knows the meals spaghetti bolognese
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: A merged meal brings its aliases along
+        Given an in-memory storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+            | 1740873600 | bolognese           |
+        When I add the aliases ragù to bolognese
+        When I add the aliases bolognese to spaghetti bolognese
+        Then the meal spaghetti bolognese has the aliases bolognese, ragù
+        And the storage, asked when ragù was consumed, returns 2025-03-01, 2025-03-02
+
+    Scenario Outline: The ignore list accepts aliases
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+            | 1740873600 | pizza               |
+            | 1740960000 | curry               |
+        When I add the aliases spag bol to spaghetti bolognese
+        When I ask for 3 meal suggestions ignoring spag bol
+        Then I get the meal records 1740873600, pizza; 1740960000, curry
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Records are removed by an alias
+        Given an in-memory storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+            | 1740873600 | pizza               |
+        When I add the aliases spag bol to spaghetti bolognese
+        When I remove the meal spag bol in the period March 2025
+        Then I get the meal records 1740787200, spaghetti bolognese
+
+    Scenario Outline: A meal is renamed by an alias and keeps its aliases
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+        When I add the aliases spag bol to spaghetti bolognese
+        When I rename spag bol to ragù in the storage
+        Then the storage knows the meals ragù
+        And the meal ragù has the aliases spag bol
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Aliases are kept in the SQLite storage
+        Given a SQLite storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+        When I add the aliases spag bol to spaghetti bolognese
+        And I reopen the storage
+        Then the meal spag bol has the aliases spag bol
+
+    Scenario Outline: Remove an alias
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+        When I add the aliases spag bol, bolognese to spaghetti bolognese
+        When I remove the aliases spag bol, bolognese
+        Then the meal spaghetti bolognese has no aliases
+        And the storage does not know the meal spag bol
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Invalid, taken and unknown aliases
+        Given an in-memory storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+            | 1740873600 | lasagne             |
+        When I add the aliases bolognese to lasagne
+        Then adding the alias bolognese to spaghetti bolognese fails because the alias is taken
+        And adding the alias spaghetti bolognese to spaghetti bolognese fails because the alias is invalid
+        And adding the alias    to spaghetti bolognese fails because the alias is invalid
+        And removing the alias spag bol fails because the alias is unknown
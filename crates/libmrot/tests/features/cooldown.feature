@@ -0,0 +1,125 @@
+Feature: Cooldown
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: A meal in its cooldown is not suggested
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1743465600 | curry    |
+            | 1744070400 | pasta    |
+        When I set the cooldown of <meal> to <duration>
+        When I ask for 3 meal suggestions
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | meal     | duration | records                                                      |
+            | in-memory | raclette | 3 months | 1743465600, curry; 1744070400, pasta                         |
+            | in-memory | raclette | 59 days  | 1739577600, raclette; 1743465600, curry; 1744070400, pasta   |
+            | in-memory | raclette | 60 days  | 1743465600, curry; 1744070400, pasta                         |
+            | in-memory | pasta    | a week   | 1739577600, raclette; 1743465600, curry; 1744070400, pasta   |
+            | in-memory | pasta    | 8 days   | 1739577600, raclette; 1743465600, curry                      |
+            | SQLite    | raclette | 3 months | 1743465600, curry; 1744070400, pasta                         |
+            | SQLite    | pasta    | 8 days   | 1739577600, raclette; 1743465600, curry                      |
+
+    Scenario Outline: A meal planned within its cooldown is not suggested
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1743465600 | curry    |
+            | 1745107200 | curry    |
+        When I set the cooldown of curry to 10 days
+        When I ask for 3 meal suggestions
+        Then I get the meal records 1739577600, raclette
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: A cleared cooldown no longer applies
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1744070400 | pasta    |
+        When I set the cooldown of raclette to 1 year
+        When I clear the cooldown of raclette
+        When I ask for 3 meal suggestions
+        Then the meal raclette has no cooldown
+        Then I get the meal records 1739577600, raclette; 1744070400, pasta
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: The random pickers skip meals in their cooldown
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1744070400 | pasta    |
+        When I set the cooldown of raclette to 3 months
+        When I ask for a random meal
+        Then the random meal is pasta
+        When I ask for 2 weighted random meals
+        Then I get the meal records 1744070400, pasta
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: The cooldown stays with the meal when it is renamed
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+        When I set the cooldown of raclette to 3 months
+        When I rename raclette to fondue in the storage
+        Then the meal fondue has a cooldown of 90 days
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: The cooldown is kept in the storage file
+        Given a SQLite storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+        When I set the cooldown of raclette to 2 weeks
+        When I reopen the storage
+        Then the meal raclette has a cooldown of 14 days
+
+    Scenario Outline: Only known meals can have a cooldown
+        Given an empty <backend> storage
+        Then setting the cooldown of raclette fails because the meal is unknown
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Parse durations
+        Then the duration <duration> has <days> days
+
+        Examples:
+            | duration             | days |
+            | 1 day                | 1    |
+            | 10 days              | 10   |
+            | a week               | 7    |
+            | Two Weeks            | 14   |
+            | 3 months             | 90   |
+            | a year               | 365  |
+            | 3 months and 1 week  | 97   |
+            | 1 year, 2 months     | 425  |
+
+    Scenario Outline: Unparsable durations
+        Then the duration <duration> cannot be parsed
+
+        Examples:
+            | duration    |
+            | fortnight   |
+            | 3           |
+            | some days   |
+            | 2 eons      |
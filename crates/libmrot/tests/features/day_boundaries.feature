@@ -0,0 +1,55 @@
+Feature: Day Boundaries
+
+    Scenario Outline: A new storage uses UTC
+        Given an empty <backend> storage
+        Then the storage has the timezone UTC
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Today is the date in the timezone of the storage
+        Given the current moment is <moment>
+        Given an empty <backend> storage
+        When I set the timezone of the storage to <timezone>
+        When I add the meal pizza on the date today to the storage
+        Then the storage, asked for today's date, returns <today>
+        Then the storage, asked when pizza was consumed, returns <today>
+        Then the record of pizza starts at the timestamp <timestamp>
+
+        Examples:
+            | backend   | moment               | timezone            | today      | timestamp  |
+            | in-memory | 2025-04-15T22:00:00Z | UTC                 | 2025-04-15 | 1744675200 |
+            | in-memory | 2025-04-15T22:00:00Z | Australia/Sydney    | 2025-04-16 | 1744725600 |
+            | in-memory | 2025-04-15T03:00:00Z | America/Los_Angeles | 2025-04-14 | 1744614000 |
+            | SQLite    | 2025-04-15T22:00:00Z | Australia/Sydney    | 2025-04-16 | 1744725600 |
+            | SQLite    | 2025-04-15T03:00:00Z | America/Los_Angeles | 2025-04-14 | 1744614000 |
+
+    Scenario Outline: Records keep their dates when the timezone changes
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+            | 1741737600 | bolognese    |
+            | 1741651200 | bolognese    |
+        When I set the timezone of the storage to <timezone>
+        Then the storage has the timezone <timezone>
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11, 2025-03-12
+        Then the storage, asked when rinderbraten was consumed, returns 2025-03-13
+        Then the record of rinderbraten starts at the timestamp <timestamp>
+
+        Examples:
+            | backend   | timezone            | timestamp  |
+            | in-memory | Australia/Sydney    | 1741784400 |
+            | in-memory | America/Los_Angeles | 1741849200 |
+            | SQLite    | Australia/Sydney    | 1741784400 |
+            | SQLite    | America/Los_Angeles | 1741849200 |
+
+    Scenario: The timezone is stored in the storage
+        Given an empty SQLite storage
+        When I set the timezone of the storage to Pacific/Auckland
+        When I reopen the storage
+        Then the storage has the timezone Pacific/Auckland
+
+    Scenario: Unknown timezones are refused
+        Then the timezone Mars/Olympus_Mons is unknown
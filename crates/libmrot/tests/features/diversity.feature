@@ -0,0 +1,107 @@
+Feature: Diversity
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Suggest one meal per tag
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | lasagne   |
+            | 1740960000 | carbonara |
+            | 1741046400 | curry     |
+            | 1741132800 | salmon    |
+        When I tag spaghetti with pasta
+        When I tag lasagne with pasta
+        When I tag carbonara with pasta, quick
+        When I tag salmon with fish, quick
+        When I ask for 3 meal suggestions with one meal per tag
+        Then I get the meal records 1740787200, spaghetti; 1741046400, curry; 1741132800, salmon
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Without the diversity rule, meals of a tag pile up
+        Given an in-memory storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | lasagne   |
+            | 1740960000 | carbonara |
+            | 1741046400 | curry     |
+        When I tag spaghetti with pasta
+        When I tag lasagne with pasta
+        When I tag carbonara with pasta
+        When I ask for 3 meal suggestions with the default diversity
+        Then I get the meal records 1740787200, spaghetti; 1740873600, lasagne; 1740960000, carbonara
+
+    Scenario Outline: Do not suggest the tags of recently eaten meals
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | lasagne   |
+            | 1741046400 | curry     |
+            | 1741132800 | salmon    |
+            | 1744502400 | penne     |
+        When I tag spaghetti with pasta
+        When I tag lasagne with pasta
+        When I tag penne with pasta
+        When I tag salmon with fish
+        When I ask for 3 meal suggestions without the tags eaten <look_back>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | look_back                           | records                                                       |
+            | in-memory | from 3 days ago through today       | 1741046400, curry; 1741132800, salmon                         |
+            | in-memory | from 10 days ago through 3 days ago | 1740787200, spaghetti; 1740873600, lasagne; 1741046400, curry |
+            | SQLite    | from 3 days ago through today       | 1741046400, curry; 1741132800, salmon                         |
+
+    Scenario: Both diversity rules together
+        Given an in-memory storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | lasagne   |
+            | 1741046400 | curry     |
+            | 1741132800 | salmon    |
+            | 1741219200 | trout     |
+            | 1744502400 | penne     |
+        When I tag spaghetti with pasta
+        When I tag lasagne with pasta
+        When I tag penne with pasta
+        When I tag salmon with fish
+        When I tag trout with fish
+        When I ask for 3 meal suggestions with one meal per tag and without the tags eaten from 3 days ago through today
+        Then I get the meal records 1741046400, curry; 1741132800, salmon
+
+    Scenario Outline: Plan one meal per tag
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | lasagne   |
+            | 1740960000 | carbonara |
+            | 1741046400 | curry     |
+            | 1741132800 | salmon    |
+        When I tag spaghetti with pasta
+        When I tag lasagne with pasta
+        When I tag carbonara with pasta
+        When I tag salmon with fish
+        When I plan the period from April 16 through April 19 with one meal per tag
+        Then I get the meal records 1744761600, spaghetti, planned; 1744848000, curry, planned; 1744934400, salmon, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: A meal in its cooldown does not take its tag from the plan
+        Given an in-memory storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | lasagne   |
+            | 1741046400 | curry     |
+        When I tag spaghetti with pasta
+        When I tag lasagne with pasta
+        When I set the cooldown of spaghetti to 50 days
+        When I plan the period from April 16 through April 18 with one meal per tag
+        Then I get the meal records 1744761600, lasagne, planned; 1744848000, curry, planned
@@ -0,0 +1,339 @@
+Feature: Import and Export
+
+    Scenario Outline: Export the records as CSV
+        Given a <backend> storage with the records
+            | date       | meal            |
+            | 1741824000 | rinderbraten    |
+            | 1741651200 | chili con carne |
+            | 1741651200 | bolognese       |
+        When I export the storage as CSV
+        Then the export is
+            """
+            date,meal,status
+            2025-03-11,bolognese,eaten
+            2025-03-11,chili con carne,eaten
+            2025-03-13,rinderbraten,eaten
+            """
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Export the records as JSON
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+            | 1741651200 | bolognese    |
+        When I export the storage as JSON
+        Then the export is
+            """
+            [
+              {"date":"2025-03-11","meal":"bolognese","status":"eaten"},
+              {"date":"2025-03-13","meal":"rinderbraten","status":"eaten"}
+            ]
+            """
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Export and import an empty storage
+        Given an empty <backend> storage
+        When I export the storage as <format>
+        When I import the export into an empty <backend> storage
+        Then the import reports 0 imported records and 0 duplicates
+        Then the storage knows no meals
+
+        Examples:
+            | backend   | format |
+            | in-memory | CSV    |
+            | in-memory | JSON   |
+            | SQLite    | CSV    |
+            | SQLite    | JSON   |
+
+    Scenario Outline: An export can be imported into another storage
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+            | 1741651200 | bolognese    |
+        When I export the storage as <format>
+        When I import the export into an empty <backend> storage
+        Then the import reports 2 imported records and 0 duplicates
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11
+        Then the storage, asked when rinderbraten was consumed, returns 2025-03-13
+
+        Examples:
+            | backend   | format |
+            | in-memory | CSV    |
+            | in-memory | JSON   |
+            | SQLite    | CSV    |
+            | SQLite    | JSON   |
+
+    Scenario Outline: An export keeps the status of the records
+        Given the current date is 2025-04-15
+        Given an empty <backend> storage
+        When I import into the storage the CSV
+            """
+            date,meal,status
+            2025-03-11,bolognese,skipped
+            2025-03-12,pizza,planned
+            2025-03-13,rinderbraten,eaten
+            """
+        When I export the storage as <format>
+        When I import the export into an empty <backend> storage
+        Then the import reports 3 imported records and 0 duplicates
+        Then the storage, asked to show the meal records in the period March 2025, returns 1741651200, bolognese, skipped; 1741737600, pizza, planned; 1741824000, rinderbraten
+
+        Examples:
+            | backend   | format |
+            | in-memory | CSV    |
+            | in-memory | JSON   |
+            | SQLite    | CSV    |
+            | SQLite    | JSON   |
+
+    Scenario Outline: Records without a status are planned after today and eaten otherwise
+        Given the current date is 2025-04-15
+        Given an empty <backend> storage
+        When I import into the storage the JSON
+            """
+            [
+              {"date":"2025-04-15","meal":"bolognese"},
+              {"date":"2025-04-16","meal":"pizza"}
+            ]
+            """
+        Then the storage, asked to show the meal records in the period April 2025, returns 1744675200, bolognese; 1744761600, pizza, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Duplicates are reported and skipped
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+        When I import into the storage the CSV
+            """
+            date,meal
+            2025-03-13,rinderbraten
+            2025-03-14,bolognese
+            2025-03-14,bolognese
+            """
+        Then the import reports 1 imported records and 2 duplicates
+        Then the import reports the duplicates 1741824000, rinderbraten; 1741910400, bolognese
+        Then the storage, asked when bolognese was consumed, returns 2025-03-14
+        Then the storage, asked when rinderbraten was consumed, returns 2025-03-13
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: A dry run does not change the storage
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+        When I import into the storage in a dry run the CSV
+            """
+            date,meal
+            2025-03-13,rinderbraten
+            2025-03-14,bolognese
+            """
+        Then the import reports 1 imported records and 1 duplicates
+        Then the storage knows the meals rinderbraten
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Malformed data is not imported at all
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+        When I import into the storage the CSV
+            """
+            date,meal
+            2025-03-14,bolognese
+            next tuesday,pizza
+            """
+        Then the import fails
+        Then the storage knows the meals rinderbraten
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: A JSON array which breaks off is not imported at all
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+        When I import into the storage the JSON
+            """
+            [
+              {"date":"2025-03-14","meal":"bolognese"},
+              {"date":"2025-03-15","meal":
+            """
+        Then the import fails
+        Then the storage knows the meals rinderbraten
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: CSV data must have a date and a meal column
+        Given an empty in-memory storage
+        When I import into the storage the CSV
+            """
+            day,meal
+            2025-03-14,bolognese
+            """
+        Then the import fails because the column date is missing
+
+    Scenario Outline: Export the records in a period as iCalendar
+        Given a <backend> storage with the records
+            | date       | meal              |
+            | 1741824000 | rinderbraten      |
+            | 1741737600 | pizza, margherita |
+            | 1741651200 | bolognese         |
+        When I export the storage as iCalendar in the period from March 12, 2025 through March 13, 2025
+        Then the export is
+            """
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//fleetingbytes//mrot//EN
+            CALSCALE:GREGORIAN
+            BEGIN:VEVENT
+            UID:20250312-88fc1bfcd95027e9@mrot
+            DTSTAMP:20250312T000000Z
+            DTSTART;VALUE=DATE:20250312
+            DTEND;VALUE=DATE:20250313
+            SUMMARY:pizza\, margherita
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:20250313-1e7d0bae97666b17@mrot
+            DTSTAMP:20250313T000000Z
+            DTSTART;VALUE=DATE:20250313
+            DTEND;VALUE=DATE:20250314
+            SUMMARY:rinderbraten
+            END:VEVENT
+            END:VCALENDAR
+            """
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: An iCalendar export can be imported into another storage
+        Given a <backend> storage with the records
+            | date       | meal                                                                         |
+            | 1741824000 | Schweinebraten mit Knödeln, Sauerkraut und noch vielen anderen Beilagen dazu |
+            | 1741651200 | bolognese                                                                    |
+        When I export the storage as iCalendar
+        When I import the export into an empty <backend> storage
+        Then the import reports 2 imported records and 0 duplicates
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11
+        Then the storage, asked when Schweinebraten mit Knödeln, Sauerkraut und noch vielen anderen Beilagen dazu was consumed, returns 2025-03-13
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Meals planned in a calendar application are imported from the event summaries
+        Given an empty in-memory storage
+        When I import into the storage the iCalendar
+            """
+            BEGIN:VCALENDAR
+            VERSION:2.0
+            PRODID:-//Some Calendar//EN
+            BEGIN:VTIMEZONE
+            TZID:Europe/Berlin
+            END:VTIMEZONE
+            BEGIN:VEVENT
+            UID:abc@example.com
+            DTSTART;VALUE=DATE:20250314
+            SUMMARY:chili con carne
+            END:VEVENT
+            BEGIN:VEVENT
+            UID:def@example.com
+            DTSTART;TZID=Europe/Berlin:20250315T120000
+            DTEND;TZID=Europe/Berlin:20250315T130000
+            SUMMARY:spaghetti\, aglio
+              e olio
+            BEGIN:VALARM
+            ACTION:DISPLAY
+            END:VALARM
+            END:VEVENT
+            END:VCALENDAR
+            """
+        Then the import reports 2 imported records and 0 duplicates
+        Then the storage, asked when chili con carne was consumed, returns 2025-03-14
+        Then the storage, asked when spaghetti, aglio e olio was consumed, returns 2025-03-15
+
+    Scenario: Events are imported on the day of their start in the timezone of the storage
+        Given an empty in-memory storage
+        When I set the timezone of the storage to Europe/Berlin
+        When I import into the storage the iCalendar
+            """
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            DTSTART:20250314T233000Z
+            SUMMARY:chili con carne
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART;TZID=America/New_York:20250315T200000
+            SUMMARY:pizza
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART;TZID=Europe/London:20250316T230000
+            SUMMARY:curry
+            END:VEVENT
+            BEGIN:VEVENT
+            DTSTART:20250317T233000
+            SUMMARY:bolognese
+            END:VEVENT
+            END:VCALENDAR
+            """
+        Then the import reports 4 imported records and 0 duplicates
+        Then the storage, asked when chili con carne was consumed, returns 2025-03-15
+        Then the storage, asked when pizza was consumed, returns 2025-03-16
+        Then the storage, asked when curry was consumed, returns 2025-03-17
+        Then the storage, asked when bolognese was consumed, returns 2025-03-17
+
+    Scenario: The summary of an alarm is not the meal of its event
+        Given an empty in-memory storage
+        When I import into the storage the iCalendar
+            """
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            DTSTART;VALUE=DATE:20250314
+            BEGIN:VALARM
+            ACTION:EMAIL
+            SUMMARY:lunch is ready
+            DTSTART:20250101T000000Z
+            END:VALARM
+            SUMMARY:chili con carne
+            END:VEVENT
+            END:VCALENDAR
+            """
+        Then the import reports 1 imported records and 0 duplicates
+        Then the storage, asked when chili con carne was consumed, returns 2025-03-14
+        Then the storage knows the meals chili con carne
+
+    Scenario: Events without a summary are not imported
+        Given an empty in-memory storage
+        When I import into the storage the iCalendar
+            """
+            BEGIN:VCALENDAR
+            BEGIN:VEVENT
+            DTSTART;VALUE=DATE:20250314
+            END:VEVENT
+            END:VCALENDAR
+            """
+        Then the import fails because the calendar is invalid
@@ -0,0 +1,272 @@
+Feature: Journal
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Operations are journaled
+        Given an empty <backend> storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When I add the meal curry on the dates from April 2, 2025 through April 3, 2025 to the storage
+        When I rename pizza to calzone in the storage
+        When I remove the records in April 3, 2025 from the storage
+        When I skip the records in April 2, 2025
+        Then the journal lists the operations
+            | operation                                   | undone |
+            | add pizza (2025-04-01)                      | false  |
+            | add curry (2025-04-02), curry (2025-04-03)  | false  |
+            | rename pizza to calzone: pizza (2025-04-01) | false  |
+            | remove curry (2025-04-03)                   | false  |
+            | mark curry (2025-04-02) as skipped          | false  |
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Operations which change nothing are not journaled
+        Given an empty <backend> storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When I remove the records in April 3, 2025 from the storage
+        When I rename soup to stew in the storage
+        When I rename pizza to pizza in the storage
+        Then the journal lists the operations
+            | operation              | undone |
+            | add pizza (2025-04-01) | false  |
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undo a removal
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+            | 1741651200 | bolognese    |
+        When I remove the records in March 2025 from the storage
+        Then the storage knows no records
+        When I undo the latest operation
+        Then the undone operation was remove bolognese (2025-03-11), rinderbraten (2025-03-13)
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11
+        Then the storage, asked when rinderbraten was consumed, returns 2025-03-13
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undo and redo an addition
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1741651200 | bolognese |
+        When I add the meal bolognese on the date March 11, 2025 to the storage
+        When I undo the latest operation
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11
+        When I redo the earliest undone operation
+        Then the redone operation was add bolognese (2025-03-11)
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11, 2025-03-11
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undoing a rename of a whole meal keeps its id
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1741651200 | bolognese |
+            | 1741737600 | curry     |
+            | 1741824000 | bolognese |
+        When I rename bolognese to carbonara in the storage
+        Then the meal carbonara has the id 1
+        When I undo the latest operation
+        Then the meal bolognese has the id 1
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11, 2025-03-13
+        Then the storage has no records of carbonara
+        When I redo the earliest undone operation
+        Then the meal carbonara has the id 1
+        Then the storage, asked when carbonara was consumed, returns 2025-03-11, 2025-03-13
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undo a rename in a period and a merge
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1741651200 | bolognese |
+            | 1741737600 | curry     |
+            | 1741824000 | bolognese |
+        When I rename bolognese to curry in the storage in the period March 13, 2025
+        When I rename curry to korma in the storage
+        When I undo the latest operation
+        When I undo the latest operation
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11, 2025-03-13
+        Then the storage, asked when curry was consumed, returns 2025-03-12
+        Then the storage has no records of korma
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undoing a merging rename restores the replaced meal
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1741651200 | bolognese |
+            | 1741737600 | curry     |
+        When I set the cooldown of bolognese to 5 days
+        When I tag bolognese with pasta
+        When I add the aliases spag bol to bolognese
+        When I rename bolognese to curry in the storage
+        Then the storage does not know the meal bolognese
+        When I undo the latest operation
+        Then the meal bolognese has the id 1
+        Then the meal bolognese has a cooldown of 5 days, the tags pasta and the aliases spag bol
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11
+        Then the storage, asked when curry was consumed, returns 2025-03-12
+        When I redo the earliest undone operation
+        Then the storage does not know the meal bolognese
+        Then the storage, asked when curry was consumed, returns 2025-03-11, 2025-03-12
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undoing a rename in a period restores the meal it emptied
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1741651200 | bolognese |
+        When I rename bolognese to carbonara in the storage in the period March 2025
+        Then the storage does not know the meal bolognese
+        When I undo the latest operation
+        Then the meal bolognese has the id 1
+        Then the storage does not know the meal carbonara
+        When I redo the earliest undone operation
+        Then the storage does not know the meal bolognese
+        Then the storage, asked when carbonara was consumed, returns 2025-03-11
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undoing a removal restores the meal it emptied
+        Given a <backend> storage with the records
+            | date       | meal      |
+            | 1741651200 | bolognese |
+            | 1741737600 | curry     |
+        When I remove the records in March 11, 2025 from the storage
+        Then the storage does not know the meal bolognese
+        When I undo the latest operation
+        Then the meal bolognese has the id 1
+        Then the storage, asked when bolognese was consumed, returns 2025-03-11
+        When I redo the earliest undone operation
+        Then the storage does not know the meal bolognese
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undoing an operation on a meal merged by an alias
+        Given an empty <backend> storage
+        When I add the meal spaghetti on the date April 1, 2025 to the storage
+        When I add the meal spag bol on the date April 2, 2025 to the storage
+        When I add the aliases spag bol to spaghetti
+        When I undo the latest operation
+        Then the undone operation was add spaghetti (2025-04-02)
+        Then the storage, asked when spaghetti was consumed, returns 2025-04-01
+        When I redo the earliest undone operation
+        Then the storage, asked when spaghetti was consumed, returns 2025-04-01, 2025-04-02
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Operations whose records have changed cannot be undone
+        Given an empty SQLite storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When another program deletes all records from the storage file
+        When I undo the latest operation
+        Then the undo failed because the records of add pizza (2025-04-01) have changed
+        Then the journal lists the operations
+            | operation              | undone |
+            | add pizza (2025-04-01) | false  |
+
+    Scenario Outline: Operations are undone from the latest and redone from the earliest
+        Given an empty <backend> storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When I add the meal curry on the date April 2, 2025 to the storage
+        When I add the meal soup on the date April 3, 2025 to the storage
+        When I undo the latest operation
+        When I undo the latest operation
+        Then the journal lists the operations
+            | operation              | undone |
+            | add pizza (2025-04-01) | false  |
+            | add curry (2025-04-02) | true   |
+            | add soup (2025-04-03)  | true   |
+        When I redo the earliest undone operation
+        Then the redone operation was add curry (2025-04-02)
+        Then the storage knows the meals pizza, curry
+        Then the meal curry has the id 2
+        Then the storage, asked when curry was consumed, returns 2025-04-02
+        Then the storage has no records of soup
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: A new operation discards the undone operations
+        Given an empty <backend> storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When I add the meal curry on the date April 2, 2025 to the storage
+        When I undo the latest operation
+        When I add the meal soup on the date April 3, 2025 to the storage
+        When I redo the earliest undone operation
+        Then there was nothing to redo
+        Then the journal lists the operations
+            | operation              | undone |
+            | add pizza (2025-04-01) | false  |
+            | add soup (2025-04-03)  | false  |
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Nothing to undo in a new storage
+        Given an empty <backend> storage
+        When I undo the latest operation
+        Then there was nothing to undo
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: The journal is kept in the storage
+        Given an empty SQLite storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When I reopen the storage
+        When I undo the latest operation
+        Then the undone operation was add pizza (2025-04-01)
+        Then the storage knows no records
+
+    Scenario Outline: Operations journaled before the timezone changed can be undone
+        Given an empty <backend> storage
+        When I add the meal pizza on the date April 1, 2025 to the storage
+        When I set the timezone of the storage to Australia/Sydney
+        When I undo the latest operation
+        Then the storage knows no records
+        When I redo the earliest undone operation
+        Then the storage, asked when pizza was consumed, returns 2025-04-01
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
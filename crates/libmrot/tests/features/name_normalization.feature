@@ -0,0 +1,98 @@
+Feature: Name Normalization
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: A meal is found regardless of its spelling
+        Given a <backend> storage with the records
+            | date       | meal              |
+            | 1740787200 | gratin à la m. o. |
+            | 1740873600 | šunkafleky        |
+        Then the storage, asked when <spelling> was consumed, returns <dates>
+
+        Examples:
+            | backend   | spelling           | dates      |
+            | in-memory | Gratin À La M. O.  | 2025-03-01 |
+            | in-memory | gratin  à la m. o. | 2025-03-01 |
+            | in-memory | ŠUNKAFLEKY         | 2025-03-02 |
+            | in-memory | šunkafleky         | 2025-03-02 |
+            | SQLite    | GRATIN À LA M. O.  | 2025-03-01 |
+            | SQLite    | šunkafleky         | 2025-03-02 |
+
+    Scenario Outline: A meal keeps its original spelling
+        Given a <backend> storage with the records
+            | date       | meal              |
+            | 1740787200 | Gratin à la M. O. |
+        When I add the meal gratin  À LA m. o. on the date 2025-03-05 to the storage
+        When I add the meal Šunkafleky on the date 2025-03-06 to the storage
+        When I add the meal šunkafleky on the date 2025-03-07 to the storage
+        Then the storage knows the meals Gratin à la M. O., Šunkafleky
+        And the storage, asked when gratin à la m. o. was consumed, returns 2025-03-01, 2025-03-05
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Unique meals group the spellings of a meal
+        Given an in-memory store with the records spelled as they are
+            | date       | meal   |
+            | 1740787200 | Pizza  |
+            | 1740873600 | pizza  |
+            | 1740960000 | curry  |
+            | 1741046400 | PIZZA  |
+            | 1741132800 | lentils|
+        When I ask for unique meals
+        Then I get the meal records 1740960000, curry; 1741046400, PIZZA; 1741132800, lentils
+
+    Scenario: Suggestions group the spellings of a meal
+        Given an in-memory store with the records spelled as they are
+            | date       | meal  |
+            | 1740787200 | Pizza |
+            | 1740873600 | curry |
+            | 1740960000 | pizza |
+        When I ask for 3 meal suggestions ignoring lentils
+        Then I get the meal records 1740873600, curry; 1740960000, pizza
+
+    Scenario: Statistics count the records of all spellings of a meal
+        Given an in-memory store with the records spelled as they are
+            | date       | meal  |
+            | 1717200000 | pasta |
+            | 1742256000 | Pasta |
+            | 1742860800 | pasta |
+            | 1743465600 | PASTA |
+        When I ask for 1 overdue meals with their statistics
+        Then the meal PASTA is expected on 2025-04-08
+
+    Scenario: An exact spelling comes first
+        Given an in-memory store with the records spelled as they are
+            | date       | meal  |
+            | 1740787200 | Pizza |
+            | 1740873600 | pizza |
+        Then the storage, asked when pizza was consumed, returns 2025-03-02
+        And the storage, asked when Pizza was consumed, returns 2025-03-01
+        And the storage, asked when PIZZA was consumed, returns 2025-03-01
+
+    Scenario Outline: The ignore list matches any spelling
+        Given a <backend> storage with the records
+            | date       | meal              |
+            | 1740787200 | gratin à la m. o. |
+            | 1740873600 | šunkafleky        |
+            | 1740960000 | pizza             |
+        When I ask for 3 meal suggestions ignoring <ignore_list>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | ignore_list                    | records                             |
+            | in-memory | GRATIN À LA M. O.              | 1740873600, šunkafleky; 1740960000, pizza |
+            | in-memory | šunkafleky, Pizza              | 1740787200, gratin à la m. o.       |
+            | SQLite    | Gratin  à la M. O., ŠUNKAFLEKY | 1740960000, pizza                   |
+
+    Scenario: Aliases match any spelling
+        Given an in-memory storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti bolognese |
+        When I add the aliases Spag Bol to spaghetti bolognese
+        When I add the meal SPAG  BOL on the date 2025-03-05 to the storage
+        Then the storage knows the meals spaghetti bolognese
+        And the storage, asked when spag bol was consumed, returns 2025-03-01, 2025-03-05
@@ -0,0 +1,81 @@
+Feature: Plan meals
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Fill the days of a period
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1743465600 | curry    |
+            | 1744070400 | pasta    |
+            | 1735689600 | duck     |
+        When I plan the period 2025-04-16 through 2025-04-18
+        Then I get the meal records 1744761600, duck, planned; 1744848000, raclette, planned; 1744934400, curry, planned
+        And the storage, asked to show the meal records in the period 2025-04-01 through 2025-04-30, returns 1743465600, curry; 1744070400, pasta
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Record a plan
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1743465600 | curry    |
+            | 1735689600 | duck     |
+        When I plan the period 2025-04-16 through 2025-04-17
+        And I record the plan
+        Then the storage, asked to show the meal records in the period 2025-04-16 through 2025-04-30, returns 1744761600, duck, planned; 1744848000, raclette, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Days with a record are not planned and their meals are not repeated
+        Given an in-memory storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1743465600 | curry    |
+            | 1744070400 | pasta    |
+            | 1735689600 | duck     |
+            | 1744848000 | curry    |
+        When I plan the period 2025-04-16 through 2025-04-19
+        Then I get the meal records 1744761600, duck, planned; 1744934400, raclette, planned; 1745020800, pasta, planned
+
+    Scenario: Days stay empty when the candidates run out
+        Given an in-memory storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1735689600 | duck     |
+        When I plan the period 2025-04-16 through 2025-04-20
+        Then I get the meal records 1744761600, duck, planned; 1744848000, raclette, planned
+
+    Scenario Outline: Ignored meals are not planned
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1743465600 | curry    |
+            | 1744070400 | pasta    |
+            | 1735689600 | duck     |
+            | 1745193600 | pasta    |
+        When I plan the period 2025-04-16 through 2025-04-18, ignoring <ignore_list> and ignore period <ignore_period>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | ignore_list | ignore_period                 | records                                                                               |
+            | in-memory | duck        | 2025-04-21 through 2025-04-25 | 1744761600, raclette, planned; 1744848000, curry, planned                             |
+            | in-memory | duck        | None                          | 1744761600, raclette, planned; 1744848000, curry, planned; 1744934400, pasta, planned |
+            | SQLite    | duck        | 2025-04-21 through 2025-04-25 | 1744761600, raclette, planned; 1744848000, curry, planned                             |
+
+    Scenario: A meal is not planned within its cooldown
+        Given an in-memory storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+            | 1745107200 | raclette |
+            | 1735689600 | duck     |
+        When I set the cooldown of raclette to 5 days
+        And I plan the period 2025-04-22 through 2025-04-26
+        Then I get the meal records 1745280000, duck, planned; 1745539200, raclette, planned
@@ -29,3 +29,66 @@ Feature: Suggest Meals
             | possibilities           |
             | rinderbraten, bolognese |
 
+
+    Scenario: No weighted random meals from empty storage
+        Given an in-memory storage with the records
+            | date       | meal               |
+        When I ask for 3 weighted random meals
+        Then I get no weighted random meals
+
+    Scenario Outline: Weighted random meals are distinct
+        Given the current date is 2025-04-15
+        Given a <backend> storage with the records
+            | date       | meal               |
+            | 1741824000 | rinderbraten       |
+            | 1741737600 | bolognese          |
+            | 1741651200 | bolognese          |
+            | 1741564800 | rougaille saucisse |
+            | 1741392000 | pizza              |
+        When I ask for <number> weighted random meals
+        Then I get <distinct> distinct meals out of rinderbraten, bolognese, rougaille saucisse, pizza
+
+        Examples:
+            | backend   | number | distinct |
+            | in-memory | 1      | 1        |
+            | in-memory | 3      | 3        |
+            | in-memory | 10     | 4        |
+            | SQLite    | 3      | 3        |
+            | SQLite    | 10     | 4        |
+
+    Scenario Outline: Weighted random meals respect the ignore list and the ignore period
+        Given the current date is 2025-04-15
+        Given a <backend> storage with the records
+            | date       | meal               |
+            | 1745020800 | rinderbraten       |
+            | 1741824000 | rinderbraten       |
+            | 1741737600 | bolognese          |
+            | 1741564800 | rougaille saucisse |
+            | 1741392000 | pizza              |
+        When I ask for 10 weighted random meals ignoring pizza and the meals in the period from tomorrow through 11 days after tomorrow
+        Then I get the meals bolognese, rougaille saucisse in any order
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: The same seed picks the same weighted random meals
+        Given the current date is 2025-04-15
+        Given an in-memory storage with the records
+            | date       | meal               |
+            | 1741824000 | rinderbraten       |
+            | 1741737600 | bolognese          |
+            | 1741564800 | rougaille saucisse |
+            | 1741392000 | pizza              |
+        When I ask for 2 weighted random meals with the seed 42
+        Then I get 2 distinct meals out of rinderbraten, bolognese, rougaille saucisse, pizza
+        Then asking again with the seed 42 picks the same meals
+
+    Scenario: Meals not consumed for a long time are picked more often
+        Given the current date is 2025-04-15
+        Given an in-memory storage with the records
+            | date       | meal               |
+            | 1744588800 | rinderbraten       |
+            | 1735689600 | bolognese          |
+        Then out of 200 weighted random meals with the seeds 0 and up, bolognese is picked more often than rinderbraten
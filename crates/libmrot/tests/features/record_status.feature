@@ -0,0 +1,186 @@
+Feature: Record Status
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Future records are planned, the others eaten
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744070400 | pasta |
+            | 1744675200 | duck  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Then the storage, asked to show the meal records in the period 2025-04-01 through 2025-04-30, returns 1744070400, pasta; 1744675200, duck; 1744761600, pizza, planned; 1744848000, curry, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Planned records become pending when their day passes
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744070400 | pasta |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+            | 1745107200 | duck  |
+        Given the current date changes to 2025-04-18
+        When I ask for the pending records
+        Then I get the meal records 1744761600, pizza, planned; 1744848000, curry, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Confirm the pending records
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+            | 1745107200 | duck  |
+        Given the current date changes to 2025-04-18
+        When I confirm the pending records
+        Then I get the meal records 1744761600, pizza; 1744848000, curry
+        And there are no pending records
+        And the storage, asked to show the meal records in the period 2025-04-16 through 2025-04-30, returns 1744761600, pizza; 1744848000, curry; 1745107200, duck, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Confirm the records of a meal in a period
+        Given an in-memory storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Given the current date changes to 2025-04-18
+        When I confirm the records of pizza in the period 2025-04-16 through 2025-04-17
+        Then I get the meal records 1744761600, pizza
+        When I ask for the pending records
+        Then I get the meal records 1744848000, curry, planned
+
+    Scenario Outline: Skip the pending records
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Given the current date changes to 2025-04-18
+        When I skip the pending records
+        Then I get the meal records 1744761600, pizza, skipped; 1744848000, curry, skipped
+        And there are no pending records
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Skipped records do not count as eaten
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1740787200 | pasta |
+            | 1743465600 | curry |
+            | 1744588800 | pasta |
+        When I skip the records in the period 2025-04-14
+        When I ask for 2 meal suggestions
+        Then I get the meal records 1740787200, pasta; 1743465600, curry
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: A meal with only skipped records is still suggested
+        Given an in-memory storage with the records
+            | date       | meal  |
+            | 1743465600 | curry |
+            | 1744588800 | pasta |
+        When I skip the records in the period 2025-04-14
+        When I ask for 2 meal suggestions
+        Then I get the meal records 1743465600, curry; 1744588800, pasta, skipped
+
+    Scenario: A skipped plan does not ignore the meal
+        Given an in-memory storage with the records
+            | date       | meal  |
+            | 1743465600 | curry |
+            | 1744070400 | pasta |
+            | 1745107200 | pasta |
+        When I skip the records in the period 2025-04-20
+        When I ask for 2 meal suggestions, ignoring the period 2025-04-16 through 2025-04-30
+        Then I get the meal records 1743465600, curry; 1744070400, pasta
+
+    Scenario Outline: The status survives undoing a removal
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Given the current date changes to 2025-04-18
+        When I skip the records in the period 2025-04-16
+        And I remove the records in 2025-04-16 through 2025-04-17 from the storage
+        And I undo the latest operation
+        Then the storage, asked to show the meal records in the period 2025-04-16 through 2025-04-17, returns 1744761600, pizza, skipped; 1744848000, curry, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: The status is kept in the SQLite storage
+        Given a SQLite storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Given the current date changes to 2025-04-18
+        When I skip the records in the period 2025-04-16
+        And I reopen the storage
+        When I ask for the pending records
+        Then I get the meal records 1744848000, curry, planned
+
+    Scenario Outline: Confirming a period leaves the records of today and later days planned
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+            | 1745107200 | duck  |
+        Given the current date changes to 2025-04-17
+        When I confirm the records in the period 2025-04-16 through 2025-04-30
+        Then I get the meal records 1744761600, pizza
+        When I confirm the records in the period 2025-04-20
+        Then no records changed
+        And the storage, asked to show the meal records in the period 2025-04-16 through 2025-04-30, returns 1744761600, pizza; 1744848000, curry, planned; 1745107200, duck, planned
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Undo and redo confirming the pending records
+        Given a <backend> storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Given the current date changes to 2025-04-18
+        When I skip the records in the period 2025-04-16
+        And I confirm the records in the period 2025-04-16 through 2025-04-17
+        And I undo the latest operation
+        Then the storage, asked to show the meal records in the period 2025-04-16 through 2025-04-17, returns 1744761600, pizza, skipped; 1744848000, curry, planned
+        When I redo the earliest undone operation
+        Then the storage, asked to show the meal records in the period 2025-04-16 through 2025-04-17, returns 1744761600, pizza; 1744848000, curry
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Skipping is undone after reopening the SQLite storage
+        Given a SQLite storage with the records
+            | date       | meal  |
+            | 1744761600 | pizza |
+            | 1744848000 | curry |
+        Given the current date changes to 2025-04-18
+        When I skip the pending records
+        And I reopen the storage
+        And I undo the latest operation
+        When I ask for the pending records
+        Then I get the meal records 1744761600, pizza, planned; 1744848000, curry, planned
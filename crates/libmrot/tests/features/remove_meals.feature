@@ -1,5 +1,8 @@
 Feature: Remove Meals
 
+    Background:
+        Given the current date is 2025-04-15
+
     Scenario Outline: Remove meals of all kinds in a given period
         Given an in-memory storage with the records
             | date       | meal                |
@@ -58,3 +61,49 @@ Feature: Remove Meals
             | gratin à la m. o. | March 2025    | 1742083200, gratin à la m. o.; 1742169600, gratin à la m. o.; 1742256000, gratin à la m. o. | March 2025  | 1741651200, bolognese; 1741737600, bolognese; 1741824000, rinderbraten; 1741910400, flammkuchen; 1741996800, tortelloni; 1742342400, bramboráky; 1742428800, bramboráky; 1742515200, rougailles saucisse; 1742601600, rougailles saucisse; 1742688000, spätzle; 1742774400, spätzle                                                                                              |
             | gratin à la m. o. | February 2025 |                                                                                             | March 2025  | 1741651200, bolognese; 1741737600, bolognese; 1741824000, rinderbraten; 1741910400, flammkuchen; 1741996800, tortelloni; 1742083200, gratin à la m. o.; 1742169600, gratin à la m. o.; 1742256000, gratin à la m. o.; 1742342400, bramboráky; 1742428800, bramboráky; 1742515200, rougailles saucisse; 1742601600, rougailles saucisse; 1742688000, spätzle; 1742774400, spätzle |
             | curry             | March 2025    |                                                                                             | March 2025  | 1741651200, bolognese; 1741737600, bolognese; 1741824000, rinderbraten; 1741910400, flammkuchen; 1741996800, tortelloni; 1742083200, gratin à la m. o.; 1742169600, gratin à la m. o.; 1742256000, gratin à la m. o.; 1742342400, bramboráky; 1742428800, bramboráky; 1742515200, rougailles saucisse; 1742601600, rougailles saucisse; 1742688000, spätzle; 1742774400, spätzle |
+
+    Scenario Outline: Preview removing meals without removing them
+        Given a <backend> storage with the records
+            | date       | meal       |
+            | 1741996800 | tortelloni |
+            | 1741910400 | bolognese  |
+            | 1741824000 | tortelloni |
+        When I preview removing all meals in the period March 14, 2025 through March 15, 2025
+        Then I get the meal records 1741910400, bolognese; 1741996800, tortelloni
+        When I preview removing the meal tortelloni in the period March 2025
+        Then I get the meal records 1741824000, tortelloni; 1741996800, tortelloni
+        Then the storage, asked to show the meal records in the period March 2025, returns 1741824000, tortelloni; 1741910400, bolognese; 1741996800, tortelloni
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Removing all records of a meal forgets the meal
+        Given a <backend> storage with the records
+            | date       | meal       |
+            | 1741996800 | tortelloni |
+            | 1741910400 | bolognese  |
+            | 1741824000 | tortelloni |
+        When I remove the meal tortelloni in the period March 2025
+        Then the storage knows the meals bolognese
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Remove one of several records of a meal on a day
+        Given a <backend> storage with the records
+            | date       | meal       |
+            | 1741996800 | tortelloni |
+            | 1741996800 | tortelloni |
+            | 1741910400 | bolognese  |
+        When I remove exactly the records 1741996800, tortelloni; 1741651200, bolognese
+        Then I get the meal records 1741996800, tortelloni
+        Then the storage, asked to show the meal records in the period March 2025, returns 1741910400, bolognese; 1741996800, tortelloni
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
@@ -1,5 +1,8 @@
 Feature: Rename Meals
 
+    Background:
+        Given the current date is 2025-04-15
+
     Scenario Outline: Rename the records of a meal
         Given an in-memory storage with the records
             | date       | meal         |
@@ -18,3 +21,85 @@ Feature: Rename Meals
             | spaghetti | bolognese | from March 11 through March 12, 2025 | 1741651200, spaghetti; 1741737600, spaghetti                                               | 2025-03-11, 2025-03-12                         |
             | spaghetti | bolognese | from March 14 through March 15, 2025 | 1741910400, spaghetti; 1741996800, spaghetti                                               | 2025-03-14, 2025-03-15                         |
             | spaghetti | bolognese | from March 12 through March 14, 2025 | 1741737600, spaghetti; 1741910400, spaghetti                                               | 2025-03-12, 2025-03-14                         |
+
+    Scenario Outline: Renaming a meal in all periods keeps its id
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741996800 | spaghetti    |
+            | 1741910400 | spaghetti    |
+            | 1741824000 | rinderbraten |
+        When I rename the meal <old_name> to <new_name> in the period None
+        Then the meal <new_name> has the id <id>
+        Then the storage knows the meals <meals>
+
+        Examples:
+            | backend   | old_name     | new_name  | id | meals                   |
+            | in-memory | spaghetti    | bolognese | 1  | bolognese, rinderbraten |
+            | in-memory | rinderbraten | roastbeef | 2  | spaghetti, roastbeef    |
+            | SQLite    | spaghetti    | bolognese | 1  | bolognese, rinderbraten |
+            | SQLite    | rinderbraten | roastbeef | 2  | spaghetti, roastbeef    |
+
+    Scenario Outline: Renaming a meal to the name of another meal merges their records
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741996800 | spaghetti    |
+            | 1741910400 | bolognese    |
+            | 1741824000 | spaghetti    |
+        When I rename the meal <old_name> to <new_name> in the period None
+        Then the storage knows the meals <meals>
+        Then the storage, asked when <new_name> was consumed, returns <new_name_dates>
+
+        Examples:
+            | backend   | old_name  | new_name  | meals     | new_name_dates                     |
+            | in-memory | spaghetti | bolognese | bolognese | 2025-03-13, 2025-03-14, 2025-03-15 |
+            | SQLite    | spaghetti | bolognese | bolognese | 2025-03-13, 2025-03-14, 2025-03-15 |
+
+    Scenario Outline: Renaming all records of a meal in a period forgets the old meal
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741996800 | spaghetti    |
+            | 1741910400 | spaghetti    |
+            | 1741824000 | rinderbraten |
+        When I rename the meal spaghetti to bolognese in the period March 2025
+        Then the storage knows the meals rinderbraten, bolognese
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Renaming a meal to another spelling of its name corrects the spelling
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741996800 | spaghetti    |
+            | 1741910400 | spaghetti    |
+            | 1741824000 | rinderbraten |
+        When I rename the meal spaghetti to <new_name> in the period <period>
+        Then I get the meal records 1741910400, spaghetti; 1741996800, spaghetti
+        Then the meal <new_name> has the id 1
+        Then the storage knows the meals <new_name>, rinderbraten
+
+        Examples:
+            | backend   | new_name  | period         |
+            | in-memory | Spaghetti | None           |
+            | in-memory | SPAGHETTI | March 15, 2025 |
+            | SQLite    | Spaghetti | None           |
+            | SQLite    | SPAGHETTI | March 15, 2025 |
+
+    Scenario Outline: Preview renaming a meal without renaming it
+        Given a <backend> storage with the records
+            | date       | meal         |
+            | 1741996800 | spaghetti    |
+            | 1741910400 | spaghetti    |
+            | 1741824000 | rinderbraten |
+        When I preview renaming the meal spaghetti in the period <period>
+        Then I get the meal records <renamed_records>
+        Then the storage knows the meals spaghetti, rinderbraten
+        Then the storage, asked when spaghetti was consumed, returns 2025-03-14, 2025-03-15
+
+        Examples:
+            | backend   | period         | renamed_records                              |
+            | in-memory | None           | 1741910400, spaghetti; 1741996800, spaghetti |
+            | in-memory | March 15, 2025 | 1741996800, spaghetti                        |
+            | SQLite    | None           | 1741910400, spaghetti; 1741996800, spaghetti |
+            | SQLite    | March 15, 2025 | 1741996800, spaghetti                        |
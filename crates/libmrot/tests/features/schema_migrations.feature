@@ -0,0 +1,93 @@
+Feature: Schema Migrations
+
+    Scenario: A new storage has the current schema version
+        Given a storage file which does not exist yet
+        When I open the storage
+        Then the storage has the current schema version
+
+    Scenario Outline: A storage created before schema versioning is migrated
+        Given a legacy storage file with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+            | 1741737600 | bolognese    |
+            | 1741651200 | bolognese    |
+        When I open the storage
+        Then the storage has the current schema version
+        Then the storage knows the meals rinderbraten, bolognese
+        Then the storage has the timezone UTC
+        Then the storage, asked when <meal> was consumed, returns <naive_dates>
+
+        Examples:
+            | meal         | naive_dates            |
+            | bolognese    | 2025-03-11, 2025-03-12 |
+            | rinderbraten | 2025-03-13             |
+
+    Scenario: Records after the migration are planned
+        Given a legacy storage file with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+            | 4102444800 | bolognese    |
+        When I open the storage
+        Then the storage, asked to show the meal records in the period 2025-03-13 through 2100-01-01, returns 1741824000, rinderbraten; 4102444800, bolognese, planned
+
+    Scenario: Records after the moment of the migration are planned
+        Given a legacy storage file with the records
+            | date       | meal         |
+            | 1741651200 | bolognese    |
+            | 1741824000 | rinderbraten |
+        When I open the storage at 2025-03-12T12:00:00Z
+        Then the meal bolognese is eaten
+        Then the meal rinderbraten is planned
+
+    Scenario Outline: Records after today in the timezone of the storage are planned after the migration
+        Given a storage file with schema version 5 in the timezone <timezone> with records of bolognese, pizza, curry
+        When I open the storage
+        Then the meal bolognese is eaten
+        Then the meal pizza is eaten
+        Then the meal curry is planned
+
+        Examples:
+            | timezone           |
+            | UTC                |
+            | Pacific/Kiritimati |
+            | Pacific/Honolulu   |
+
+    Scenario: Meals spelled alike are merged into the meal recorded first
+        Given a storage file with schema version 8 with the dishes
+            | id | name  | cooldown | tags                | aliases | date       |
+            | 1  | Pizza |          | italian             |         | 1741651200 |
+            | 2  | curry |          |                     |         | 1741737600 |
+            | 3  | PIZZA | 5        | italian, vegetarian | pie     | 1741824000 |
+        When I open the storage
+        Then the storage has the current schema version
+        Then the storage knows the meals Pizza, curry
+        Then the meal Pizza has the id 1
+        Then the meal Pizza has a cooldown of 5 days, the tags italian, vegetarian and the aliases pie
+        Then the storage, asked when Pizza was consumed, returns 2025-03-11, 2025-03-13
+        Then the journaled operation is add Pizza (2025-03-11), curry (2025-03-12), Pizza (2025-03-13)
+
+    Scenario: A storage is opened repeatedly
+        Given a storage file which does not exist yet
+        When I open the storage
+        When I open the storage
+        Then the storage has the current schema version
+
+    Scenario: A storage from a newer version of the library is refused
+        Given a storage file with schema version 9999
+        When I open the storage
+        Then opening the storage fails because its schema version 9999 is too new
+
+    Scenario: A storage with an outdated schema is not migrated when opened read-only
+        Given a storage file with schema version 5 in the timezone UTC with records of bolognese, pizza, curry
+        When I open the storage read-only
+        Then opening the storage fails because its schema version 5 is outdated
+        Then the storage file still has schema version 5
+
+    Scenario: A storage opened read-only can be read but not changed
+        Given a legacy storage file with the records
+            | date       | meal         |
+            | 1741824000 | rinderbraten |
+        When I open the storage
+        When I open the storage read-only
+        Then the storage knows the meals rinderbraten
+        Then changing the storage fails
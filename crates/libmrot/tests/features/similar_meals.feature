@@ -0,0 +1,37 @@
+Feature: Similar Meals
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Suggest meals with similar names
+        Given a <backend> storage with the records
+            | date       | meal                |
+            | 1740787200 | spaghetti           |
+            | 1740873600 | spaghetti bolognese |
+            | 1740960000 | pizza               |
+            | 1741046400 | pita                |
+            | 1741132800 | šunkafleky          |
+            | 1741219200 | gratin à la m. o.   |
+        Then the meals similar to <name> are <meals>
+
+        Examples:
+            | backend   | name              | meals                |
+            | in-memory | spagetti          | spaghetti            |
+            | in-memory | SPAGETTI          | spaghetti            |
+            | in-memory | pitza             | pita, pizza          |
+            | in-memory | pizzza            | pizza                |
+            | in-memory | sunkafleky        | šunkafleky           |
+            | in-memory | gratin a la m. o. | gratin à la m. o.    |
+            | in-memory | spaghetti bolonese | spaghetti bolognese |
+            | SQLite    | spagetti          | spaghetti            |
+            | SQLite    | pitza             | pita, pizza          |
+
+    Scenario: Names which are too different are not similar
+        Given an in-memory storage with the records
+            | date       | meal      |
+            | 1740787200 | spaghetti |
+            | 1740873600 | pizza     |
+        Then no meals are similar to ratatouille
+        And no meals are similar to spaghetti
+        And no meals are similar to Pizza
+        And no meals are similar to pasta
@@ -0,0 +1,51 @@
+Feature: Statistics
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Statistics of the eaten meals
+        Given a <backend> storage with the records
+            | date       | meal    |
+            | 1740787200 | pizza   |
+            | 1740873600 | curry   |
+            | 1740960000 | steak   |
+            | 1741392000 | pizza   |
+            | 1741478400 | curry   |
+            | 1741564800 | lentils |
+            | 1741651200 | goulash |
+            | 1742083200 | pizza   |
+        Then the statistics count 8 records of 5 distinct meals
+        And the 2 most frequent meals are pizza, curry
+        And the meals eaten once are goulash, lentils, steak
+        And the repeat interval of pizza is 7.5 days on average and 7.5 days in the median
+        And the repeat interval of curry is 7 days on average and 7 days in the median
+        And the longest streak without a repeated meal has 5 meals from 2025-03-03 through 2025-03-11
+        And the busiest weekday is Sun with 3 meals
+        And the statistics of from March 8, 2025 through March 16, 2025 count 5 records of 4 distinct meals
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Planned meals are not counted
+        Given an in-memory storage with the records
+            | date       | meal  |
+            | 1740787200 | pizza |
+            | 1745020800 | curry |
+        Then the statistics count 1 record of 1 distinct meal
+        And the meals eaten once are pizza
+
+    Scenario: Differently spelled names count as one meal
+        Given an in-memory storage with the records
+            | date       | meal   |
+            | 1740787200 | pizza  |
+            | 1740873600 | Pizza  |
+            | 1740960000 | curry  |
+        Then the statistics count 3 records of 2 distinct meals
+        And the 1 most frequent meals are pizza
+
+    Scenario: Statistics of an empty storage
+        Given an empty in-memory storage
+        Then the statistics count 0 records of 0 distinct meals
+        And the statistics have no most frequent meals, no longest streak and no busiest weekday
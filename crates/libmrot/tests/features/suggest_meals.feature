@@ -1,5 +1,8 @@
 Feature: Suggest Meals
 
+    Background:
+        Given the current date is 2025-04-15
+
     Scenario Outline:
         Given an in-memory storage with the records
             | date       | meal               |
@@ -65,3 +68,75 @@ Feature: Suggest Meals
             | 3      | gratin à la m. o., šunkafleky, chicken         | 2025-02-20 through 2025-03-13 | 1739664000, chinese noodles                                                        |
             | 3      | gratin à la m. o., šunkafleky, chicken         | 2025-02-21 through 2025-03-13 | 1739664000, chinese noodles; 1740009600, chicken curry                             |
             | 3      | gratin à la m. o., šunkafleky, chicken curry   | 2025-02-21 through 2025-03-13 | 1739664000, chinese noodles; 1739836800, chicken                                   |
+
+    Scenario Outline: Rank suggestions by a strategy
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1734220800 | raclette |
+            | 1739577600 | raclette |
+            | 1742256000 | pasta    |
+            | 1742860800 | pasta    |
+            | 1743465600 | pasta    |
+            | 1744070400 | pasta    |
+            | 1743465600 | curry    |
+            | 1743811200 | curry    |
+            | 1744156800 | curry    |
+            | 1735689600 | duck     |
+        When I ask for <number> meal suggestions by the strategy <strategy>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | strategy       | number | records                                                                           |
+            | in-memory | oldest-first   | 4      | 1735689600, duck; 1739577600, raclette; 1744070400, pasta; 1744156800, curry      |
+            | in-memory | least-frequent | 4      | 1735689600, duck; 1739577600, raclette; 1744156800, curry; 1744070400, pasta      |
+            | in-memory | least-frequent | 2      | 1735689600, duck; 1739577600, raclette                                            |
+            | in-memory | overdue        | 4      | 1744156800, curry; 1744070400, pasta; 1739577600, raclette; 1735689600, duck      |
+            | in-memory | overdue        | 1      | 1744156800, curry                                                                 |
+            | SQLite    | oldest-first   | 4      | 1735689600, duck; 1739577600, raclette; 1744070400, pasta; 1744156800, curry      |
+            | SQLite    | least-frequent | 4      | 1735689600, duck; 1739577600, raclette; 1744156800, curry; 1744070400, pasta      |
+            | SQLite    | overdue        | 4      | 1744156800, curry; 1744070400, pasta; 1739577600, raclette; 1735689600, duck      |
+
+    Scenario: A planned meal is not overdue
+        Given an in-memory storage with the records
+            | date       | meal  |
+            | 1743465600 | curry |
+            | 1743811200 | curry |
+            | 1745107200 | curry |
+            | 1742256000 | pasta |
+            | 1742860800 | pasta |
+        When I ask for 2 meal suggestions by the strategy overdue
+        Then I get the meal records 1742860800, pasta; 1745107200, curry, planned
+
+    Scenario Outline: A long break does not hide a weekly favourite
+        Given the current date is 2025-04-15
+        And a <backend> storage with the records
+            | date       | meal     |
+            | 1717200000 | pasta    |
+            | 1742256000 | pasta    |
+            | 1742860800 | pasta    |
+            | 1743465600 | pasta    |
+            | 1734220800 | raclette |
+            | 1739577600 | raclette |
+        When I ask for 2 meal suggestions by the strategy overdue
+        Then I get the meal records 1743465600, pasta; 1739577600, raclette
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: Overdue meals are expected after their median interval
+        Given the current date is 2025-04-15
+        And an in-memory storage with the records
+            | date       | meal     |
+            | 1717200000 | pasta    |
+            | 1742256000 | pasta    |
+            | 1742860800 | pasta    |
+            | 1743465600 | pasta    |
+            | 1734220800 | raclette |
+            | 1739577600 | raclette |
+            | 1735689600 | duck     |
+        When I ask for 3 overdue meals with their statistics
+        Then the meal pasta is expected on 2025-04-08
+        And the meal raclette is expected on 2025-04-18
+        And the meal duck is not expected on any date
@@ -0,0 +1,168 @@
+Feature: Tags
+
+    Background:
+        Given the current date is 2025-04-15
+
+    Scenario Outline: Suggest only meals with a tag
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+            | 1740873600 | salmon      |
+            | 1740960000 | pizza       |
+            | 1741046400 | curry       |
+        When I tag ratatouille with vegetarian, french
+        When I tag pizza with vegetarian
+        When I tag salmon with fish
+        When I ask for 4 meal suggestions tagged <tags>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | tags               | records                                    |
+            | in-memory | vegetarian         | 1740787200, ratatouille; 1740960000, pizza |
+            | in-memory | vegetarian, french | 1740787200, ratatouille                    |
+            | in-memory | fish               | 1740873600, salmon                         |
+            | SQLite    | vegetarian         | 1740787200, ratatouille; 1740960000, pizza |
+            | SQLite    | vegetarian, french | 1740787200, ratatouille                    |
+
+    Scenario Outline: Leave out meals with a tag
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+            | 1740873600 | salmon      |
+            | 1740960000 | pizza       |
+            | 1741046400 | curry       |
+        When I tag ratatouille with vegetarian, french
+        When I tag pizza with vegetarian
+        When I tag salmon with fish
+        When I ask for 4 meal suggestions not tagged <tags>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | tags         | records                                                       |
+            | in-memory | fish         | 1740787200, ratatouille; 1740960000, pizza; 1741046400, curry |
+            | in-memory | fish, french | 1740960000, pizza; 1741046400, curry                          |
+            | SQLite    | fish         | 1740787200, ratatouille; 1740960000, pizza; 1741046400, curry |
+            | SQLite    | vegetarian   | 1740873600, salmon; 1741046400, curry                         |
+
+    Scenario Outline: The ignore list accepts tags with the tag prefix
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+            | 1740873600 | salmon      |
+            | 1740960000 | pizza       |
+            | 1741046400 | curry       |
+        When I tag ratatouille with vegetarian
+        When I tag salmon with fish
+        When I ask for 4 meal suggestions ignoring <ignore_list>
+        Then I get the meal records <records>
+
+        Examples:
+            | backend   | ignore_list              | records                                                                          |
+            | in-memory | tag:fish                 | 1740787200, ratatouille; 1740960000, pizza; 1741046400, curry                    |
+            | in-memory | tag:vegetarian, curry    | 1740873600, salmon; 1740960000, pizza                                            |
+            | in-memory | vegetarian               | 1740787200, ratatouille; 1740873600, salmon; 1740960000, pizza; 1741046400, curry |
+            | SQLite    | tag:fish, tag:vegetarian | 1740960000, pizza; 1741046400, curry                                             |
+            | SQLite    | tag: fish                | 1740787200, ratatouille; 1740960000, pizza; 1741046400, curry                    |
+
+    Scenario Outline: Unique meals with a tag
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+            | 1740873600 | salmon      |
+            | 1740960000 | ratatouille |
+        When I tag ratatouille with vegetarian
+        When I ask for unique meals tagged vegetarian
+        Then I get the meal records 1740960000, ratatouille
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Show the records of the meals without a tag
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+            | 1740873600 | salmon      |
+            | 1740960000 | ratatouille |
+            | 1741046400 | curry       |
+        When I tag salmon with fish
+        When I ask for the records in the period from March 1 through March 3, 2025 not tagged fish
+        Then I get the meal records 1740787200, ratatouille; 1740960000, ratatouille
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Pick a random meal with a tag
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+            | 1740873600 | salmon      |
+            | 1740960000 | pizza       |
+        When I tag salmon with fish
+        When I ask for a random meal tagged fish
+        Then the random meal is salmon
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Remove tags
+        Given a <backend> storage with the records
+            | date       | meal        |
+            | 1740787200 | ratatouille |
+        When I tag ratatouille with vegetarian, french, summer
+        When I remove the tags french, winter from ratatouille
+        Then the meal ratatouille has the tags summer, vegetarian
+        When I remove the tags summer, vegetarian from ratatouille
+        Then the meal ratatouille has no tags
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: The tags stay with the meal when it is renamed
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+        When I tag raclette with cheese, winter
+        When I rename raclette to fondue in the storage
+        Then the meal fondue has the tags cheese, winter
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario: The tags are kept in the storage file
+        Given a SQLite storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+        When I tag raclette with cheese
+        When I reopen the storage
+        Then the meal raclette has the tags cheese
+
+    Scenario Outline: Only known meals can be tagged
+        Given an empty <backend> storage
+        Then tagging raclette with cheese fails because the meal is unknown
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
+
+    Scenario Outline: Tags cannot be empty
+        Given a <backend> storage with the records
+            | date       | meal     |
+            | 1739577600 | raclette |
+        Then tagging raclette with an empty tag fails
+        Then the meal raclette has no tags
+
+        Examples:
+            | backend   |
+            | in-memory |
+            | SQLite    |
@@ -1,7 +1,7 @@
 Feature: Unique Meals
 
     Scenario Outline:
-        Given an in-memory storage with the records
+        Given a <backend> storage with the records
             | date       | meal                |
             | 1742774400 | spätzle             |
             | 1742688000 | spätzle             |
@@ -21,7 +21,6 @@ Feature: Unique Meals
         Then I get the meal records <records>
 
         Examples:
-            | records                                                                                                                                                                                                       |
-            | 1741737600, bolognese; 1741824000, rinderbraten; 1741910400, flammkuchen; 1741996800, tortelloni; 1742256000, gratin à la m. o.; 1742428800, bramboráky; 1742601600, rougailles saucisse; 1742774400, spätzle |
-
-
+            | backend   | records                                                                                                                                                                                                       |
+            | in-memory | 1741737600, bolognese; 1741824000, rinderbraten; 1741910400, flammkuchen; 1741996800, tortelloni; 1742256000, gratin à la m. o.; 1742428800, bramboráky; 1742601600, rougailles saucisse; 1742774400, spätzle |
+            | SQLite    | 1741737600, bolognese; 1741824000, rinderbraten; 1741910400, flammkuchen; 1741996800, tortelloni; 1742256000, gratin à la m. o.; 1742428800, bramboráky; 1742601600, rougailles saucisse; 1742774400, spätzle |
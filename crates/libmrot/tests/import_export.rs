@@ -0,0 +1,121 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{when, then, gherkin::Step};
+use libmrot::{Error as LibMrotError, ExchangeFormat};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::{MealRecords, WrappedPeriod}, common_steps::new_storage};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, an_empty_storage, a_storage_with_records, storage_when_meal, storage_knows_meals, storage_show_meal_records};
+
+fn exchange_format(format: &str) -> Result<ExchangeFormat> {
+    match format {
+        "CSV" => Ok(ExchangeFormat::Csv),
+        "JSON" => Ok(ExchangeFormat::Json),
+        "iCalendar" => Ok(ExchangeFormat::Ics),
+        _ => Err(Error::UndefinedValue(format!("exchange format {}", format))),
+    }
+}
+
+fn import(world: &mut World, format: ExchangeFormat, data: String, dry_run: bool) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_import_report = Some(storage.import(format, data.as_bytes(), dry_run));
+    Ok(())
+}
+
+fn export(world: &mut World, format: ExchangeFormat, period: WrappedPeriod) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let mut exported = Vec::new();
+    storage.export(format, period.to_option_period(storage)?, &mut exported)?;
+    world.exported = Some(String::from_utf8(exported).map_err(|e| Error::UnexpectedErrResult(e.to_string()))?);
+    Ok(())
+}
+
+#[when(regex = r"^I export the storage as (?P<format>CSV|JSON|iCalendar)$")]
+async fn export_storage(world: &mut World, format: String) -> Result<()> {
+    export(world, exchange_format(&format)?, "None".parse()?)
+}
+
+#[when(regex = r"^I export the storage as (?P<format>CSV|JSON|iCalendar) in the period (?P<period>.*)$")]
+async fn export_storage_in_period(world: &mut World, format: String, period: WrappedPeriod) -> Result<()> {
+    export(world, exchange_format(&format)?, period)
+}
+
+#[when(regex = r"^I import the export into an empty (?P<backend>in-memory|SQLite) storage$")]
+async fn import_export_into_empty_storage(world: &mut World, backend: String) -> Result<()> {
+    let exported = world.exported.clone().ok_or(Error::UndefinedValue("exported".to_string()))?;
+    let format = match exported.chars().next() {
+        Some('[') => ExchangeFormat::Json,
+        Some('B') => ExchangeFormat::Ics,
+        _ => ExchangeFormat::Csv,
+    };
+    world.storage = None;
+    if let Some(path) = world.storage_path.take() {
+        let _ = std::fs::remove_file(path);
+    }
+    world.storage = Some(new_storage(world, &backend)?);
+    import(world, format, exported, false)
+}
+
+#[when(regex = r"^I import into the storage (?P<dry_run>in a dry run )?the (?P<format>CSV|JSON|iCalendar)$")]
+async fn import_into_storage(world: &mut World, dry_run: String, format: String, step: &Step) -> Result<()> {
+    let data = step.docstring.clone().ok_or(Error::UndefinedValue("docstring".to_string()))?;
+    import(world, exchange_format(&format)?, data, !dry_run.is_empty())
+}
+
+#[then(regex = r"^the export is$")]
+async fn export_is(world: &mut World, step: &Step) -> Result<()> {
+    let exported = world.exported.as_ref().ok_or(Error::UndefinedValue("exported".to_string()))?;
+    let expected = step.docstring.as_ref().ok_or(Error::UndefinedValue("docstring".to_string()))?;
+    let exported = exported.replace("\r\n", "\n");
+    assert_eq!(exported.trim(), expected.trim(), "the export is {:?} but we expected {:?}", exported, expected);
+    Ok(())
+}
+
+#[then(regex = r"^the import reports (?P<imported>\d+) imported records and (?P<duplicates>\d+) duplicates$")]
+async fn import_reports_counts(world: &mut World, expected_imported: usize, expected_duplicates: usize) -> Result<()> {
+    let report = world.result_import_report.as_ref().ok_or(Error::UndefinedValue("result_import_report".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    assert_eq!(report.imported.len(), expected_imported, "the import reports {} imported records but we expected {}", report.imported.len(), expected_imported);
+    assert_eq!(report.duplicates.len(), expected_duplicates, "the import reports {} duplicates but we expected {}", report.duplicates.len(), expected_duplicates);
+    Ok(())
+}
+
+#[then(regex = r"^the import reports the duplicates (?P<records>.*)$")]
+async fn import_reports_duplicates(world: &mut World, expected_records: MealRecords) -> Result<()> {
+    let report = world.result_import_report.as_ref().ok_or(Error::UndefinedValue("result_import_report".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    assert_eq!(report.duplicates, expected_records.to_vec_mealrecord(), "the import reports the duplicates {:?} but we expected {:?}", report.duplicates, expected_records);
+    Ok(())
+}
+
+#[then(regex = r"^the import fails$")]
+async fn import_fails(world: &mut World) -> Result<()> {
+    let result = world.result_import_report.as_ref().ok_or(Error::UndefinedValue("result_import_report".to_string()))?;
+    assert!(result.is_err(), "the import returned {:?} but we expected an error", result);
+    Ok(())
+}
+
+#[then(regex = r"^the import fails because the column (?P<column>.*) is missing$")]
+async fn import_fails_missing_column(world: &mut World, column: String) -> Result<()> {
+    let result = world.result_import_report.as_ref().ok_or(Error::UndefinedValue("result_import_report".to_string()))?;
+    assert!(matches!(result, Err(LibMrotError::MissingColumn(name)) if *name == column), "the import returned {:?} but we expected MissingColumn({})", result, column);
+    Ok(())
+}
+
+#[then(regex = r"^the import fails because the calendar is invalid$")]
+async fn import_fails_invalid_calendar(world: &mut World) -> Result<()> {
+    let result = world.result_import_report.as_ref().ok_or(Error::UndefinedValue("result_import_report".to_string()))?;
+    assert!(matches!(result, Err(LibMrotError::InvalidCalendar(_))), "the import returned {:?} but we expected InvalidCalendar", result);
+    Ok(())
+}
+
+#[then(regex = r"^the storage knows no meals$")]
+async fn storage_knows_no_meals(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let actual_meals = storage.meals()?;
+    assert!(actual_meals.is_empty(), "storage.meals returned {:?} but we expected none", actual_meals);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/import_export.feature").await;
+}
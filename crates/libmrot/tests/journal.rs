@@ -0,0 +1,84 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{when, then, gherkin::Step};
+use libmrot::{Error as LibMrotError, TagFilter};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, an_empty_storage, a_storage_with_records, storage_when_meal, storage_knows_meals, meal_has_id, reopen_storage, add_meal_on_dates, set_cooldown, tag_meal, add_aliases, remove_records, set_timezone, undo, redo, storage_does_not_know_meal, rename_meal, meal_has_settings};
+
+#[when(regex = r"^I skip the records in (?P<period>.*)$")]
+async fn skip_records(world: &mut World, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.skip(Some(storage.period(&period)?), None)?;
+    Ok(())
+}
+
+#[when(regex = r"^I rename (?P<old_name>.*) to (?P<new_name>.*) in the storage in the period (?P<period>.*)$")]
+async fn rename_meal_in_period(world: &mut World, old_name: String, new_name: String, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.rename(&old_name, &new_name, Some(storage.period(&period)?))?;
+    Ok(())
+}
+
+#[when(regex = r"^another program deletes all records from the storage file$")]
+async fn delete_records_behind_storage(world: &mut World) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let connection = sqlite::open(path).map_err(LibMrotError::from)?;
+    connection.execute("DELETE FROM occurrences").map_err(LibMrotError::from)?;
+    Ok(())
+}
+
+#[then(regex = r"^the (?:undone|redone) operation was (?P<operation>.*)$")]
+async fn operation_was(world: &mut World, expected_operation: String) -> Result<()> {
+    let option_entry = world.result_journal_entry.as_ref().ok_or(Error::UndefinedValue("result_journal_entry".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    let entry = option_entry.as_ref().ok_or(Error::UnexpectedErrResult("there was no operation".to_string()))?;
+    let actual_operation = entry.operation().to_string();
+    assert_eq!(actual_operation, expected_operation, "the operation was {} but we expected {}", actual_operation, expected_operation);
+    Ok(())
+}
+
+#[then(regex = r"^the undo failed because the records of (?P<operation>.*) have changed$")]
+async fn undo_failed(world: &mut World, expected_operation: String) -> Result<()> {
+    let result = world.result_journal_entry.as_ref().ok_or(Error::UndefinedValue("result_journal_entry".to_string()))?;
+    assert!(matches!(result, Err(LibMrotError::ChangedRecords(operation)) if *operation == expected_operation), "the undo returned {:?} but we expected a ChangedRecords error", result);
+    Ok(())
+}
+
+#[then(regex = r"^there was nothing to (?:undo|redo)$")]
+async fn nothing_to_do(world: &mut World) -> Result<()> {
+    let option_entry = world.result_journal_entry.as_ref().ok_or(Error::UndefinedValue("result_journal_entry".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    assert!(option_entry.is_none(), "the operation was {:?} but we expected none", option_entry);
+    Ok(())
+}
+
+#[then(regex = r"^the journal lists the operations$")]
+async fn journal_lists_operations(world: &mut World, step: &Step) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let table = step.table.as_ref().ok_or(Error::UndefinedValue("step table".to_string()))?;
+    let expected: Vec<(String, String)> = table.rows.iter().skip(1).map(|row| (row[0].clone(), row[1].clone())).collect();
+    let actual: Vec<(String, String)> = storage.journal()?.iter().map(|entry| (entry.operation().to_string(), entry.is_undone().to_string())).collect();
+    assert_eq!(actual, expected, "the journal lists {:?} but we expected {:?}", actual, expected);
+    Ok(())
+}
+
+#[then(regex = r"^the storage knows no records$")]
+async fn storage_knows_no_records(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let records = storage.get_last_cooked_unique(&TagFilter::default())?;
+    assert!(records.is_empty(), "the storage has the records {:?} but we expected none", records);
+    Ok(())
+}
+
+#[then(regex = r"^the storage has no records of (?P<meal>.*)$")]
+async fn storage_has_no_records_of(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let dates = storage.when(&meal)?;
+    assert!(dates.is_empty(), "the storage has records of {} on {:?} but we expected none", meal, dates);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/journal.feature").await;
+}
@@ -0,0 +1,27 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{given, gherkin::Step};
+use libmrot::{FixedClock, MealStore, MemoryStore, Storage};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records, check_result_vec_mealrecord, storage_when_meal, storage_knows_meals, add_meal_on_dates, add_aliases, ask_for_suggestions_ignoring, ask_for_unique_meals, ask_for_overdue_statistics, meal_is_expected_on};
+
+#[given(regex = r"^an in-memory store with the records spelled as they are$")]
+async fn a_store_with_records_spelled_as_they_are(world: &mut World, step: &Step) -> Result<()> {
+    let now = world.now.ok_or(Error::UndefinedValue("now".to_string()))?;
+    let store = MemoryStore::default();
+    if let Some(table) = step.table.as_ref() {
+        for row in table.rows.iter().skip(1) {
+            let timestamp = row[0].parse::<i64>().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+            store.add(&row[1], &[timestamp], now)?;
+        }
+    }
+    world.storage = Some(Storage::with_store(Box::new(store) as Box<dyn MealStore>).with_clock(FixedClock::new(now)));
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/name_normalization.feature").await;
+}
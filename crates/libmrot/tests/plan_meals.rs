@@ -0,0 +1,40 @@
+//! Implementation of tests for libmrot
+
+use cucumber::when;
+use libmrot::{Diversity, OldestFirst, TagFilter};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::{WrappedPeriod, Meals}};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records, check_result_vec_mealrecord, storage_show_meal_records, set_cooldown};
+
+#[when(regex = r"^I plan the period (?P<period>.*), ignoring (?P<ignore_list>.*) and ignore period (?P<ignore_period>.*)$")]
+async fn plan_period_ignoring(world: &mut World, period: String, ignore_list: Meals, ignore_period: WrappedPeriod) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    let option_ignore_period = ignore_period.to_option_period(storage)?;
+    let result = storage.plan(period, option_ignore_period, ignore_list.to_vec_string(), &TagFilter::default(), &Diversity::default(), &OldestFirst);
+    world.result_vec_mealrecord = Some(result);
+    Ok(())
+}
+
+#[when(regex = r"^I plan the period (?P<period>[^,]*)$")]
+async fn plan_period(world: &mut World, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    let result = storage.plan(period, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &OldestFirst);
+    world.result_vec_mealrecord = Some(result);
+    Ok(())
+}
+
+#[when(regex = r"^I record the plan$")]
+async fn record_plan(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let plan = world.result_vec_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_vec_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    storage.add_records(plan)?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/plan_meals.feature").await;
+}
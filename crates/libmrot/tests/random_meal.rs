@@ -1,14 +1,15 @@
 //! Implementation of tests for libmrot
 
 use cucumber::{when, then};
+use libmrot::TagFilter;
 use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::Meals};
 #[allow(unused_imports)]
-use mrot_test_utils::common_steps::a_storage_with_records;
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records};
 
 #[when(regex = r"^I ask for a random meal")]
 async fn get_random_meal(world: &mut World) -> Result<()> {
     let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
-    let result = storage.random();
+    let result = storage.random(&TagFilter::default());
     world.result_option_mealrecord = Some(result);
     Ok(())
 }
@@ -37,6 +38,80 @@ async fn verify_random_is_one_of_expected_meals(world: &mut World, meals: Meals)
     Ok(())
 }
 
+#[when(regex = r"^I ask for (?P<number>\d+) weighted random meals?$")]
+async fn get_weighted_random_meals(world: &mut World, number: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.weighted_random(number, None, Vec::new(), &TagFilter::default(), None));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) weighted random meals? with the seed (?P<seed>\d+)$")]
+async fn get_seeded_weighted_random_meals(world: &mut World, number: u64, seed: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.weighted_random(number, None, Vec::new(), &TagFilter::default(), Some(seed)));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) weighted random meals? ignoring (?P<meals>.*) and the meals in the period (?P<period>.*)$")]
+async fn get_filtered_weighted_random_meals(world: &mut World, number: u64, meals: Meals, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    world.result_vec_mealrecord = Some(storage.weighted_random(number, Some(period), meals.to_vec_string(), &TagFilter::default(), None));
+    Ok(())
+}
+
+#[then(regex = r"^I get no weighted random meals$")]
+async fn verify_no_weighted_random_meals(world: &mut World) -> Result<()> {
+    let records = world.result_vec_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_vec_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    assert!(records.is_empty(), "storage.weighted_random returned {:?} but we expected none", records);
+    Ok(())
+}
+
+#[then(regex = r"^I get the meals (?P<meals>.*) in any order$")]
+async fn verify_meals_in_any_order(world: &mut World, meals: Meals) -> Result<()> {
+    let records = world.result_vec_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_vec_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    let mut actual_meals: Vec<String> = records.iter().map(|record| record.meal()).collect();
+    let mut expected_meals = meals.to_vec_string();
+    actual_meals.sort();
+    expected_meals.sort();
+    assert_eq!(actual_meals, expected_meals, "storage.weighted_random returned {:?} but we expected {:?}", actual_meals, expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^I get (?P<number>\d+) distinct meals out of (?P<meals>.*)$")]
+async fn verify_distinct_meals(world: &mut World, number: usize, meals: Meals) -> Result<()> {
+    let records = world.result_vec_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_vec_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    let mut actual_meals: Vec<String> = records.iter().map(|record| record.meal()).collect();
+    let possible_meals = meals.to_vec_string();
+    assert!(actual_meals.iter().all(|meal| possible_meals.contains(meal)), "storage.weighted_random returned {:?} which are not all out of {:?}", actual_meals, possible_meals);
+    actual_meals.sort();
+    actual_meals.dedup();
+    assert_eq!(actual_meals.len(), number, "storage.weighted_random returned {} distinct meals but we expected {}", actual_meals.len(), number);
+    Ok(())
+}
+
+#[then(regex = r"^asking again with the seed (?P<seed>\d+) picks the same meals$")]
+async fn verify_same_picks_with_seed(world: &mut World, seed: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let records = world.result_vec_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_vec_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    let again = storage.weighted_random(records.len() as u64, None, Vec::new(), &TagFilter::default(), Some(seed))?;
+    assert_eq!(&again, records, "storage.weighted_random picked {:?} but the first time it picked {:?}", again, records);
+    Ok(())
+}
+
+#[then(regex = r"^out of (?P<times>\d+) weighted random meals with the seeds 0 and up, (?P<often>.*) is picked more often than (?P<rarely>.*)$")]
+async fn verify_weighting(world: &mut World, times: u64, often: String, rarely: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let mut picks: Vec<String> = Vec::new();
+    for seed in 0..times {
+        picks.extend(storage.weighted_random(1, None, Vec::new(), &TagFilter::default(), Some(seed))?.iter().map(|record| record.meal()));
+    }
+    let often_count = picks.iter().filter(|meal| **meal == often).count();
+    let rarely_count = picks.iter().filter(|meal| **meal == rarely).count();
+    assert!(often_count > rarely_count, "{} was picked {} times and {} {} times but we expected {} more often", often, often_count, rarely, rarely_count, often);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() {
     let world = construct_world();
@@ -0,0 +1,93 @@
+//! Implementation of tests for libmrot
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use cucumber::{given, when, then};
+use libmrot::{FixedClock, TagFilter};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records, check_result_vec_mealrecord, storage_show_meal_records, reopen_storage, ask_for_suggestions, remove_records, undo, redo};
+
+#[given(regex = r"^the current date changes to (?P<date>\d{4}-\d{2}-\d{2})$")]
+async fn the_current_date_changes(world: &mut World, date: String) -> Result<()> {
+    let naive_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")?;
+    let noon = naive_date.and_hms_opt(12, 0, 0).ok_or(Error::UndefinedValue("noon".to_string()))?;
+    let now = Utc.from_utc_datetime(&noon);
+    world.now = Some(now);
+    let storage = world.storage.take().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.storage = Some(storage.with_clock(FixedClock::new(now)));
+    Ok(())
+}
+
+#[when(regex = r"^I confirm the pending records$")]
+async fn confirm_pending(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.confirm(None, None));
+    Ok(())
+}
+
+#[when(regex = r"^I confirm the records of (?P<meal>.*) in the period (?P<period>.*)$")]
+async fn confirm_meal_in_period(world: &mut World, meal: String, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    world.result_vec_mealrecord = Some(storage.confirm(Some(period), Some(meal)));
+    Ok(())
+}
+
+#[when(regex = r"^I confirm the records in the period (?P<period>.*)$")]
+async fn confirm_in_period(world: &mut World, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    world.result_vec_mealrecord = Some(storage.confirm(Some(period), None));
+    Ok(())
+}
+
+#[when(regex = r"^I skip the pending records$")]
+async fn skip_pending(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.skip(None, None));
+    Ok(())
+}
+
+#[when(regex = r"^I skip the records in the period (?P<period>.*)$")]
+async fn skip_in_period(world: &mut World, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    world.result_vec_mealrecord = Some(storage.skip(Some(period), None));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for the pending records$")]
+async fn ask_for_pending(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.pending());
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions, ignoring the period (?P<period>.*)$")]
+async fn ask_for_suggestions_ignoring_period(world: &mut World, number: u64, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    world.result_vec_mealrecord = Some(storage.what(number, Some(period), Vec::new(), &TagFilter::default()));
+    Ok(())
+}
+
+#[then(regex = r"^there are no pending records$")]
+async fn no_pending_records(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let pending = storage.pending()?;
+    assert!(pending.is_empty(), "found the pending records {:?} but we expected none", pending);
+    Ok(())
+}
+
+#[then(regex = r"^no records changed$")]
+async fn no_records_changed(world: &mut World) -> Result<()> {
+    let changed = world.result_vec_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_vec_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    assert!(changed.is_empty(), "changed the records {:?} but we expected none", changed);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/record_status.feature").await;
+}
@@ -1,25 +1,47 @@
 //! Implementation of tests for libmrot
 
 use cucumber::when;
+use libmrot::MealRecord;
 use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
 #[allow(unused_imports)]
-use mrot_test_utils::common_steps::{check_result_vec_mealrecord, a_storage_with_records, storage_show_meal_records};
-use libmrot::Period;
+use mrot_test_utils::common_steps::{the_current_date_is, check_result_vec_mealrecord, a_storage_with_records, storage_show_meal_records, storage_knows_meals, remove_meal_in_period};
 
 #[when(regex = r"^I remove all meals in the period (?P<period>.*)$")]
 async fn remove_all_meals(world: &mut World, period: String) -> Result<()> {
     let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
-    let period = Period::new(&period)?;
+    let period = storage.period(&period)?;
     let result = storage.remove(period, None);
     world.result_vec_mealrecord = Some(result);
     Ok(())
 }
 
-#[when(regex = r"^I remove the meal (?P<meal>.*) in the period (?P<period>.*)$")]
-async fn remove_meal_in_period(world: &mut World, meal: String, period: String) -> Result<()> {
+#[when(regex = r"^I preview removing all meals in the period (?P<period>.*)$")]
+async fn preview_remove_all_meals(world: &mut World, period: String) -> Result<()> {
     let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
-    let period = Period::new(&period)?;
-    let result = storage.remove(period, Some(meal));
+    let period = storage.period(&period)?;
+    let result = storage.preview_remove(period, None);
+    world.result_vec_mealrecord = Some(result);
+    Ok(())
+}
+
+#[when(regex = r"^I preview removing the meal (?P<meal>.*) in the period (?P<period>.*)$")]
+async fn preview_remove_meal_in_period(world: &mut World, meal: String, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    let result = storage.preview_remove(period, Some(meal));
+    world.result_vec_mealrecord = Some(result);
+    Ok(())
+}
+
+#[when(regex = r"^I remove exactly the records (?P<records>.*)$")]
+async fn remove_records(world: &mut World, records: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let mut records_to_remove = Vec::new();
+    for record in records.split("; ") {
+        let (timestamp, meal) = record.split_once(", ").ok_or(Error::UndefinedValue("meal record".to_string()))?;
+        records_to_remove.push(MealRecord::from_meal_and_timestamp(meal, timestamp.parse()?)?);
+    }
+    let result = storage.remove_records(&records_to_remove);
     world.result_vec_mealrecord = Some(result);
     Ok(())
 }
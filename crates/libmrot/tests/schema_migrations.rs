@@ -0,0 +1,198 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{given, when, then, gherkin::Step};
+use chrono::{DateTime, TimeDelta, Utc};
+use libmrot::{Error as LibMrotError, MealStore, RecordFilter, SqliteStore, Storage, Timezone, SCHEMA_VERSION};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{storage_when_meal, storage_knows_meals, storage_has_timezone, storage_show_meal_records, meal_has_id, meal_has_settings};
+use mrot_test_utils::common_steps::unique_storage_path;
+
+#[given(regex = r"^a storage file which does not exist yet$")]
+async fn a_non_existing_storage_file(world: &mut World) -> Result<()> {
+    world.storage_path = Some(unique_storage_path());
+    Ok(())
+}
+
+#[given(regex = r"^a legacy storage file with the records$")]
+async fn a_legacy_storage_file(world: &mut World, step: &Step) -> Result<()> {
+    let path = unique_storage_path();
+    let connection = sqlite::open(&path).map_err(LibMrotError::from)?;
+    connection.execute("CREATE TABLE meals (date INTEGER, meal TEXT)").map_err(LibMrotError::from)?;
+    if let Some(table) = step.table.as_ref() {
+        for row in table.rows.iter().skip(1) {
+            let timestamp: i64 = row[0].parse()?;
+            let meal = &row[1];
+            connection.execute(format!("INSERT INTO meals VALUES ({}, '{}')", timestamp, meal)).map_err(LibMrotError::from)?;
+        }
+    }
+    world.storage_path = Some(path);
+    Ok(())
+}
+
+#[given(regex = r"^a storage file with schema version (?P<version>\d+)$")]
+async fn a_storage_file_with_schema_version(world: &mut World, version: i64) -> Result<()> {
+    let path = unique_storage_path();
+    let connection = sqlite::open(&path).map_err(LibMrotError::from)?;
+    connection.execute(format!("PRAGMA user_version = {}", version)).map_err(LibMrotError::from)?;
+    world.storage_path = Some(path);
+    Ok(())
+}
+
+#[given(regex = r"^a storage file with schema version 5 in the timezone (?P<timezone>\S+) with records of (?P<meals>.*)$")]
+async fn a_storage_file_with_schema_version_5(world: &mut World, timezone: String, meals: String) -> Result<()> {
+    let path = unique_storage_path();
+    let connection = sqlite::open(&path).map_err(LibMrotError::from)?;
+    connection.execute(format!("
+        CREATE TABLE dishes (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE, created INTEGER NOT NULL, cooldown INTEGER);
+        CREATE TABLE occurrences (id INTEGER PRIMARY KEY, dish_id INTEGER NOT NULL REFERENCES dishes (id), date INTEGER NOT NULL);
+        CREATE VIEW records AS SELECT occurrences.id AS id, occurrences.date AS date, dishes.id AS dish_id, dishes.name AS meal FROM occurrences JOIN dishes ON dishes.id = occurrences.dish_id;
+        CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+        INSERT INTO settings (key, value) VALUES ('timezone', '{}');
+        CREATE TABLE journal (id INTEGER PRIMARY KEY, performed INTEGER NOT NULL, operation TEXT NOT NULL, old_name TEXT, new_name TEXT, undone INTEGER NOT NULL DEFAULT 0);
+        CREATE TABLE journal_records (id INTEGER PRIMARY KEY, journal_id INTEGER NOT NULL REFERENCES journal (id), meal TEXT NOT NULL, date INTEGER NOT NULL);
+        CREATE TABLE journal_dishes (id INTEGER PRIMARY KEY, journal_id INTEGER NOT NULL REFERENCES journal (id), role TEXT NOT NULL, dish_id INTEGER NOT NULL, name TEXT NOT NULL, created INTEGER NOT NULL, cooldown INTEGER);
+        PRAGMA user_version = 5;
+    ", timezone)).map_err(LibMrotError::from)?;
+    let timezone: Timezone = timezone.parse()?;
+    let today = timezone.date_of(Utc::now().timestamp())?;
+    for (offset, meal) in meals.split(", ").enumerate() {
+        let date = today + TimeDelta::days(offset as i64 - 1);
+        let timestamp = timezone.start_of_day(&date);
+        connection.execute(format!("INSERT INTO dishes (name, created) VALUES ('{}', {})", meal, timestamp)).map_err(LibMrotError::from)?;
+        connection.execute(format!("INSERT INTO occurrences (dish_id, date) VALUES (last_insert_rowid(), {})", timestamp)).map_err(LibMrotError::from)?;
+    }
+    world.storage_path = Some(path);
+    Ok(())
+}
+
+#[given(regex = r"^a storage file with schema version 8 with the dishes$")]
+async fn a_storage_file_with_schema_version_8(world: &mut World, step: &Step) -> Result<()> {
+    let path = unique_storage_path();
+    SqliteStore::open(path.to_str().ok_or(Error::UndefinedValue("storage_path as str".to_string()))?)?;
+    let connection = sqlite::open(&path).map_err(LibMrotError::from)?;
+    connection.execute("INSERT INTO journal (performed, operation) VALUES (1741824000, 'add')").map_err(LibMrotError::from)?;
+    if let Some(table) = step.table.as_ref() {
+        for row in table.rows.iter().skip(1) {
+            let cooldown = if row[2].is_empty() { "NULL" } else { &row[2] };
+            connection.execute(format!("INSERT INTO dishes (id, name, created, cooldown) VALUES ({}, '{}', {}, {})", row[0], row[1], row[5], cooldown)).map_err(LibMrotError::from)?;
+            connection.execute(format!("INSERT INTO occurrences (dish_id, date) VALUES ({}, {})", row[0], row[5])).map_err(LibMrotError::from)?;
+            connection.execute(format!("INSERT INTO journal_records (journal_id, meal, date) VALUES (1, '{}', {})", row[1], row[5])).map_err(LibMrotError::from)?;
+            for tag in row[3].split(", ").filter(|tag| !tag.is_empty()) {
+                connection.execute(format!("INSERT INTO dish_tags (dish_id, tag) VALUES ({}, '{}')", row[0], tag)).map_err(LibMrotError::from)?;
+            }
+            for alias in row[4].split(", ").filter(|alias| !alias.is_empty()) {
+                connection.execute(format!("INSERT INTO dish_aliases (alias, dish_id) VALUES ('{}', {})", alias, row[0])).map_err(LibMrotError::from)?;
+            }
+        }
+    }
+    connection.execute("ALTER TABLE journal_records DROP COLUMN new_status").map_err(LibMrotError::from)?;
+    connection.execute("PRAGMA user_version = 8").map_err(LibMrotError::from)?;
+    world.storage_path = Some(path);
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) is (?P<status>planned|eaten)$")]
+async fn check_status(world: &mut World, meal: String, expected_status: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let filter = RecordFilter { meal: Some(meal.clone()), period: None, status: None };
+    let records = storage.store().select(&filter)?;
+    let statuses: Vec<String> = records.iter().map(|record| record.status().to_string()).collect();
+    assert_eq!(statuses, vec![expected_status.clone()], "{} has the statuses {:?} but we expected {}", meal, statuses, expected_status);
+    Ok(())
+}
+
+#[then(regex = r"^the journaled operation is (?P<operation>.*)$")]
+async fn journaled_operation_is(world: &mut World, expected_operation: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let actual_operations: Vec<String> = storage.journal()?.iter().map(|entry| entry.operation().to_string()).collect();
+    assert_eq!(actual_operations, vec![expected_operation.clone()], "the journal lists {:?} but we expected {}", actual_operations, expected_operation);
+    Ok(())
+}
+
+#[when(regex = r"^I open the storage$")]
+async fn open_storage(world: &mut World) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let path_str = path.to_str().ok_or(Error::UndefinedValue("storage_path as str".to_string()))?;
+    world.storage = None;
+    match SqliteStore::open(path_str) {
+        Ok(store) => {
+            world.storage = Some(Storage::with_store(Box::new(store) as Box<dyn MealStore>));
+            world.result_open_storage = Some(Ok(()));
+        }
+        Err(e) => world.result_open_storage = Some(Err(e)),
+    }
+    Ok(())
+}
+
+#[when(regex = r"^I open the storage read-only$")]
+async fn open_storage_read_only(world: &mut World) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let path_str = path.to_str().ok_or(Error::UndefinedValue("storage_path as str".to_string()))?;
+    world.storage = None;
+    match SqliteStore::open_read_only(path_str) {
+        Ok(store) => {
+            world.storage = Some(Storage::with_store(Box::new(store) as Box<dyn MealStore>));
+            world.result_open_storage = Some(Ok(()));
+        }
+        Err(e) => world.result_open_storage = Some(Err(e)),
+    }
+    Ok(())
+}
+
+#[when(regex = r"^I open the storage at (?P<moment>.*)$")]
+async fn open_storage_at(world: &mut World, moment: String) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let path_str = path.to_str().ok_or(Error::UndefinedValue("storage_path as str".to_string()))?;
+    let now = DateTime::parse_from_rfc3339(&moment).map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?.with_timezone(&Utc);
+    world.storage = None;
+    world.storage = Some(Storage::with_store(Box::new(SqliteStore::open_at(path_str, now)?) as Box<dyn MealStore>));
+    Ok(())
+}
+
+#[then(regex = r"^the storage has the current schema version$")]
+async fn check_schema_version(world: &mut World) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let actual_version = SqliteStore::open(path.to_str().ok_or(Error::UndefinedValue("storage_path as str".to_string()))?)?.schema_version()?;
+    assert_eq!(actual_version, SCHEMA_VERSION, "storage has schema version {} but we expected {}", actual_version, SCHEMA_VERSION);
+    Ok(())
+}
+
+#[then(regex = r"^opening the storage fails because its schema version (?P<version>\d+) is too new$")]
+async fn check_schema_too_new(world: &mut World, expected_version: i64) -> Result<()> {
+    let result = world.result_open_storage.as_ref().ok_or(Error::UndefinedValue("result_open_storage".to_string()))?;
+    assert!(matches!(result, Err(LibMrotError::SchemaTooNew(version)) if *version == expected_version), "opening the storage returned {:?} but we expected SchemaTooNew({})", result, expected_version);
+    Ok(())
+}
+
+#[then(regex = r"^opening the storage fails because its schema version (?P<version>\d+) is outdated$")]
+async fn check_schema_outdated(world: &mut World, expected_version: i64) -> Result<()> {
+    let result = world.result_open_storage.as_ref().ok_or(Error::UndefinedValue("result_open_storage".to_string()))?;
+    assert!(matches!(result, Err(LibMrotError::SchemaOutdated(version)) if *version == expected_version), "opening the storage returned {:?} but we expected SchemaOutdated({})", result, expected_version);
+    Ok(())
+}
+
+#[then(regex = r"^the storage file still has schema version (?P<version>\d+)$")]
+async fn check_unchanged_schema_version(world: &mut World, expected_version: i64) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let connection = sqlite::open(path).map_err(LibMrotError::from)?;
+    let mut statement = connection.prepare("PRAGMA user_version").map_err(LibMrotError::from)?;
+    statement.next().map_err(LibMrotError::from)?;
+    let actual_version = statement.read::<i64, _>(0).map_err(LibMrotError::from)?;
+    assert_eq!(actual_version, expected_version, "storage file has schema version {} but we expected {}", actual_version, expected_version);
+    Ok(())
+}
+
+#[then(regex = r"^changing the storage fails$")]
+async fn check_change_fails(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.add_meal_on_dates("pizza", &vec!["today".to_string()]);
+    assert!(result.is_err(), "changing the storage returned {:?} but we expected an error", result);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/schema_migrations.feature").await;
+}
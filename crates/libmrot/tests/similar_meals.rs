@@ -0,0 +1,28 @@
+//! Implementation of tests for libmrot
+
+use cucumber::then;
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::Meals};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, a_storage_with_records};
+
+#[then(regex = r"^the meals similar to (?P<name>.*) are (?P<meals>.*)$")]
+async fn similar_meals_are(world: &mut World, name: String, expected_meals: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let similar_meals = storage.similar_meals(&name)?;
+    assert_eq!(similar_meals, expected_meals.to_vec_string(), "storage.similar_meals returned {:?} but we expected {:?}", similar_meals, expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^no meals are similar to (?P<name>.*)$")]
+async fn no_similar_meals(world: &mut World, name: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let similar_meals = storage.similar_meals(&name)?;
+    assert!(similar_meals.is_empty(), "storage.similar_meals returned {:?} but we expected none", similar_meals);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/similar_meals.feature").await;
+}
@@ -0,0 +1,88 @@
+//! Implementation of tests for libmrot
+
+use chrono::NaiveDate;
+use cucumber::then;
+use libmrot::Statistics;
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::Meals};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, an_empty_storage, a_storage_with_records};
+
+fn statistics(world: &World, option_period: Option<&str>) -> Result<Statistics> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let option_period = option_period.map(|period| storage.period(period)).transpose()?;
+    Ok(storage.statistics(option_period)?)
+}
+
+#[then(regex = r"^the statistics count (?P<records>\d+) records? of (?P<meals>\d+) distinct meals?$")]
+async fn statistics_count(world: &mut World, expected_records: usize, expected_meals: usize) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    assert_eq!(statistics.records(), expected_records, "statistics counted {} records but we expected {}", statistics.records(), expected_records);
+    assert_eq!(statistics.distinct_meals(), expected_meals, "statistics counted {} distinct meals but we expected {}", statistics.distinct_meals(), expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^the statistics of (?P<period>.*) count (?P<records>\d+) records? of (?P<meals>\d+) distinct meals?$")]
+async fn statistics_of_period_count(world: &mut World, period: String, expected_records: usize, expected_meals: usize) -> Result<()> {
+    let statistics = statistics(world, Some(&period))?;
+    assert_eq!(statistics.records(), expected_records, "statistics counted {} records but we expected {}", statistics.records(), expected_records);
+    assert_eq!(statistics.distinct_meals(), expected_meals, "statistics counted {} distinct meals but we expected {}", statistics.distinct_meals(), expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^the (?P<number>\d+) most frequent meals are (?P<meals>.*)$")]
+async fn most_frequent_meals(world: &mut World, number: usize, expected_meals: Meals) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    let meals: Vec<String> = statistics.most_frequent(number).iter().map(|meal| meal.meal()).collect();
+    assert_eq!(meals, expected_meals.to_vec_string(), "the most frequent meals are {:?} but we expected {:?}", meals, expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^the meals eaten once are (?P<meals>.*)$")]
+async fn meals_eaten_once(world: &mut World, expected_meals: Meals) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    let meals: Vec<String> = statistics.eaten_once().iter().map(|meal| meal.meal()).collect();
+    assert_eq!(meals, expected_meals.to_vec_string(), "the meals eaten once are {:?} but we expected {:?}", meals, expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^the repeat interval of (?P<meal>.*) is (?P<average>[\d.]+) days on average and (?P<median>[\d.]+) days in the median$")]
+async fn repeat_interval(world: &mut World, meal: String, expected_average: f64, expected_median: f64) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    let meal_statistics = statistics.meals().iter().find(|statistics| statistics.meal() == meal).ok_or(Error::UndefinedValue(meal.clone()))?;
+    assert_eq!(meal_statistics.average_interval(), Some(expected_average), "the average interval of {} is {:?} but we expected {}", meal, meal_statistics.average_interval(), expected_average);
+    assert_eq!(meal_statistics.median_interval(), Some(expected_median), "the median interval of {} is {:?} but we expected {}", meal, meal_statistics.median_interval(), expected_median);
+    Ok(())
+}
+
+#[then(regex = r"^the longest streak without a repeated meal has (?P<meals>\d+) meals from (?P<first>\d{4}-\d{2}-\d{2}) through (?P<last>\d{4}-\d{2}-\d{2})$")]
+async fn longest_streak(world: &mut World, expected_meals: usize, first: String, last: String) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    let streak = statistics.longest_streak().ok_or(Error::UndefinedValue("longest streak".to_string()))?;
+    assert_eq!(streak.meals(), expected_meals, "the longest streak has {} meals but we expected {}", streak.meals(), expected_meals);
+    assert_eq!(streak.first_date(), NaiveDate::parse_from_str(&first, "%Y-%m-%d")?);
+    assert_eq!(streak.last_date(), NaiveDate::parse_from_str(&last, "%Y-%m-%d")?);
+    Ok(())
+}
+
+#[then(regex = r"^the busiest weekday is (?P<weekday>\w+) with (?P<meals>\d+) meals$")]
+async fn busiest_weekday(world: &mut World, expected_weekday: String, expected_meals: usize) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    let (weekday, meals) = statistics.busiest_weekday().ok_or(Error::UndefinedValue("busiest weekday".to_string()))?;
+    assert_eq!((weekday.to_string(), meals), (expected_weekday.clone(), expected_meals), "the busiest weekday is {} with {} meals but we expected {} with {}", weekday, meals, expected_weekday, expected_meals);
+    Ok(())
+}
+
+#[then(regex = r"^the statistics have no most frequent meals, no longest streak and no busiest weekday$")]
+async fn no_meals_streak_and_weekday(world: &mut World) -> Result<()> {
+    let statistics = statistics(world, None)?;
+    assert!(statistics.most_frequent(3).is_empty(), "the most frequent meals are {:?} but we expected none", statistics.most_frequent(3));
+    assert_eq!(statistics.longest_streak(), None, "the statistics have the longest streak {:?} but we expected none", statistics.longest_streak());
+    assert_eq!(statistics.busiest_weekday(), None, "the statistics have the busiest weekday {:?} but we expected none", statistics.busiest_weekday());
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/stats.feature").await;
+}
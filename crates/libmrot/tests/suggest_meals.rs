@@ -1,20 +1,44 @@
 //! Implementation of tests for libmrot
 
-use cucumber::when;
+use cucumber::{then, when};
+use libmrot::{Diversity, LeastFrequent, OldestFirst, Overdue, SuggestionStrategy, TagFilter, WeightedRandom};
 use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::{WrappedPeriod, Meals}};
 #[allow(unused_imports)]
-use mrot_test_utils::common_steps::{check_result_vec_mealrecord, a_storage_with_records};
+use mrot_test_utils::common_steps::{the_current_date_is, check_result_vec_mealrecord, a_storage_with_records, ask_for_overdue_statistics, meal_is_expected_on};
 
 #[when(regex = r"^I ask for (?P<number>\d+) meal suggestions, ignoring (?P<ignore_list>.*) and ignore period (?P<ignore_period>.*)$")]
 async fn ask_for_suggestions(world: &mut World, number: u64, ignore_list: Meals, ignore_period: WrappedPeriod) -> Result<()> {
     let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
     let ignore = ignore_list.to_vec_string();
-    let option_period = ignore_period.to_option_period();
-    let result = storage.what(number, option_period, ignore);
+    let option_period = ignore_period.to_option_period(storage)?;
+    let result = storage.what(number, option_period, ignore, &TagFilter::default());
     world.result_vec_mealrecord = Some(result);
     Ok(())
 }
 
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions by the strategy (?P<strategy>\S+)$")]
+async fn ask_for_suggestions_by_strategy(world: &mut World, number: u64, strategy: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let strategy: Box<dyn SuggestionStrategy> = match strategy.as_str() {
+        "oldest-first" => Box::new(OldestFirst),
+        "least-frequent" => Box::new(LeastFrequent),
+        "weighted-random" => Box::new(WeightedRandom::new(Some(0))),
+        "overdue" => Box::new(Overdue),
+        _ => return Err(Error::UndefinedValue(format!("strategy {}", strategy))),
+    };
+    let result = storage.suggest(number, None, Vec::new(), &TagFilter::default(), &Diversity::default(), strategy.as_ref());
+    world.result_vec_mealrecord = Some(result);
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.+) is not expected on any date$")]
+async fn meal_is_not_expected(world: &mut World, meal: String) -> Result<()> {
+    let statistics = world.result_vec_mealstatistics.as_ref().ok_or(Error::UndefinedValue("result_vec_mealstatistics".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    let statistic = statistics.iter().find(|statistic| statistic.meal() == meal).ok_or(Error::UndefinedValue(format!("statistics of {}", meal)))?;
+    assert_eq!(statistic.expected_next(), None);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() {
     let world = construct_world();
@@ -0,0 +1,92 @@
+//! Implementation of tests for libmrot
+
+use cucumber::{when, then};
+use libmrot::{Error as LibMrotError, TagFilter};
+use mrot_test_utils::{normal_world as construct_world, World, Result, Error, argument::Meals};
+#[allow(unused_imports)]
+use mrot_test_utils::common_steps::{the_current_date_is, an_empty_storage, a_storage_with_records, check_result_vec_mealrecord, reopen_storage, tag_meal, ask_for_suggestions_ignoring, random_meal_is, rename_meal};
+
+#[when(regex = r"^I remove the tags (?P<tags>.*) from (?P<meal>.*)$")]
+async fn remove_tags(world: &mut World, tags: Meals, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.remove_tags(&meal, &tags.to_vec_string())?;
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions tagged (?P<tags>.*)$")]
+async fn ask_for_tagged_suggestions(world: &mut World, number: u64, tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let tag_filter = TagFilter::default().with_tags(&tags.to_vec_string());
+    world.result_vec_mealrecord = Some(storage.what(number, None, Vec::new(), &tag_filter));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions not tagged (?P<tags>.*)$")]
+async fn ask_for_untagged_suggestions(world: &mut World, number: u64, tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let tag_filter = TagFilter::default().with_excluded_tags(&tags.to_vec_string());
+    world.result_vec_mealrecord = Some(storage.what(number, None, Vec::new(), &tag_filter));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for unique meals tagged (?P<tags>.*)$")]
+async fn ask_for_tagged_unique_meals(world: &mut World, tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let tag_filter = TagFilter::default().with_tags(&tags.to_vec_string());
+    world.result_vec_mealrecord = Some(storage.get_last_cooked_unique(&tag_filter));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for the records in the period (?P<range>.*) not tagged (?P<tags>.*)$")]
+async fn ask_for_untagged_records(world: &mut World, range: String, tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let tag_filter = TagFilter::default().with_excluded_tags(&tags.to_vec_string());
+    world.result_vec_mealrecord = Some(storage.show(&range, &tag_filter));
+    Ok(())
+}
+
+#[when(regex = r"^I ask for a random meal tagged (?P<tags>.*)$")]
+async fn ask_for_tagged_random_meal(world: &mut World, tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let tag_filter = TagFilter::default().with_tags(&tags.to_vec_string());
+    world.result_option_mealrecord = Some(storage.random(&tag_filter));
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) has the tags (?P<tags>.*)$")]
+async fn meal_has_tags(world: &mut World, meal: String, expected_tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert_eq!(known_meal.tags(), expected_tags.to_vec_string(), "{} has the tags {:?} but we expected {:?}", meal, known_meal.tags(), expected_tags);
+    Ok(())
+}
+
+#[then(regex = r"^the meal (?P<meal>.*) has no tags$")]
+async fn meal_has_no_tags(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert!(known_meal.tags().is_empty(), "{} has the tags {:?} but we expected none", meal, known_meal.tags());
+    Ok(())
+}
+
+#[then(regex = r"^tagging (?P<meal>.*) with an empty tag fails$")]
+async fn tagging_with_empty_tag_fails(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.add_tags(&meal, &[String::from("vegetarian"), String::from("  ")]);
+    assert!(matches!(result, Err(LibMrotError::InvalidTag(_))), "add_tags returned {:?} but we expected InvalidTag", result);
+    Ok(())
+}
+
+#[then(regex = r"^tagging (?P<meal>.*) with (?P<tag>.*) fails because the meal is unknown$")]
+async fn tagging_fails(world: &mut World, meal: String, tag: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.add_tags(&meal, &[tag]);
+    assert!(matches!(result, Err(LibMrotError::UnknownMeal(ref name)) if *name == meal), "add_tags returned {:?} but we expected UnknownMeal({})", result, meal);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let world = construct_world();
+    world.run("tests/features/tags.feature").await;
+}
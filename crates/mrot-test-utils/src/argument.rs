@@ -3,7 +3,7 @@
 
 use crate::Error;
 use chrono::{naive::NaiveDate, DateTime};
-use libmrot::{MealRecord, Period};
+use libmrot::{MealRecord, MealStore, Period, Storage};
 use std::{fmt, str::FromStr};
 
 const NAIVE_DATE_PARSE_FROM_STRING_FORMAT: &str = "%Y-%m-%d";
@@ -143,10 +143,7 @@ pub struct MealRecords(Vec<MealRecord>);
 
 impl MealRecords {
     pub fn to_vec_mealrecord(&self) -> Vec<MealRecord> {
-        self.0
-            .iter()
-            .map(|meal_record| meal_record.clone())
-            .collect()
+        self.0.to_vec()
     }
 }
 
@@ -154,7 +151,7 @@ impl FromStr for MealRecords {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s == "" {
+        if s.is_empty() {
             return Ok(MealRecords::default());
         }
         let meal_records: Result<Vec<MealRecord>, _> =
@@ -169,16 +166,14 @@ impl fmt::Debug for MealRecords {
     }
 }
 
-/// Wraps a [`Period`] so we can create it from a string from the feature files.
+/// Wraps an optional [`Period`] expression from the feature files. The expression is resolved
+/// against the clock of the storage.
 #[derive(Default)]
-pub struct WrappedPeriod(Option<Period>);
+pub struct WrappedPeriod(Option<String>);
 
 impl WrappedPeriod {
-    pub fn to_option_period(&self) -> Option<Period> {
-        match self.0 {
-            None => None,
-            Some(ref period) => Some(period.clone()),
-        }
+    pub fn to_option_period<S: MealStore>(&self, storage: &Storage<S>) -> libmrot::Result<Option<Period>> {
+        self.0.as_deref().map(|expression| storage.period(expression)).transpose()
     }
 }
 
@@ -187,11 +182,11 @@ impl FromStr for WrappedPeriod {
 
     /// Construct a WrappedPeriod from a string.
     /// The string `"None"` constructs WrappedPeriod containing `None`.
-    /// Other strings try to construct a WrappedPeriod containing `Some(Period {...})`.
+    /// Other strings construct a WrappedPeriod containing `Some` period expression.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let option_period = match s {
             "None" => None,
-            _ => Some(Period::new(s)?),
+            _ => Some(String::from(s)),
         };
         Ok(WrappedPeriod(option_period))
     }
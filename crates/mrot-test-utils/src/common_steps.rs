@@ -1,14 +1,68 @@
 //! A collection of test steps used in the tests of [libmrot] which are shared among test targets
 
-use crate::{World, Result, Error, argument::{DateString, MealRecords, NaiveDates}};
-use cucumber::{given, then, gherkin::Step};
-use libmrot::Storage;
+use crate::{World, Result, Error, argument::{DateString, MealRecords, Meals, NaiveDates, TextDates}};
+use cucumber::{given, when, then, gherkin::Step};
+use libmrot::{parse_duration, Diversity, FixedClock, MealStore, MemoryStore, Overdue, SqliteStore, Storage, TagFilter, Timezone};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use std::{path::PathBuf, sync::atomic::{AtomicUsize, Ordering}};
+
+static STORAGE_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a path to a storage file in the temporary directory which is unique for the test run.
+pub fn unique_storage_path() -> PathBuf {
+    let n = STORAGE_FILE_COUNTER.fetch_add(1, Ordering::SeqCst);
+    std::env::temp_dir().join(format!("mrot-test-{}-{}.sql", std::process::id(), n))
+}
+
+/// Creates an empty storage with the given backend. SQLite storages are created in a temporary file
+/// which is removed when the scenario is cleaned up. If the scenario set the current date, the
+/// storage's clock is stopped at that date.
+pub fn new_storage(world: &mut World, backend: &str) -> Result<Storage<Box<dyn MealStore>>> {
+    let store: Box<dyn MealStore> = match backend {
+        "SQLite" => {
+            let path = unique_storage_path();
+            let path_str = path.to_str().ok_or(Error::UndefinedValue("storage path as str".to_string()))?.to_string();
+            world.storage_path = Some(path);
+            Box::new(SqliteStore::open(&path_str)?)
+        }
+        _ => Box::new(MemoryStore::default()),
+    };
+    let storage = Storage::with_store(store);
+    match world.now {
+        Some(now) => Ok(storage.with_clock(FixedClock::new(now))),
+        None => Ok(storage),
+    }
+}
+
+/// Stops the clock at noon (UTC) of the given date
+#[given(regex = r"^the current date is (?P<date>\d{4}-\d{2}-\d{2})$")]
+pub async fn the_current_date_is(world: &mut World, date: String) -> Result<()> {
+    let naive_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")?;
+    let noon = naive_date.and_hms_opt(12, 0, 0).ok_or(Error::UndefinedValue("noon".to_string()))?;
+    world.now = Some(Utc.from_utc_datetime(&noon));
+    Ok(())
+}
+
+/// Stops the clock at the given RFC 3339 moment
+#[given(regex = r"^the current moment is (?P<moment>\S+)$")]
+pub async fn the_current_moment_is(world: &mut World, moment: String) -> Result<()> {
+    world.now = Some(DateTime::parse_from_rfc3339(&moment)?.with_timezone(&Utc));
+    Ok(())
+}
+
+/// Provides an empty storage
+#[given(regex = r"^an empty (?P<backend>in-memory|SQLite) storage$")]
+pub async fn an_empty_storage(world: &mut World, backend: String) -> Result<()> {
+    let storage = new_storage(world, &backend)?;
+    world.storage = Some(storage);
+    Ok(())
+}
 
 /// Provides a storage filled with the records specified in the feature file (in the step table)
-#[given(regex = r"^an in-memory storage with the records$")]
-pub async fn a_storage_with_records(world: &mut World, step: &Step) -> Result<()> {
+#[given(regex = r"^an? (?P<backend>in-memory|SQLite) storage with the records$")]
+pub async fn a_storage_with_records(world: &mut World, backend: String, step: &Step) -> Result<()> {
     if let Some(table) = step.table.as_ref() {
-        let storage = Storage::open(":memory:")?;
+        let storage = new_storage(world, &backend)?;
         for row in table.rows.iter().skip(1) {
             let date_string = row[0].parse::<DateString>()?;
             let meal = &row[1];
@@ -20,6 +74,135 @@ pub async fn a_storage_with_records(world: &mut World, step: &Step) -> Result<()
     Ok(())
 }
 
+/// Opens the SQLite storage file of the scenario again, with the clock stopped as before
+#[when(regex = r"^I reopen the storage$")]
+pub async fn reopen_storage(world: &mut World) -> Result<()> {
+    let path = world.storage_path.as_ref().ok_or(Error::UndefinedValue("storage_path".to_string()))?;
+    let path_str = path.to_str().ok_or(Error::UndefinedValue("storage_path as str".to_string()))?.to_string();
+    world.storage = None;
+    let storage = Storage::with_store(Box::new(SqliteStore::open(&path_str)?) as Box<dyn MealStore>);
+    world.storage = Some(match world.now {
+        Some(now) => storage.with_clock(FixedClock::new(now)),
+        None => storage,
+    });
+    Ok(())
+}
+
+/// Records the meal on each of the dates
+#[when(regex = r"^I add the meal (?P<meal>.*) on the dates? (?P<text_dates>.*) to the storage$")]
+pub async fn add_meal_on_dates(world: &mut World, meal: String, text_dates: TextDates) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.add_meal_on_dates(&meal, &text_dates.to_vec_string())?;
+    Ok(())
+}
+
+/// Removes all records in the period
+#[when(regex = r"^I remove the records in (?P<period>.*) from the storage$")]
+pub async fn remove_records(world: &mut World, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.remove(storage.period(&period)?, None)?;
+    Ok(())
+}
+
+/// Removes the records of the meal in the period
+#[when(regex = r"^I remove the meal (?P<meal>.*) in the period (?P<period>.*)$")]
+pub async fn remove_meal_in_period(world: &mut World, meal: String, period: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let period = storage.period(&period)?;
+    world.result_vec_mealrecord = Some(storage.remove(period, Some(meal)));
+    Ok(())
+}
+
+/// Renames all records of a meal
+#[when(regex = r"^I rename (?P<old_name>.*) to (?P<new_name>.*) in the storage$")]
+pub async fn rename_meal(world: &mut World, old_name: String, new_name: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.rename(&old_name, &new_name, None)?;
+    Ok(())
+}
+
+/// Sets the cooldown of a meal to a duration like `2 weeks`
+#[when(regex = r"^I set the cooldown of (?P<meal>.*) to (?P<duration>.*)$")]
+pub async fn set_cooldown(world: &mut World, meal: String, duration: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.set_cooldown(&meal, Some(parse_duration(&duration)?))?;
+    Ok(())
+}
+
+/// Adds the tags to a meal
+#[when(regex = r"^I tag (?P<meal>.*) with (?P<tags>.*)$")]
+pub async fn tag_meal(world: &mut World, meal: String, tags: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.add_tags(&meal, &tags.to_vec_string())?;
+    Ok(())
+}
+
+/// Adds the aliases to a meal
+#[when(regex = r"^I add the aliases (?P<aliases>.*) to (?P<meal>.*)$")]
+pub async fn add_aliases(world: &mut World, aliases: Meals, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    storage.add_aliases(&meal, &aliases.to_vec_string())?;
+    Ok(())
+}
+
+/// Changes the timezone of the storage
+#[when(regex = r"^I set the timezone of the storage to (?P<timezone>.*)$")]
+pub async fn set_timezone(world: &mut World, timezone: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let timezone: Timezone = timezone.parse()?;
+    storage.set_timezone(timezone)?;
+    Ok(())
+}
+
+/// Undoes the latest operation of the journal
+#[when(regex = r"^I undo the latest operation$")]
+pub async fn undo(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_journal_entry = Some(storage.undo());
+    Ok(())
+}
+
+/// Redoes the earliest undone operation of the journal
+#[when(regex = r"^I redo the earliest undone operation$")]
+pub async fn redo(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_journal_entry = Some(storage.redo());
+    Ok(())
+}
+
+/// Asks for meal suggestions
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions$")]
+pub async fn ask_for_suggestions(world: &mut World, number: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.what(number, None, Vec::new(), &TagFilter::default()));
+    Ok(())
+}
+
+/// Asks for meal suggestions which leave out the meals of the ignore list
+#[when(regex = r"^I ask for (?P<number>\d+) meal suggestions ignoring (?P<ignore_list>.*)$")]
+pub async fn ask_for_suggestions_ignoring(world: &mut World, number: u64, ignore_list: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.what(number, None, ignore_list.to_vec_string(), &TagFilter::default()));
+    Ok(())
+}
+
+/// Asks for the last record of each meal
+#[when(regex = r"^I ask for unique meals$")]
+pub async fn ask_for_unique_meals(world: &mut World) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    world.result_vec_mealrecord = Some(storage.get_last_cooked_unique(&TagFilter::default()));
+    Ok(())
+}
+
+/// Asks for the statistics of the most overdue meals
+#[when(regex = r"^I ask for (?P<number>\d+) overdue meals with their statistics$")]
+pub async fn ask_for_overdue_statistics(world: &mut World, number: u64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let result = storage.suggest_statistics(number, None, Vec::new(), &TagFilter::default(), &Diversity::default(), &Overdue);
+    world.result_vec_mealstatistics = Some(result);
+    Ok(())
+}
+
 /// Checks the `Result<Vec<MealRecord>>`
 #[then(regex = r"^I get the meal records (?P<records>.*)$")]
 pub async fn check_result_vec_mealrecord(world: &mut World, expected_records: MealRecords) -> Result<()> {
@@ -32,7 +215,7 @@ pub async fn check_result_vec_mealrecord(world: &mut World, expected_records: Me
 #[then(regex = r"^the storage, asked to show the meal records in the period (?P<show_range>.*), returns (?P<meal_records>.*)$")]
 pub async fn storage_show_meal_records(world: &mut World, show_range: String, expected_meal_records: MealRecords) -> Result<()> {
     let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
-    let actual_meal_records = storage.show(&show_range)?;
+    let actual_meal_records = storage.show(&show_range, &TagFilter::default())?;
     assert_eq!(actual_meal_records, expected_meal_records.to_vec_mealrecord(), "storage.show returned {:?} but we expected {:?}", actual_meal_records, expected_meal_records);
     Ok(())
 }
@@ -45,3 +228,67 @@ pub async fn storage_when_meal(world: &mut World, meal: String, expected_naive_d
     assert_eq!(actual_naive_dates, expected_naive_dates.to_vec_naivedate(), "storage.when returned {:?} but we expected {:?}", actual_naive_dates, expected_naive_dates);
     Ok(())
 }
+
+/// Checks the names of the meals known to the storage
+#[then(regex = r"^the storage knows the meals (?P<meals>.*)$")]
+pub async fn storage_knows_meals(world: &mut World, expected_meals: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let actual_meals: Vec<String> = storage.meals()?.iter().map(|meal| meal.name()).collect();
+    assert_eq!(actual_meals, expected_meals.to_vec_string(), "storage.meals returned {:?} but we expected {:?}", actual_meals, expected_meals);
+    Ok(())
+}
+
+/// Checks the id of a meal known to the storage
+#[then(regex = r"^the meal (?P<meal>.*) has the id (?P<id>\d+)$")]
+pub async fn meal_has_id(world: &mut World, meal: String, expected_id: i64) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let actual_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {} in the storage", meal)))?;
+    assert_eq!(actual_meal.id().get(), expected_id, "meal {} has the id {} but we expected {}", meal, actual_meal.id(), expected_id);
+    Ok(())
+}
+
+/// Checks the cooldown, the tags and the aliases of a meal known to the storage
+#[then(regex = r"^the meal (?P<meal>.*) has a cooldown of (?P<days>\d+) days, the tags (?P<tags>.*) and the aliases (?P<aliases>.*)$")]
+pub async fn meal_has_settings(world: &mut World, meal: String, expected_days: u32, expected_tags: Meals, expected_aliases: Meals) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let known_meal = storage.meal(&meal)?.ok_or(Error::UndefinedValue(format!("meal {}", meal)))?;
+    assert_eq!(known_meal.cooldown(), Some(expected_days), "{} has the cooldown {:?} but we expected {} days", meal, known_meal.cooldown(), expected_days);
+    assert_eq!(known_meal.tags(), expected_tags.to_vec_string(), "{} has the tags {:?} but we expected {:?}", meal, known_meal.tags(), expected_tags);
+    assert_eq!(known_meal.aliases(), expected_aliases.to_vec_string(), "{} has the aliases {:?} but we expected {:?}", meal, known_meal.aliases(), expected_aliases);
+    Ok(())
+}
+
+/// Checks the timezone of the storage
+#[then(regex = r"^the storage has the timezone (?P<timezone>.*)$")]
+pub async fn storage_has_timezone(world: &mut World, expected_timezone: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let actual_timezone = storage.timezone();
+    assert_eq!(actual_timezone.name(), expected_timezone, "storage has the timezone {} but we expected {}", actual_timezone, expected_timezone);
+    Ok(())
+}
+
+/// Checks that the storage does not know a meal
+#[then(regex = r"^the storage does not know the meal (?P<meal>.*)$")]
+pub async fn storage_does_not_know_meal(world: &mut World, meal: String) -> Result<()> {
+    let storage = world.storage.as_ref().ok_or(Error::UndefinedValue("storage".to_string()))?;
+    let option_meal = storage.meal(&meal)?;
+    assert!(option_meal.is_none(), "storage.meal returned {:?} but we expected None", option_meal);
+    Ok(())
+}
+
+/// Checks the meal of the `Result<Option<MealRecord>>` of a random pick
+#[then(regex = r"^the random meal is (?P<meal>.*)$")]
+pub async fn random_meal_is(world: &mut World, expected_meal: String) -> Result<()> {
+    let record = world.result_option_mealrecord.as_ref().ok_or(Error::UndefinedValue("result_option_mealrecord".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?.clone().ok_or(Error::UndefinedValue("random meal".to_string()))?;
+    assert_eq!(record.meal(), expected_meal, "storage.random returned {} but we expected {}", record.meal(), expected_meal);
+    Ok(())
+}
+
+/// Checks the date on which a meal of the statistics is expected next
+#[then(regex = r"^the meal (?P<meal>.+) is expected on (?P<date>\d{4}-\d{2}-\d{2})$")]
+pub async fn meal_is_expected_on(world: &mut World, meal: String, date: String) -> Result<()> {
+    let statistics = world.result_vec_mealstatistics.as_ref().ok_or(Error::UndefinedValue("result_vec_mealstatistics".to_string()))?.as_ref().map_err(|e| Error::UnexpectedErrResult(format!("{:?}", e)))?;
+    let statistic = statistics.iter().find(|statistic| statistic.meal() == meal).ok_or(Error::UndefinedValue(format!("statistics of {}", meal)))?;
+    assert_eq!(statistic.expected_next(), Some(NaiveDate::parse_from_str(&date, "%Y-%m-%d")?));
+    Ok(())
+}
@@ -13,9 +13,9 @@ use tracing_subscriber::{
     fmt::format,
     layer::{Layer, SubscriberExt as _},
 };
-use std::{io, path::Path};
-use libmrot::{Storage, MealRecord};
-use chrono::NaiveDate;
+use std::{io, path::{Path, PathBuf}};
+use libmrot::{ImportReport, JournalEntry, MealRecord, MealStatistics, MealStore, Storage};
+use chrono::{DateTime, NaiveDate, Utc};
 
 /// Result type alias with mrot-test-util's Error.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -24,18 +24,29 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Default, cucumber::World)]
 #[world(init = Self::default)]
 pub struct World {
-    pub storage: Option<Storage>,
+    pub storage: Option<Storage<Box<dyn MealStore>>>,
     pub two_timer_parse_result: Option<String>,
     pub parse_result: Option<libmrot::Result<Vec<NaiveDate>>>,
     pub result_vec_mealrecord: Option<libmrot::Result<Vec<MealRecord>>>,
     pub result_option_mealrecord: Option<libmrot::Result<Option<MealRecord>>>,
     pub result_mealrecord: Option<libmrot::Result<MealRecord>>,
+    pub storage_path: Option<PathBuf>,
+    pub result_open_storage: Option<libmrot::Result<()>>,
+    pub now: Option<DateTime<Utc>>,
+    pub exported: Option<String>,
+    pub result_import_report: Option<libmrot::Result<ImportReport>>,
+    pub result_journal_entry: Option<libmrot::Result<Option<JournalEntry>>>,
+    pub result_vec_mealstatistics: Option<libmrot::Result<Vec<MealStatistics>>>,
 }
 
 /// Clean-up procedure after each scenario
 fn cleanup(world: Option<&mut World>) {
-    if let Some(_w) = world {
+    if let Some(w) = world {
         info!("Cleaning up the World");
+        w.storage = None;
+        if let Some(path) = w.storage_path.take() {
+            let _ = std::fs::remove_file(path);
+        }
     }
 }
 
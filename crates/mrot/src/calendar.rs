@@ -0,0 +1,209 @@
+//! Calendar view of the records, as printed by `mrot show --calendar`
+
+use chrono::{Datelike, Days, NaiveDate};
+use libmrot::{MealRecord, Status};
+use std::{
+    collections::HashMap,
+    env,
+    io::{self, IsTerminal},
+};
+
+/// Width of a day cell in characters
+const CELL_WIDTH: usize = 10;
+/// Marker of the days in the range which have no record
+const EMPTY_DAY: &str = "·";
+const REVERSE: &str = "\x1b[7m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// A grid of the weeks from the Monday before *first_date* through the Sunday after *last_date*,
+/// with the meals of the records in the cells of their days.
+pub(crate) struct Calendar<'a> {
+    records: &'a [MealRecord],
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+    today: NaiveDate,
+    styled: bool,
+}
+
+impl<'a> Calendar<'a> {
+    pub(crate) fn new(
+        records: &'a [MealRecord],
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+        today: NaiveDate,
+    ) -> Self {
+        Calendar {
+            records,
+            first_date,
+            last_date,
+            today,
+            styled: false,
+        }
+    }
+
+    /// Highlights today and the planned meals with terminal colors, in addition to the markers
+    /// which are always printed.
+    pub(crate) fn styled(mut self, styled: bool) -> Self {
+        self.styled = styled;
+        self
+    }
+
+    /// Returns the title of the calendar: the month or the months of the range.
+    fn title(&self) -> String {
+        let (first, last) = (self.first_date, self.last_date);
+        if (first.year(), first.month()) == (last.year(), last.month()) {
+            first.format("%B %Y").to_string()
+        } else if first.year() == last.year() {
+            format!("{} – {}", first.format("%B"), last.format("%B %Y"))
+        } else {
+            format!("{} – {}", first.format("%B %Y"), last.format("%B %Y"))
+        }
+    }
+
+    /// Returns the lines of a day cell: the day of the month followed by the meals of the day.
+    /// Days outside the range are left blank.
+    fn cell(&self, date: NaiveDate, meals: &[&MealRecord]) -> Vec<Cell> {
+        if date < self.first_date || date > self.last_date {
+            return Vec::new();
+        }
+        let day = if date.day() == 1 || date == self.first_date {
+            date.format("%b %-d").to_string()
+        } else {
+            date.day().to_string()
+        };
+        let mut lines = if date == self.today {
+            vec![Cell::new(format!("[{}]", day), Some(REVERSE))]
+        } else {
+            vec![Cell::new(day, None)]
+        };
+        if meals.is_empty() {
+            lines.push(Cell::new(EMPTY_DAY.to_string(), None));
+        }
+        lines.extend(meals.iter().map(|record| match record.status() {
+            Status::Eaten => Cell::new(record.meal(), None),
+            Status::Planned => Cell::new(format!("({})", record.meal()), Some(DIM)),
+            Status::Skipped => Cell::new(format!("~{}", record.meal()), Some(DIM)),
+        }));
+        lines
+    }
+}
+
+impl std::fmt::Display for Calendar<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut meals_by_date: HashMap<NaiveDate, Vec<&MealRecord>> = HashMap::new();
+        for record in self.records {
+            meals_by_date
+                .entry(record.naive_date())
+                .or_default()
+                .push(record);
+        }
+        writeln!(f, "{}", self.title())?;
+        let weekdays: Vec<String> = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .map(|weekday| format!("{:<CELL_WIDTH$}", weekday))
+            .collect();
+        writeln!(f, "{}", weekdays.join(" ").trim_end())?;
+        let mut monday = self
+            .first_date
+            .checked_sub_days(Days::new(
+                self.first_date.weekday().num_days_from_monday().into(),
+            ))
+            .unwrap_or(self.first_date);
+        while monday <= self.last_date {
+            let cells: Vec<Vec<Cell>> = monday
+                .iter_days()
+                .take(7)
+                .map(|date| {
+                    let meals = meals_by_date.get(&date).map(Vec::as_slice).unwrap_or(&[]);
+                    self.cell(date, meals)
+                })
+                .collect();
+            let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+            for line in 0..height {
+                let texts: Vec<String> = cells
+                    .iter()
+                    .map(|cell| match cell.get(line) {
+                        Some(cell_line) => cell_line.render(self.styled),
+                        None => " ".repeat(CELL_WIDTH),
+                    })
+                    .collect();
+                writeln!(f, "{}", texts.join(" ").trim_end())?;
+            }
+            match monday.checked_add_days(Days::new(7)) {
+                Some(next_monday) => monday = next_monday,
+                None => break,
+            }
+        }
+        write!(
+            f,
+            "[day] today, (meal) planned, ~meal skipped, {} no record",
+            EMPTY_DAY
+        )
+    }
+}
+
+/// Returns true if the calendar printed to stdout should be styled with terminal colors, i.e.
+/// if stdout is a terminal and the `NO_COLOR` environment variable is not set.
+pub(crate) fn use_colors() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// A line of a day cell, with the terminal style in which it is highlighted.
+struct Cell {
+    text: String,
+    style: Option<&'static str>,
+}
+
+impl Cell {
+    fn new(text: String, style: Option<&'static str>) -> Self {
+        Cell { text, style }
+    }
+
+    /// Returns the text cut or padded to the width of the cell, styled if *styled*.
+    fn render(&self, styled: bool) -> String {
+        let text = if self.text.chars().count() > CELL_WIDTH {
+            let mut cut: String = self.text.chars().take(CELL_WIDTH - 1).collect();
+            cut.push('…');
+            cut
+        } else {
+            format!("{:<CELL_WIDTH$}", self.text)
+        };
+        match (styled, self.style) {
+            (true, Some(style)) => format!("{}{}{}", style, text, RESET),
+            _ => text,
+        }
+    }
+}
+
+#[test]
+fn calendar_of_a_week() {
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+    let records = vec![
+        MealRecord::from_meal_and_naivedate("pizza", &date(10)),
+        MealRecord::from_meal_and_naivedate("spaghetti bolognese", &date(12)),
+        MealRecord::from_meal_and_naivedate("steak", &date(14)).with_status(Status::Planned),
+        MealRecord::from_meal_and_naivedate("salad", &date(14)).with_status(Status::Planned),
+    ];
+    let calendar = Calendar::new(&records, date(10), date(16), date(12));
+    assert_eq!(
+        calendar.to_string(),
+        "March 2025\n\
+         Mon        Tue        Wed        Thu        Fri        Sat        Sun\n\
+         Mar 10     11         [12]       13         14         15         16\n\
+         pizza      ·          spaghetti… ·          (steak)    ·          ·\n                                            \
+         (salad)\n\
+         [day] today, (meal) planned, ~meal skipped, · no record"
+    );
+}
+
+#[test]
+fn styled_calendar_highlights_today_and_planned_meals() {
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+    let records =
+        vec![MealRecord::from_meal_and_naivedate("steak", &date(13)).with_status(Status::Planned)];
+    let calendar = Calendar::new(&records, date(12), date(13), date(12)).styled(true);
+    let text = calendar.to_string();
+    assert!(text.contains("\x1b[7m[Mar 12]  \x1b[0m"));
+    assert!(text.contains("\x1b[2m(steak)   \x1b[0m"));
+}
@@ -1,19 +1,63 @@
 //! CLI for mrot
 
+use crate::{
+    completion::{ignored_candidates, meal_candidates, meal_or_tag_candidates},
+    config::Strategy,
+    output::{ExchangeFormat, OutputFormat},
+    NOW_ENV_VAR,
+};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 #[cfg(test)]
 use clap::CommandFactory;
 use clap::{
     ArgAction::{Append, SetTrue},
     Args, Parser, Subcommand,
 };
+use clap_complete::engine::ArgValueCandidates;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 pub(crate) struct Cli {
+    /// Pretend that the current moment is this date (YYYY-MM-DD), date and time in the timezone
+    /// of the storage (YYYY-MM-DDTHH:MM:SS) or RFC 3339 timestamp. Makes scripts reproducible.
+    #[arg(long, global = true, hide = true, env = NOW_ENV_VAR, value_parser = parse_now)]
+    pub(crate) now: Option<Now>,
+    /// Output format of the commands which list meals or dates, and of export
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub(crate) format: OutputFormat,
     #[command(subcommand)]
     pub(crate) command: Command,
 }
 
+/// The moment given with the `--now` option.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Now {
+    /// An RFC 3339 timestamp
+    Exact(DateTime<Utc>),
+    /// A date and time in the timezone of the storage, which is only known once it is opened
+    WallClock(NaiveDateTime),
+}
+
+/// Parses the value of the `--now` option. A date without time means noon of that day.
+fn parse_now(s: &str) -> Result<Now, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Ok(Now::Exact(datetime.with_timezone(&Utc)));
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(12, 0, 0).expect("noon is a valid time"))
+        })
+        .map(Now::WallClock)
+        .map_err(|e| {
+            format!(
+                "{}: expected YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS or RFC 3339",
+                e
+            )
+        })
+}
+
 #[derive(Subcommand)]
 pub(crate) enum Command {
     /// Add records of meals eaten
@@ -24,8 +68,18 @@ pub(crate) enum Command {
     What(WhatArgs),
     /// Suggest a random meal
     Random(RandomArgs),
+    /// Plan meals for the days of a period which have no record yet
+    Plan(PlanArgs),
+    /// Confirm that planned meals were eaten
+    Confirm(ConfirmArgs),
+    /// Mark planned meals as skipped, i.e. not eaten
+    Skip(SkipArgs),
+    /// Show past planned meals which were neither confirmed nor skipped
+    Pending(PendingArgs),
     /// Show recorded meals
     Show(ShowArgs),
+    /// Show recorded meals in a calendar grid
+    Cal(CalArgs),
     /// Searches records of a given meal
     When(WhenArgs),
     /// Show unique recorded meals
@@ -34,6 +88,29 @@ pub(crate) enum Command {
     Remove(RemoveArgs),
     /// Rename meals
     Rename(RenameArgs),
+    /// Undo the latest add, import, remove or rename
+    Undo(UndoArgs),
+    /// Redo the earliest undone operation
+    Redo(RedoArgs),
+    /// Show recent operations which changed the records
+    History(HistoryArgs),
+    /// Show statistics of the eaten meals
+    Stats(StatsArgs),
+    /// Export the records as CSV, JSON or iCalendar
+    Export(ExportArgs),
+    /// Import records exported earlier as CSV, JSON or iCalendar
+    Import(ImportArgs),
+    /// Manage how long meals rest before they are suggested again
+    #[command(subcommand)]
+    Cooldown(CooldownCommand),
+    /// Manage the tags of meals, e.g. vegetarian or fish
+    #[command(subcommand)]
+    Tag(TagCommand),
+    /// Manage other names of meals, e.g. spag bol for spaghetti bolognese
+    #[command(subcommand)]
+    Alias(AliasCommand),
+    /// Plan meals in an interactive terminal UI
+    Tui(TuiArgs),
     /// Manage mrot configuration
     #[command(subcommand)]
     Config(ConfigCommand),
@@ -48,6 +125,7 @@ pub(crate) enum Command {
 #[derive(Args)]
 pub(crate) struct AddArgs {
     /// Meal to add (e.g. "rib eye steak")
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
     pub(crate) meal: String,
     /// Day to add this meal on
     #[arg(short, long, action = Append)]
@@ -68,8 +146,91 @@ pub(crate) struct WhatArgs {
     /// Limit to a number of suggestions (overrides config)
     #[arg(short, long)]
     pub(crate) number: Option<u64>,
-    /// Ignore a certain meal (can use multiple times, overrides config)
+    /// Ignore a meal, or the meals with a tag as tag:TAG (can use multiple times, overrides config)
+    #[arg(short, long, action = Append, add = ArgValueCandidates::new(meal_or_tag_candidates))]
+    pub(crate) ignore: Option<Vec<String>>,
+    /// Ignore meals planned in this time span
+    #[arg(short = 'p', long)]
+    pub(crate) ignore_period: Option<String>,
+    /// Consider also ignored meals
+    #[arg(short = 'I', long, action = SetTrue, conflicts_with = "ignore")]
+    pub(crate) no_ignore: bool,
+    /// Disregard planned meals
+    #[arg(short = 'P', long, action = SetTrue, conflicts_with = "ignore_period")]
+    pub(crate) no_ignore_period: bool,
+    /// How to rank the meals to suggest (overrides config)
+    #[arg(short, long)]
+    pub(crate) strategy: Option<Strategy>,
+    /// Suggest at most one meal per tag (overrides config)
+    #[arg(short = 'o', long, action = SetTrue, conflicts_with = "many_per_tag")]
+    pub(crate) one_per_tag: bool,
+    /// Allow several meals with the same tag (overrides config)
+    #[arg(short = 'O', long, action = SetTrue)]
+    pub(crate) many_per_tag: bool,
+    /// Leave out the meals sharing a tag with a meal recorded in this time span (overrides config)
+    #[arg(short = 'l', long)]
+    pub(crate) look_back: Option<String>,
+    /// Disregard the tags of recently recorded meals
+    #[arg(short = 'L', long, action = SetTrue, conflicts_with = "look_back")]
+    pub(crate) no_look_back: bool,
+    /// Consider only the meals with this tag (can use multiple times)
     #[arg(short, long, action = Append)]
+    pub(crate) tag: Option<Vec<String>>,
+    /// Leave out the meals with this tag (can use multiple times)
+    #[arg(short = 'T', long, action = Append)]
+    pub(crate) exclude_tag: Option<Vec<String>>,
+}
+
+#[derive(Args)]
+pub(crate) struct RandomArgs {
+    /// Prefer the meals not consumed for a long time, leaving out the ignored and planned meals
+    /// like the what command
+    #[arg(short, long, action = SetTrue)]
+    pub(crate) weighted: bool,
+    /// Number of distinct meals to pick
+    #[arg(short, long, requires = "weighted")]
+    pub(crate) number: Option<u64>,
+    /// Seed for the random picks, to pick the same meals again
+    #[arg(short, long, requires = "weighted")]
+    pub(crate) seed: Option<u64>,
+    /// Ignore a meal, or the meals with a tag as tag:TAG (can use multiple times, overrides config)
+    #[arg(
+        short,
+        long,
+        action = Append,
+        requires = "weighted",
+        add = ArgValueCandidates::new(meal_or_tag_candidates)
+    )]
+    pub(crate) ignore: Option<Vec<String>>,
+    /// Ignore meals planned in this time span
+    #[arg(short = 'p', long, requires = "weighted")]
+    pub(crate) ignore_period: Option<String>,
+    /// Consider also ignored meals
+    #[arg(short = 'I', long, action = SetTrue, conflicts_with = "ignore", requires = "weighted")]
+    pub(crate) no_ignore: bool,
+    /// Disregard planned meals
+    #[arg(
+        short = 'P',
+        long,
+        action = SetTrue,
+        conflicts_with = "ignore_period",
+        requires = "weighted"
+    )]
+    pub(crate) no_ignore_period: bool,
+    /// Consider only the meals with this tag (can use multiple times)
+    #[arg(short, long, action = Append)]
+    pub(crate) tag: Option<Vec<String>>,
+    /// Leave out the meals with this tag (can use multiple times)
+    #[arg(short = 'T', long, action = Append)]
+    pub(crate) exclude_tag: Option<Vec<String>>,
+}
+
+#[derive(Args)]
+pub(crate) struct PlanArgs {
+    /// Time range to plan meals for (e.g. "next week")
+    pub(crate) range: String,
+    /// Ignore a meal, or the meals with a tag as tag:TAG (can use multiple times, overrides config)
+    #[arg(short, long, action = Append, add = ArgValueCandidates::new(meal_or_tag_candidates))]
     pub(crate) ignore: Option<Vec<String>>,
     /// Ignore meals planned in this time span
     #[arg(short = 'p', long)]
@@ -80,44 +241,283 @@ pub(crate) struct WhatArgs {
     /// Disregard planned meals
     #[arg(short = 'P', long, action = SetTrue, conflicts_with = "ignore_period")]
     pub(crate) no_ignore_period: bool,
+    /// How to rank the meals to plan (overrides config)
+    #[arg(short, long)]
+    pub(crate) strategy: Option<Strategy>,
+    /// Plan at most one meal per tag (overrides config)
+    #[arg(short = 'o', long, action = SetTrue, conflicts_with = "many_per_tag")]
+    pub(crate) one_per_tag: bool,
+    /// Allow several meals with the same tag (overrides config)
+    #[arg(short = 'O', long, action = SetTrue)]
+    pub(crate) many_per_tag: bool,
+    /// Leave out the meals sharing a tag with a meal recorded in this time span (overrides config)
+    #[arg(short = 'l', long)]
+    pub(crate) look_back: Option<String>,
+    /// Disregard the tags of recently recorded meals
+    #[arg(short = 'L', long, action = SetTrue, conflicts_with = "look_back")]
+    pub(crate) no_look_back: bool,
+    /// Consider only the meals with this tag (can use multiple times)
+    #[arg(short, long, action = Append)]
+    pub(crate) tag: Option<Vec<String>>,
+    /// Leave out the meals with this tag (can use multiple times)
+    #[arg(short = 'T', long, action = Append)]
+    pub(crate) exclude_tag: Option<Vec<String>>,
+    /// Only show the plan, do not record it
+    #[arg(short = 'n', long, action = SetTrue)]
+    pub(crate) dry_run: bool,
+    /// Record the plan without asking for confirmation
+    #[arg(short, long, action = SetTrue)]
+    pub(crate) yes: bool,
 }
 
 #[derive(Args)]
-pub(crate) struct RandomArgs;
+pub(crate) struct ConfirmArgs {
+    /// Time range of the records to confirm (default: all pending records)
+    pub(crate) range: Option<String>,
+    /// Confirm only the records of this meal
+    #[arg(short, long, add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: Option<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct SkipArgs {
+    /// Time range of the records to skip (default: all pending records)
+    pub(crate) range: Option<String>,
+    /// Skip only the records of this meal
+    #[arg(short, long, add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: Option<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct PendingArgs;
 
 #[derive(Args)]
 pub(crate) struct ShowArgs {
     /// Date or date range to show meals from (overrides config)
     pub(crate) range: Option<String>,
+    /// Consider only the meals with this tag (can use multiple times)
+    #[arg(short, long, action = Append)]
+    pub(crate) tag: Option<Vec<String>>,
+    /// Leave out the meals with this tag (can use multiple times)
+    #[arg(short = 'T', long, action = Append)]
+    pub(crate) exclude_tag: Option<Vec<String>>,
+    /// Show the meals in a calendar grid (only text format)
+    #[arg(short, long)]
+    pub(crate) calendar: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct CalArgs {
+    /// Date or date range to show meals from (overrides config)
+    pub(crate) range: Option<String>,
+    /// Consider only the meals with this tag (can use multiple times)
+    #[arg(short, long, action = Append)]
+    pub(crate) tag: Option<Vec<String>>,
+    /// Leave out the meals with this tag (can use multiple times)
+    #[arg(short = 'T', long, action = Append)]
+    pub(crate) exclude_tag: Option<Vec<String>>,
 }
 
 #[derive(Args)]
 pub(crate) struct WhenArgs {
     /// Meal to search for
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
     pub(crate) meal: String,
 }
 
 #[derive(Args)]
-pub(crate) struct UniqueArgs;
+pub(crate) struct UniqueArgs {
+    /// Consider only the meals with this tag (can use multiple times)
+    #[arg(short, long, action = Append)]
+    pub(crate) tag: Option<Vec<String>>,
+    /// Leave out the meals with this tag (can use multiple times)
+    #[arg(short = 'T', long, action = Append)]
+    pub(crate) exclude_tag: Option<Vec<String>>,
+}
 
 #[derive(Args)]
 pub(crate) struct RemoveArgs {
     /// Time range to show meals from
     pub(crate) range: String,
     /// meal to remove
-    #[arg(short, long)]
+    #[arg(short, long, add = ArgValueCandidates::new(meal_candidates))]
     pub(crate) meal: Option<String>,
+    /// Only show the records which would be removed
+    #[arg(short = 'n', long, action = SetTrue)]
+    pub(crate) dry_run: bool,
+    /// Do not ask for confirmation, however many records would be removed
+    #[arg(short, long, action = SetTrue)]
+    pub(crate) yes: bool,
 }
 
 #[derive(Args)]
 pub(crate) struct RenameArgs {
     /// Old name of a meal
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
     pub(crate) old_name: String,
     /// New name of a meal
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
     pub(crate) new_name: String,
-    /// New name of a meal
+    /// Time range in which to rename the meal (default: all records)
     #[arg(short, long)]
     pub(crate) period: Option<String>,
+    /// Only show the records which would be renamed
+    #[arg(short = 'n', long, action = SetTrue)]
+    pub(crate) dry_run: bool,
+    /// Do not ask for confirmation, however many records would be renamed
+    #[arg(short, long, action = SetTrue)]
+    pub(crate) yes: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct UndoArgs;
+
+#[derive(Args)]
+pub(crate) struct RedoArgs;
+
+#[derive(Args)]
+pub(crate) struct TuiArgs;
+
+#[derive(Args)]
+pub(crate) struct HistoryArgs {
+    /// Number of recent operations to show
+    #[arg(short, long, default_value_t = 10)]
+    pub(crate) number: usize,
+}
+
+#[derive(Args)]
+pub(crate) struct StatsArgs {
+    /// Date or date range to compute the statistics for (default: all records)
+    pub(crate) period: Option<String>,
+    /// Number of most frequent meals to show
+    #[arg(short, long, default_value_t = 5)]
+    pub(crate) number: usize,
+}
+
+#[derive(Args)]
+pub(crate) struct ExportArgs {
+    /// Date or date range to export meals from (default: all records, or the configured show
+    /// range for iCalendar)
+    pub(crate) range: Option<String>,
+    /// File to write the records to (default: standard output) in the format csv, json or ics
+    /// given by --format. Without --format, a file ending with .json or .ics gets JSON or
+    /// iCalendar, anything else CSV.
+    #[arg(short, long)]
+    pub(crate) output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+#[command(allow_missing_positional = true)]
+pub(crate) struct ImportArgs {
+    /// Format of the imported records (default: from the extension of the file, or CSV)
+    #[arg(id = "file_format", value_name = "FORMAT", value_enum)]
+    pub(crate) format: Option<ExchangeFormat>,
+    /// File to read the records from, "-" for standard input. A file ending with .json or .ics is
+    /// read as JSON or iCalendar unless the format says otherwise.
+    pub(crate) file: PathBuf,
+    /// Only report what would be imported
+    #[arg(short = 'n', long, action = SetTrue)]
+    pub(crate) dry_run: bool,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum CooldownCommand {
+    /// Do not suggest a meal until the duration has passed since its last record
+    Set(CooldownSetArgs),
+    /// Remove the cooldown of a meal
+    Clear(CooldownClearArgs),
+    /// Show the meals with a cooldown
+    List(CooldownListArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct CooldownSetArgs {
+    /// Meal to set the cooldown of
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: String,
+    /// Duration like "10 days", "2 weeks" or "3 months and 1 week"
+    pub(crate) duration: String,
+}
+
+#[derive(Args)]
+pub(crate) struct CooldownClearArgs {
+    /// Meal to remove the cooldown of
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: String,
+}
+
+#[derive(Args)]
+pub(crate) struct CooldownListArgs;
+
+#[derive(Subcommand)]
+pub(crate) enum TagCommand {
+    /// Add tags to a meal
+    Add(TagAddArgs),
+    /// Remove tags from a meal
+    Remove(TagRemoveArgs),
+    /// Show the tags of the meals
+    List(TagListArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct TagAddArgs {
+    /// Meal to tag
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: String,
+    /// Tags to add (e.g. vegetarian)
+    #[arg(required = true)]
+    pub(crate) tags: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct TagRemoveArgs {
+    /// Meal to remove the tags from
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: String,
+    /// Tags to remove
+    #[arg(required = true)]
+    pub(crate) tags: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct TagListArgs {
+    /// Show only the tags of this meal (default: all tagged meals)
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum AliasCommand {
+    /// Add aliases to a meal, merging the records of meals which have the names of the aliases
+    Add(AliasAddArgs),
+    /// Remove aliases
+    Remove(AliasRemoveArgs),
+    /// Show the aliases of the meals
+    List(AliasListArgs),
+}
+
+#[derive(Args)]
+pub(crate) struct AliasAddArgs {
+    /// Meal to add the aliases to
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: String,
+    /// Aliases to add (e.g. "spag bol")
+    #[arg(required = true)]
+    pub(crate) aliases: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct AliasRemoveArgs {
+    /// Aliases to remove
+    #[arg(required = true)]
+    pub(crate) aliases: Vec<String>,
+}
+
+#[derive(Args)]
+pub(crate) struct AliasListArgs {
+    /// Show only the aliases of this meal (default: all meals with aliases)
+    #[arg(add = ArgValueCandidates::new(meal_candidates))]
+    pub(crate) meal: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -140,6 +540,10 @@ pub(crate) enum ConfigSetCommand {
     What(ConfigSetWhatCommand),
     /// Set the limits when showing planned meals
     Show(ConfigSetShowArgs),
+    /// Set the timezone in which days begin and end (stored in the records file)
+    Timezone(ConfigSetTimezoneArgs),
+    /// Set the number of records which remove and rename change without asking for confirmation
+    Confirm(ConfigSetConfirmArgs),
 }
 
 #[derive(Subcommand)]
@@ -148,6 +552,12 @@ pub(crate) enum ConfigSetWhatCommand {
     Number(ConfigSetWhatNumberArgs),
     /// Set the number of days to look ahead for planned meals
     IgnorePeriod(ConfigSetWhatIgnorePeriodArgs),
+    /// Set how to rank the meals to suggest
+    Strategy(ConfigSetWhatStrategyArgs),
+    /// Set whether to suggest at most one meal per tag
+    OnePerTag(ConfigSetWhatOnePerTagArgs),
+    /// Set the period whose meals' tags are not suggested
+    LookBack(ConfigSetWhatLookBackArgs),
 }
 
 #[derive(Args)]
@@ -163,12 +573,45 @@ pub(crate) struct ConfigSetWhatIgnorePeriodArgs {
     pub(crate) ignore_period: Option<String>,
 }
 
+#[derive(Args)]
+pub(crate) struct ConfigSetWhatStrategyArgs {
+    /// How to rank the meals to suggest
+    pub(crate) strategy: Strategy,
+}
+
+#[derive(Args)]
+pub(crate) struct ConfigSetWhatOnePerTagArgs {
+    /// Suggest at most one meal per tag
+    #[arg(action = clap::ArgAction::Set)]
+    pub(crate) one_per_tag: bool,
+}
+
+#[derive(Args)]
+pub(crate) struct ConfigSetWhatLookBackArgs {
+    /// Optional string with a date expression describing the date or date range whose meals' tags
+    /// are not suggested. Enter no string at all (not even an empty string) to configure no
+    /// look-back period.
+    pub(crate) look_back: Option<String>,
+}
+
 #[derive(Args)]
 pub(crate) struct ConfigSetShowArgs {
     /// Time range in which to show meals
     pub(crate) range: String,
 }
 
+#[derive(Args)]
+pub(crate) struct ConfigSetTimezoneArgs {
+    /// IANA timezone name (e.g. "Europe/Prague"), or "local" for the timezone of this system
+    pub(crate) timezone: String,
+}
+
+#[derive(Args)]
+pub(crate) struct ConfigSetConfirmArgs {
+    /// Max number of records to change without asking for confirmation
+    pub(crate) threshold: usize,
+}
+
 #[derive(Subcommand)]
 pub(crate) enum ConfigGetCommand {
     /// See the configuration for meal suggestions
@@ -176,6 +619,10 @@ pub(crate) enum ConfigGetCommand {
     What(ConfigGetWhatCommand),
     /// See the configuration for showing meals
     Show(ConfigGetShowArgs),
+    /// See the timezone in which days begin and end
+    Timezone(ConfigGetTimezoneArgs),
+    /// See the number of records which remove and rename change without asking for confirmation
+    Confirm(ConfigGetConfirmArgs),
 }
 
 #[derive(Subcommand)]
@@ -184,6 +631,12 @@ pub(crate) enum ConfigGetWhatCommand {
     Number(ConfigGetWhatNumberArgs),
     /// Days to look ahead for planned meals
     IgnorePeriod(ConfigGetWhatIgnorePeriodArgs),
+    /// How to rank the meals to suggest
+    Strategy(ConfigGetWhatStrategyArgs),
+    /// Whether to suggest at most one meal per tag
+    OnePerTag(ConfigGetWhatOnePerTagArgs),
+    /// Period whose meals' tags are not suggested
+    LookBack(ConfigGetWhatLookBackArgs),
 }
 
 #[derive(Args)]
@@ -192,16 +645,31 @@ pub(crate) struct ConfigGetWhatNumberArgs;
 #[derive(Args)]
 pub(crate) struct ConfigGetWhatIgnorePeriodArgs;
 
+#[derive(Args)]
+pub(crate) struct ConfigGetWhatStrategyArgs;
+
+#[derive(Args)]
+pub(crate) struct ConfigGetWhatOnePerTagArgs;
+
+#[derive(Args)]
+pub(crate) struct ConfigGetWhatLookBackArgs;
+
 #[derive(Args)]
 pub(crate) struct ConfigGetShowArgs;
 
+#[derive(Args)]
+pub(crate) struct ConfigGetTimezoneArgs;
+
+#[derive(Args)]
+pub(crate) struct ConfigGetConfirmArgs;
+
 #[derive(Subcommand)]
 pub(crate) enum ConfigIgnoreCommand {
-    /// Add a meal to the ignore list
+    /// Add a meal or a tag to the ignore list
     Add(ConfigIgnoreAddArgs),
-    /// Remove a meal from the ignore list
+    /// Remove a meal or a tag from the ignore list
     Remove(ConfigIgnoreRemoveArgs),
-    /// Show the meals on the ignore list
+    /// Show the meals and tags on the ignore list
     Show(ConfigIgnoreShowArgs),
     /// Clear the meal ignore list
     Clear(ConfigIgnoreClearArgs),
@@ -209,13 +677,15 @@ pub(crate) enum ConfigIgnoreCommand {
 
 #[derive(Args)]
 pub(crate) struct ConfigIgnoreAddArgs {
-    /// Meal to add to the ignore list
+    /// Meal, or tag given as tag:TAG, to add to the ignore list
+    #[arg(add = ArgValueCandidates::new(meal_or_tag_candidates))]
     pub(crate) meal: String,
 }
 
 #[derive(Args)]
 pub(crate) struct ConfigIgnoreRemoveArgs {
-    /// Meal to remove from the ignore list
+    /// Meal or tag to remove from the ignore list
+    #[arg(add = ArgValueCandidates::new(ignored_candidates))]
     pub(crate) meal: String,
 }
 
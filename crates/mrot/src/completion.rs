@@ -0,0 +1,171 @@
+//! Dynamic shell completion of meal names and ignore list entries
+
+use crate::{
+    config::MrotConfig,
+    run::{get_storage_path, APP_NAME, CONFIG_FILE_NAME},
+};
+use clap_complete::{
+    engine::{complete, CompletionCandidate},
+    env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Shells, Zsh},
+};
+use libmrot::{Storage, TagFilter, TAG_PREFIX};
+use serde_json::json;
+use std::{ffi::OsString, io::Write, path::Path};
+
+/// The shells for which `COMPLETE=<shell> mrot` prints the registration of dynamic completions.
+pub(crate) const SHELLS: Shells<'static> =
+    Shells(&[&Bash, &Elvish, &Fish, &Nushell, &Powershell, &Zsh]);
+
+/// Opens the storage read-only if it exists. Completion must neither create, migrate nor change
+/// it, so there are no candidates until a command has migrated an outdated storage.
+fn existing_storage() -> Option<Storage> {
+    let storage_path = get_storage_path().ok()?;
+    match Path::new(&storage_path).exists() {
+        true => Storage::open_read_only(&storage_path).ok(),
+        false => None,
+    }
+}
+
+/// Completes the names of the recorded meals, with the date of their last record as help.
+pub(crate) fn meal_candidates() -> Vec<CompletionCandidate> {
+    let Some(storage) = existing_storage() else {
+        return Vec::new();
+    };
+    storage
+        .get_last_cooked_unique(&TagFilter::default())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|record| {
+            CompletionCandidate::new(record.meal())
+                .help(Some(record.naive_date().to_string().into()))
+        })
+        .collect()
+}
+
+/// Completes the names of the recorded meals and the tags of the meals, with the tag prefix.
+pub(crate) fn meal_or_tag_candidates() -> Vec<CompletionCandidate> {
+    let mut tags: Vec<String> = existing_storage()
+        .and_then(|storage| storage.meals().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|meal| meal.tags())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    meal_candidates()
+        .into_iter()
+        .chain(tags.into_iter().map(|tag| {
+            CompletionCandidate::new(format!("{}{}", TAG_PREFIX, tag)).help(Some("tag".into()))
+        }))
+        .collect()
+}
+
+/// Completes the entries of the configured ignore list.
+pub(crate) fn ignored_candidates() -> Vec<CompletionCandidate> {
+    confy::load::<MrotConfig>(APP_NAME, CONFIG_FILE_NAME)
+        .map(|cfg| cfg.what.ignore.to_vec_string())
+        .unwrap_or_default()
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Nushell completion adapter, which clap_complete does not provide. It registers an external
+/// completer which hands the other commands to the completer configured before.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Nushell;
+
+impl EnvCompleter for Nushell {
+    fn name(&self) -> &'static str {
+        "nushell"
+    }
+
+    fn is(&self, name: &str) -> bool {
+        name == "nushell" || name == "nu"
+    }
+
+    fn write_registration(
+        &self,
+        var: &str,
+        name: &str,
+        bin: &str,
+        completer: &str,
+        buf: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        let previous = format!("${}_previous_completer", name.replace('-', "_"));
+        writeln!(
+            buf,
+            r#"let {previous_var} = $env.config.completions.external.completer
+$env.config.completions.external.enable = true
+$env.config.completions.external.completer = {{|spans|
+    if ($spans | first) == '{bin}' {{
+        {var}=nushell ^'{completer}' -- ...$spans | from json
+    }} else if {previous} != null {{
+        do {previous} $spans
+    }}
+}}"#,
+            previous_var = &previous[1..],
+        )
+    }
+
+    fn write_complete(
+        &self,
+        cmd: &mut clap::Command,
+        args: Vec<OsString>,
+        current_dir: Option<&Path>,
+        buf: &mut dyn Write,
+    ) -> Result<(), std::io::Error> {
+        let index = args.len() - 1;
+        let candidates: Vec<_> = complete(cmd, args, index, current_dir)?
+            .into_iter()
+            .map(|candidate| {
+                let value = candidate.get_value().to_string_lossy().into_owned();
+                match candidate.get_help() {
+                    Some(help) => json!({
+                        "value": value,
+                        "description": help.to_string().lines().next().unwrap_or_default(),
+                    }),
+                    None => json!({ "value": value }),
+                }
+            })
+            .collect();
+        writeln!(buf, "{}", json!(candidates))
+    }
+}
+
+#[test]
+fn nushell_completes_as_json() {
+    use clap::{Arg, Command};
+    use clap_complete::engine::ArgValueCandidates;
+
+    let mut cmd = Command::new("mrot").subcommand(Command::new("when").arg(Arg::new("meal").add(
+        ArgValueCandidates::new(|| {
+            vec![
+                CompletionCandidate::new("pizza").help(Some("2025-04-12".into())),
+                CompletionCandidate::new("pita"),
+                CompletionCandidate::new("curry"),
+            ]
+        }),
+    )));
+    let args = ["mrot", "when", "pi"].map(OsString::from).to_vec();
+    let mut buf = Vec::new();
+    Nushell
+        .write_complete(&mut cmd, args, None, &mut buf)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(buf).unwrap(),
+        "[{\"description\":\"2025-04-12\",\"value\":\"pizza\"},{\"value\":\"pita\"}]\n"
+    );
+}
+
+#[test]
+fn nushell_registration_keeps_previous_completer() {
+    let mut buf = Vec::new();
+    Nushell
+        .write_registration("COMPLETE", "mrot", "mrot", "/usr/bin/mrot", &mut buf)
+        .unwrap();
+    let registration = String::from_utf8(buf).unwrap();
+    assert!(registration.starts_with("let mrot_previous_completer = "));
+    assert!(registration.contains("COMPLETE=nushell ^'/usr/bin/mrot' -- ...$spans | from json"));
+    assert!(registration.contains("do $mrot_previous_completer $spans"));
+}
@@ -1,5 +1,7 @@
 //! Configuration for mrot.
 
+use clap::ValueEnum;
+use libmrot::{LeastFrequent, OldestFirst, Overdue, SuggestionStrategy, WeightedRandom};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -7,6 +9,8 @@ use std::fmt;
 pub(crate) struct MrotConfig {
     pub(crate) what: What,
     pub(crate) show: Show,
+    #[serde(default)]
+    pub(crate) confirm: Confirm,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,6 +18,12 @@ pub(crate) struct What {
     pub(crate) number: u64,
     pub(crate) ignore: Ignore,
     pub(crate) ignore_period: Option<String>,
+    #[serde(default)]
+    pub(crate) strategy: Strategy,
+    #[serde(default)]
+    pub(crate) one_per_tag: bool,
+    #[serde(default)]
+    pub(crate) look_back: Option<String>,
 }
 
 impl Default for What {
@@ -22,6 +32,44 @@ impl Default for What {
             number: 3,
             ignore: Ignore::default(),
             ignore_period: Some(String::from("from tomorrow through 11 days after tomorrow")),
+            strategy: Strategy::default(),
+            one_per_tag: false,
+            look_back: None,
+        }
+    }
+}
+
+/// How `mrot what` ranks the meals to suggest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Strategy {
+    /// Meals not consumed for the longest time first
+    #[default]
+    OldestFirst,
+    /// Meals with the fewest records first
+    LeastFrequent,
+    /// Random meals, preferring the ones not consumed for a long time
+    WeightedRandom,
+    /// Meals most overdue in their own rhythm first
+    Overdue,
+}
+
+impl Strategy {
+    pub(crate) fn suggestion_strategy(&self) -> Box<dyn SuggestionStrategy> {
+        match self {
+            Strategy::OldestFirst => Box::new(OldestFirst),
+            Strategy::LeastFrequent => Box::new(LeastFrequent),
+            Strategy::WeightedRandom => Box::new(WeightedRandom::new(None)),
+            Strategy::Overdue => Box::new(Overdue),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => fmt::Display::fmt(value.get_name(), f),
+            None => Ok(()),
         }
     }
 }
@@ -39,6 +87,17 @@ impl Default for Show {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Confirm {
+    pub(crate) threshold: usize,
+}
+
+impl Default for Confirm {
+    fn default() -> Self {
+        Self { threshold: 10 }
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub(crate) struct Ignore(Vec<String>);
 
@@ -66,9 +125,9 @@ impl fmt::Display for Ignore {
         let separator = ", ";
         self.0
             .iter()
-            .take(self.0.len().checked_sub(1).unwrap_or(0))
+            .take(self.0.len().saturating_sub(1))
             .for_each(|i| {
-                out.push_str(&i);
+                out.push_str(i);
                 out.push_str(separator);
             });
         out.push_str(self.0.iter().last().unwrap_or(&"".to_string()));
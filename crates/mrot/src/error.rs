@@ -1,7 +1,10 @@
 //! Error types for the mrot app
 
+use chrono::NaiveDateTime;
 use confy::ConfyError;
-use libmrot::Error as LibMrotError;
+use csv::Error as CsvError;
+use libmrot::{Error as LibMrotError, Timezone};
+use serde_json::Error as JsonError;
 use std::{convert::From, ffi::OsString, fmt, io::Error as IoError};
 
 #[derive(Debug)]
@@ -16,6 +19,18 @@ pub(crate) enum Error {
     InvalidUnicode(OsString),
     /// No suitable path for project directory could be found, see [directories::ProjectDirs]
     NoDirectory(String),
+    /// Wraps [csv::Error]
+    Csv(CsvError),
+    /// Wraps [serde_json::Error]
+    Json(JsonError),
+    /// The output format cannot be used by the command
+    UnsupportedFormat(String),
+    /// The user did not confirm the change
+    Aborted,
+    /// The storage does not know the meal, but meals of similar names
+    UnknownMeal(String, Vec<String>),
+    /// The date and time of the `--now` option is skipped or repeated in the storage's timezone
+    InvalidNow(NaiveDateTime, Timezone),
 }
 
 impl fmt::Display for Error {
@@ -34,6 +49,27 @@ impl fmt::Display for Error {
             Error::NoDirectory(group) => {
                 fmt::Display::fmt(&format!("cannot find directory for {}", group), f)
             }
+            Error::Csv(csv_error) => fmt::Display::fmt(csv_error, f),
+            Error::Json(json_error) => fmt::Display::fmt(json_error, f),
+            Error::UnsupportedFormat(format) => fmt::Display::fmt(
+                &format!("the format {} is not supported by this command", format),
+                f,
+            ),
+            Error::Aborted => fmt::Display::fmt("aborted, nothing was changed", f),
+            Error::UnknownMeal(meal, similar_meals) => match similar_meals.is_empty() {
+                true => fmt::Display::fmt(&format!("unknown meal '{}'", meal), f),
+                false => fmt::Display::fmt(
+                    &format!("unknown meal '{}', {}", meal, did_you_mean(similar_meals)),
+                    f,
+                ),
+            },
+            Error::InvalidNow(datetime, timezone) => fmt::Display::fmt(
+                &format!(
+                    "{} does not exist or is ambiguous in the timezone {}",
+                    datetime, timezone
+                ),
+                f,
+            ),
         }
     }
 }
@@ -46,6 +82,12 @@ impl std::error::Error for Error {
             Error::LibMrot(ref libmrot_error) => Some(libmrot_error),
             Error::InvalidUnicode(_) => None,
             Error::NoDirectory(_) => None,
+            Error::Csv(ref csv_error) => Some(csv_error),
+            Error::Json(ref json_error) => Some(json_error),
+            Error::UnsupportedFormat(_) => None,
+            Error::Aborted => None,
+            Error::UnknownMeal(_, _) => None,
+            Error::InvalidNow(_, _) => None,
         }
     }
 }
@@ -73,3 +115,48 @@ impl From<OsString> for Error {
         Error::InvalidUnicode(value)
     }
 }
+
+impl From<CsvError> for Error {
+    fn from(value: CsvError) -> Self {
+        Error::Csv(value)
+    }
+}
+
+impl From<JsonError> for Error {
+    fn from(value: JsonError) -> Self {
+        Error::Json(value)
+    }
+}
+
+/// Asks whether one of the meals was meant, e.g. `did you mean 'pita' or 'pizza'?`
+pub(crate) fn did_you_mean(similar_meals: &[String]) -> String {
+    let quoted: Vec<String> = similar_meals
+        .iter()
+        .map(|meal| format!("'{}'", meal))
+        .collect();
+    let alternatives = match quoted.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, others)) => format!("{} or {}", others.join(", "), last),
+        None => String::new(),
+    };
+    format!("did you mean {}?", alternatives)
+}
+
+#[test]
+fn did_you_mean_lists_the_meals() {
+    let meals = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(did_you_mean(&meals(&["pizza"])), "did you mean 'pizza'?");
+    assert_eq!(
+        did_you_mean(&meals(&["pita", "pizza"])),
+        "did you mean 'pita' or 'pizza'?"
+    );
+    assert_eq!(
+        did_you_mean(&meals(&["pasta", "pita", "pizza"])),
+        "did you mean 'pasta', 'pita' or 'pizza'?"
+    );
+}
@@ -66,12 +66,18 @@
 //!
 //! See the full documentation in the [repository](https://github.com/fleetingbytes/mrot/)'s readme.
 
+mod calendar;
 pub(crate) mod cli;
+mod completion;
 mod config;
 mod error;
+mod output;
 mod run;
+mod tui;
 
 pub(crate) use crate::error::Error;
+use clap::CommandFactory;
+use clap_complete::CompleteEnv;
 use directories::ProjectDirs;
 use tracing::error;
 use tracing_appender::non_blocking;
@@ -80,6 +86,7 @@ use tracing_subscriber::{filter::EnvFilter, fmt, fmt::format::FmtSpan, prelude::
 pub(crate) const LOG_FILE: &str = "trace.log";
 pub(crate) const PKG_NAME: &str = env!("CARGO_PKG_NAME");
 const LOG_LEVEL_ENV_VAR: &str = concat!(env!("PKG_NAME_UPPERCASE"), "_LOG_LEVEL");
+pub(crate) const NOW_ENV_VAR: &str = concat!(env!("PKG_NAME_UPPERCASE"), "_NOW");
 
 /// Type alias for results with mrot's [Error].
 pub(crate) type Result<T> = std::result::Result<T, Error>;
@@ -110,6 +117,9 @@ fn init_tracing() -> Result<Vec<impl Drop>> {
 }
 
 fn main() -> Result<()> {
+    CompleteEnv::with_factory(cli::Cli::command)
+        .shells(completion::SHELLS)
+        .complete();
     let _guards = init_tracing()?;
     match run::run() {
         Ok(_) => Ok(()),
@@ -0,0 +1,542 @@
+//! Output formats of the listing commands
+
+use crate::{error::Error, Result};
+use chrono::{NaiveDate, Weekday};
+use clap::ValueEnum;
+use libmrot::{JournalEntry, MealRecord, MealStatistics, Statistics, Status, Timezone};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// How the listing commands print their results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// JSON array
+    Json,
+    /// One JSON object per line
+    Jsonl,
+    /// Comma-separated values with a header
+    Csv,
+    /// Tab-separated values with a header
+    Tsv,
+    /// Aligned columns with a header
+    Table,
+    /// iCalendar with an all-day event per record (only export)
+    Ics,
+}
+
+/// The formats in which the records are exported and imported.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub(crate) enum ExchangeFormat {
+    /// Comma-separated values with the columns date, meal and status
+    Csv,
+    /// JSON array of objects with the fields date, meal and status
+    Json,
+    /// iCalendar with an all-day event per record
+    Ics,
+}
+
+impl From<ExchangeFormat> for libmrot::ExchangeFormat {
+    fn from(format: ExchangeFormat) -> Self {
+        match format {
+            ExchangeFormat::Csv => libmrot::ExchangeFormat::Csv,
+            ExchangeFormat::Json => libmrot::ExchangeFormat::Json,
+            ExchangeFormat::Ics => libmrot::ExchangeFormat::Ics,
+        }
+    }
+}
+
+/// A row of output in the structured formats.
+pub(crate) trait Row: Serialize {
+    /// Names of the columns, in the order in which the row serializes its fields.
+    const HEADERS: &'static [&'static str];
+}
+
+impl Row for MealRecord {
+    const HEADERS: &'static [&'static str] = &["meal", "date", "timestamp", "status"];
+}
+
+/// Formats the record for the text format, with its status unless it was eaten.
+pub(crate) fn record_with_status(record: &MealRecord) -> String {
+    match record.status() {
+        Status::Eaten => record.to_string(),
+        status => format!("{}, {}", record, status),
+    }
+}
+
+/// A date on which a meal was recorded.
+#[derive(Serialize)]
+pub(crate) struct MealDate<'a> {
+    pub(crate) meal: &'a str,
+    pub(crate) date: NaiveDate,
+}
+
+impl Row for MealDate<'_> {
+    const HEADERS: &'static [&'static str] = &["meal", "date"];
+}
+
+/// An operation in the journal, as shown by `mrot history`.
+#[derive(Serialize)]
+pub(crate) struct HistoryEntry {
+    id: i64,
+    /// When the operation was performed, in the timezone of the storage
+    performed: String,
+    operation: &'static str,
+    records: usize,
+    undone: bool,
+    description: String,
+}
+
+impl HistoryEntry {
+    pub(crate) fn new(entry: &JournalEntry, timezone: &Timezone) -> Self {
+        HistoryEntry {
+            id: entry.id(),
+            performed: timezone
+                .naive_local(entry.performed())
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            operation: entry.operation().kind(),
+            records: entry.operation().records().len(),
+            undone: entry.is_undone(),
+            description: entry.operation().to_string(),
+        }
+    }
+}
+
+impl Row for HistoryEntry {
+    const HEADERS: &'static [&'static str] = &[
+        "id",
+        "performed",
+        "operation",
+        "records",
+        "undone",
+        "description",
+    ];
+}
+
+/// A meal suggested by the overdue strategy, with the date on which it was due again.
+#[derive(Serialize)]
+pub(crate) struct OverdueMeal {
+    meal: String,
+    date: NaiveDate,
+    expected: Option<NaiveDate>,
+}
+
+impl From<&MealStatistics> for OverdueMeal {
+    fn from(statistics: &MealStatistics) -> Self {
+        OverdueMeal {
+            meal: statistics.meal(),
+            date: statistics.last_date(),
+            expected: statistics.expected_next(),
+        }
+    }
+}
+
+impl Row for OverdueMeal {
+    const HEADERS: &'static [&'static str] = &["meal", "date", "expected"];
+}
+
+impl std::fmt::Display for OverdueMeal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.meal, self.date)?;
+        if let Some(expected) = self.expected {
+            write!(f, ", expected {}", expected)?;
+        }
+        Ok(())
+    }
+}
+
+/// The cooldown of a meal, as listed by `mrot cooldown list`.
+#[derive(Serialize)]
+pub(crate) struct MealCooldown {
+    pub(crate) meal: String,
+    pub(crate) days: u32,
+}
+
+impl Row for MealCooldown {
+    const HEADERS: &'static [&'static str] = &["meal", "days"];
+}
+
+impl std::fmt::Display for MealCooldown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} days", self.meal, self.days)
+    }
+}
+
+/// The tags of a meal, as listed by `mrot tag list`.
+#[derive(Serialize)]
+pub(crate) struct MealTags {
+    pub(crate) meal: String,
+    /// The tags, separated by commas
+    pub(crate) tags: String,
+}
+
+impl Row for MealTags {
+    const HEADERS: &'static [&'static str] = &["meal", "tags"];
+}
+
+impl std::fmt::Display for MealTags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.meal, self.tags)
+    }
+}
+
+/// The aliases of a meal, as listed by `mrot alias list`.
+#[derive(Serialize)]
+pub(crate) struct MealAliases {
+    pub(crate) meal: String,
+    /// The aliases, separated by commas
+    pub(crate) aliases: String,
+}
+
+impl Row for MealAliases {
+    const HEADERS: &'static [&'static str] = &["meal", "aliases"];
+}
+
+impl std::fmt::Display for MealAliases {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.meal, self.aliases)
+    }
+}
+
+/// The statistics listed by `mrot stats`.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Statistic {
+    Records,
+    DistinctMeals,
+    MostFrequent,
+    EatenOnce,
+    AverageInterval,
+    MedianInterval,
+    LongestStreak,
+    BusiestWeekday,
+}
+
+impl std::fmt::Display for Statistic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Statistic::Records => "records",
+            Statistic::DistinctMeals => "distinct meals",
+            Statistic::MostFrequent => "most frequent",
+            Statistic::EatenOnce => "eaten once",
+            Statistic::AverageInterval => "average interval",
+            Statistic::MedianInterval => "median interval",
+            Statistic::LongestStreak => "longest streak",
+            Statistic::BusiestWeekday => "busiest weekday",
+        };
+        std::fmt::Display::fmt(label, f)
+    }
+}
+
+/// The value of a statistic: a number of records or meals, or a number of days.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum StatisticValue {
+    Count(usize),
+    Days(f64),
+}
+
+/// A statistic of the eaten meals, as listed by `mrot stats`.
+#[derive(Serialize)]
+pub(crate) struct StatisticRow {
+    statistic: Statistic,
+    /// The meal or the weekday which the statistic is about
+    subject: Option<String>,
+    value: StatisticValue,
+    from: Option<NaiveDate>,
+    through: Option<NaiveDate>,
+}
+
+impl StatisticRow {
+    fn new(statistic: Statistic, subject: Option<String>, value: StatisticValue) -> Self {
+        StatisticRow {
+            statistic,
+            subject,
+            value,
+            from: None,
+            through: None,
+        }
+    }
+
+    /// Lists the statistics, with the *number* most frequent meals.
+    pub(crate) fn all(statistics: &Statistics, number: usize) -> Vec<Self> {
+        let mut rows = vec![
+            Self::new(
+                Statistic::Records,
+                None,
+                StatisticValue::Count(statistics.records()),
+            ),
+            Self::new(
+                Statistic::DistinctMeals,
+                None,
+                StatisticValue::Count(statistics.distinct_meals()),
+            ),
+        ];
+        rows.extend(statistics.most_frequent(number).iter().map(|meal| {
+            Self::new(
+                Statistic::MostFrequent,
+                Some(meal.meal()),
+                StatisticValue::Count(meal.count()),
+            )
+        }));
+        let mut eaten_once = statistics.eaten_once();
+        eaten_once.sort_by_key(|meal| meal.last_date());
+        rows.extend(eaten_once.into_iter().map(|meal| StatisticRow {
+            from: Some(meal.last_date()),
+            through: Some(meal.last_date()),
+            ..Self::new(
+                Statistic::EatenOnce,
+                Some(meal.meal()),
+                StatisticValue::Count(meal.count()),
+            )
+        }));
+        for (statistic, interval) in [
+            (
+                Statistic::AverageInterval,
+                MealStatistics::average_interval as fn(&MealStatistics) -> Option<f64>,
+            ),
+            (Statistic::MedianInterval, MealStatistics::median_interval),
+        ] {
+            let mut meals: Vec<&MealStatistics> = statistics.meals().iter().collect();
+            meals.sort_by_key(|meal| meal.meal());
+            rows.extend(meals.into_iter().filter_map(|meal| {
+                interval(meal)
+                    .map(|days| Self::new(statistic, Some(meal.meal()), StatisticValue::Days(days)))
+            }));
+        }
+        if let Some(streak) = statistics.longest_streak() {
+            rows.push(StatisticRow {
+                from: Some(streak.first_date()),
+                through: Some(streak.last_date()),
+                ..Self::new(
+                    Statistic::LongestStreak,
+                    None,
+                    StatisticValue::Count(streak.meals()),
+                )
+            });
+        }
+        if let Some((weekday, count)) = statistics.busiest_weekday() {
+            rows.push(Self::new(
+                Statistic::BusiestWeekday,
+                Some(weekday_name(weekday).to_string()),
+                StatisticValue::Count(count),
+            ));
+        }
+        rows
+    }
+}
+
+impl Row for StatisticRow {
+    const HEADERS: &'static [&'static str] = &["statistic", "subject", "value", "from", "through"];
+}
+
+impl std::fmt::Display for StatisticRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.statistic)?;
+        match (&self.statistic, &self.subject, &self.value) {
+            (Statistic::MostFrequent, Some(meal), StatisticValue::Count(1)) => {
+                write!(f, "{} (once)", meal)
+            }
+            (Statistic::MostFrequent, Some(meal), StatisticValue::Count(count)) => {
+                write!(f, "{} ({} times)", meal, count)
+            }
+            (Statistic::EatenOnce, Some(meal), _) => match self.from {
+                Some(date) => write!(f, "{} ({})", meal, date),
+                None => write!(f, "{}", meal),
+            },
+            (_, Some(meal), StatisticValue::Days(days)) => {
+                write!(f, "{} ({:.1} days)", meal, days)
+            }
+            (Statistic::LongestStreak, _, StatisticValue::Count(meals)) => {
+                write!(f, "{} {}", meals, meals_noun(*meals))?;
+                if let (Some(from), Some(through)) = (self.from, self.through) {
+                    write!(f, " from {} through {}", from, through)?;
+                }
+                Ok(())
+            }
+            (Statistic::BusiestWeekday, Some(weekday), StatisticValue::Count(count)) => {
+                write!(f, "{} ({} {})", weekday, count, meals_noun(*count))
+            }
+            (_, _, StatisticValue::Count(count)) => write!(f, "{}", count),
+            (_, _, StatisticValue::Days(days)) => write!(f, "{:.1} days", days),
+        }
+    }
+}
+
+/// Returns "meal" or "meals" to follow the *count*.
+fn meals_noun(count: usize) -> &'static str {
+    match count {
+        1 => "meal",
+        _ => "meals",
+    }
+}
+
+/// Returns the full English name of the weekday.
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+impl std::fmt::Display for HistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}  {}", self.performed, self.description)?;
+        if self.undone {
+            write!(f, " (undone)")?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputFormat {
+    /// Prints the rows to stdout. In the text format, each row is printed as its
+    /// [`Display`](std::fmt::Display) representation.
+    pub(crate) fn print<T: Row + std::fmt::Display>(&self, rows: &[T]) -> Result<()> {
+        self.print_with(rows, |row| row.to_string())
+    }
+
+    /// Prints the rows to stdout. In the text format, each row is printed as the string returned
+    /// by `text`.
+    pub(crate) fn print_with<T: Row>(&self, rows: &[T], text: impl Fn(&T) -> String) -> Result<()> {
+        let mut stdout = io::stdout().lock();
+        match self {
+            OutputFormat::Text => {
+                for row in rows {
+                    writeln!(stdout, "{}", text(row))?;
+                }
+            }
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut stdout, rows)?;
+                writeln!(stdout)?;
+            }
+            OutputFormat::Jsonl => {
+                for row in rows {
+                    serde_json::to_writer(&mut stdout, row)?;
+                    writeln!(stdout)?;
+                }
+            }
+            OutputFormat::Csv => write_delimited(&mut stdout, rows, b',')?,
+            OutputFormat::Tsv => write_delimited(&mut stdout, rows, b'\t')?,
+            OutputFormat::Table => write_table(&mut stdout, rows)?,
+            OutputFormat::Ics => return Err(Error::UnsupportedFormat(self.to_string())),
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => std::fmt::Display::fmt(value.get_name(), f),
+            None => Ok(()),
+        }
+    }
+}
+
+fn write_delimited<T: Row>(writer: impl Write, rows: &[T], delimiter: u8) -> Result<()> {
+    let mut csv_writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .from_writer(writer);
+    csv_writer.write_record(T::HEADERS)?;
+    for row in rows {
+        csv_writer.serialize(row)?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+fn write_table<T: Row>(mut writer: impl Write, rows: &[T]) -> Result<()> {
+    let mut lines: Vec<Vec<String>> = vec![T::HEADERS.iter().map(|h| h.to_string()).collect()];
+    for row in rows {
+        lines.push(cells(row)?);
+    }
+    let mut widths = vec![0; T::HEADERS.len()];
+    for line in lines.iter() {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    lines.insert(1, widths.iter().map(|&width| "-".repeat(width)).collect());
+    for line in lines {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(writer, "{}", cells.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+/// Returns the fields of the row as strings, in the order of its headers.
+fn cells<T: Row>(row: &T) -> Result<Vec<String>> {
+    let value = serde_json::to_value(row)?;
+    let cells = T::HEADERS
+        .iter()
+        .map(|&header| match &value[header] {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        })
+        .collect();
+    Ok(cells)
+}
+
+#[test]
+fn write_delimited_and_table() {
+    let rows: Vec<MealRecord> = vec![
+        "1741824000, rinderbraten".parse().unwrap(),
+        "1741910400, flammkuchen".parse().unwrap(),
+    ];
+    let mut csv = Vec::new();
+    write_delimited(&mut csv, &rows, b',').unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "meal,date,timestamp,status\nrinderbraten,2025-03-13,1741824000,eaten\nflammkuchen,2025-03-14,1741910400,eaten\n"
+    );
+    let mut table = Vec::new();
+    write_table(&mut table, &rows).unwrap();
+    assert_eq!(
+        String::from_utf8(table).unwrap(),
+        "meal          date        timestamp   status\n\
+         ------------  ----------  ----------  ------\n\
+         rinderbraten  2025-03-13  1741824000  eaten\n\
+         flammkuchen   2025-03-14  1741910400  eaten\n"
+    );
+}
+
+#[test]
+fn statistic_rows_as_text() {
+    let streak = StatisticRow {
+        from: NaiveDate::from_ymd_opt(2025, 3, 3),
+        through: NaiveDate::from_ymd_opt(2025, 3, 11),
+        ..StatisticRow::new(Statistic::LongestStreak, None, StatisticValue::Count(5))
+    };
+    assert_eq!(
+        streak.to_string(),
+        "longest streak: 5 meals from 2025-03-03 through 2025-03-11"
+    );
+    let interval = StatisticRow::new(
+        Statistic::AverageInterval,
+        Some(String::from("pizza")),
+        StatisticValue::Days(7.5),
+    );
+    assert_eq!(interval.to_string(), "average interval: pizza (7.5 days)");
+    let weekday = StatisticRow::new(
+        Statistic::BusiestWeekday,
+        Some(weekday_name(Weekday::Sun).to_string()),
+        StatisticValue::Count(1),
+    );
+    assert_eq!(weekday.to_string(), "busiest weekday: Sunday (1 meal)");
+}
@@ -1,14 +1,32 @@
-use crate::{cli::*, config::MrotConfig, Error, Result, LOG_FILE, PKG_NAME};
+use crate::{
+    calendar::{use_colors, Calendar},
+    cli::*,
+    config::{MrotConfig, Strategy},
+    error::did_you_mean,
+    output::{
+        self, record_with_status, HistoryEntry, MealAliases, MealCooldown, MealDate, MealTags,
+        OutputFormat, OverdueMeal, StatisticRow,
+    },
+    tui, Error, Result, LOG_FILE, PKG_NAME,
+};
 use clap::{Command as ClapCommand, CommandFactory, Parser};
 use clap_complete::{generate as generate_completions, shells, Generator};
 use clap_complete_nushell::Nushell;
 use directories::ProjectDirs;
-use libmrot::{convert_to_timestamps, parse_date as mrot_parse, Period, Storage};
-use std::io;
+use libmrot::{
+    convert_to_timestamps_in, parse_date_at as mrot_parse, parse_duration, Clock, Diversity,
+    ExchangeFormat, FixedClock, MealRecord, MealStore, Period, SqliteStore, Storage, SystemClock,
+    TagFilter, Timezone,
+};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
 use tracing::{debug, instrument};
 
-const APP_NAME: &str = PKG_NAME;
-const CONFIG_FILE_NAME: &str = "config";
+pub(crate) const APP_NAME: &str = PKG_NAME;
+pub(crate) const CONFIG_FILE_NAME: &str = "config";
 const STORAGE_FILE: &str = "database.sql";
 
 /// Parses the CLI commands and makes the required API calls to execute them.
@@ -22,20 +40,24 @@ pub fn run() -> Result<()> {
                 Some(vec_d) => vec_d,
                 None => &vec![String::from("today")],
             };
-            let storage = open_storage()?;
-            storage.add_meal_on_dates(&add.meal, &dates)?;
+            let storage = open_storage(&cli)?;
+            warn_of_similar_meals(&storage, &add.meal, io::stderr())?;
+            storage.add_meal_on_dates(&add.meal, dates)?;
         }
 
         Command::ParseDate(parse_date) => {
             let date = &parse_date.date;
+            let timezone = storage_timezone()?;
+            let now = clock(&cli, &timezone)?.local_now(&timezone);
             match &parse_date.output_timestamp {
                 false => {
-                    let mrot_dates = mrot_parse(&date)?;
+                    let mrot_dates = mrot_parse(date, now)?;
                     println!("{:?}", mrot_dates);
                 }
                 true => {
                     let date_vec = vec![String::from(date)];
-                    let converted_dates: Vec<i64> = convert_to_timestamps(&date_vec)?;
+                    let converted_dates: Vec<i64> =
+                        convert_to_timestamps_in(&date_vec, now, &timezone)?;
                     println!("{:?}", converted_dates);
                 }
             };
@@ -48,111 +70,392 @@ pub fn run() -> Result<()> {
             debug!("configured number is {}", cfg.what.number);
             let number = what.number.unwrap_or(cfg.what.number);
             debug!("resulting number is {}", number);
-            if let Some(ref ignore) = what.ignore {
-                debug!("what ignore is {:?}", ignore);
+            let ignore_list = ignore_list(&cfg, &what.ignore, what.no_ignore);
+            let storage = open_storage(&cli)?;
+            let option_ignore_period =
+                ignore_period(&storage, &cfg, &what.ignore_period, what.no_ignore_period)?;
+            let strategy = what.strategy.unwrap_or(cfg.what.strategy);
+            debug!("resulting strategy is {}", strategy);
+            let suggestions = storage.suggest_statistics(
+                number,
+                option_ignore_period,
+                ignore_list,
+                &tag_filter(&what.tag, &what.exclude_tag),
+                &diversity(
+                    &storage,
+                    &cfg,
+                    what.one_per_tag,
+                    what.many_per_tag,
+                    &what.look_back,
+                    what.no_look_back,
+                )?,
+                strategy.suggestion_strategy().as_ref(),
+            )?;
+            debug!("{:?}", suggestions);
+            match strategy {
+                Strategy::Overdue => {
+                    let meals: Vec<OverdueMeal> =
+                        suggestions.iter().map(OverdueMeal::from).collect();
+                    cli.format.print(&meals)?;
+                }
+                _ => {
+                    let meals: Vec<MealRecord> = suggestions
+                        .iter()
+                        .map(|suggestion| suggestion.last_record().clone())
+                        .collect();
+                    cli.format.print(&meals)?;
+                }
             }
-            debug!("what no_ignore is {}", what.no_ignore);
-            debug!("configured ignore list is {:?}", cfg.what.ignore);
-            let ignore_list: Vec<String> = if what.no_ignore {
-                Vec::new()
-            } else {
-                match what.ignore {
-                    None => cfg.what.ignore.to_vec_string(),
-                    Some(ref vec) => vec.clone(),
+        }
+
+        Command::Random(random) => {
+            let storage = open_storage(&cli)?;
+            let tag_filter = tag_filter(&random.tag, &random.exclude_tag);
+            let meals: Vec<_> = match random.weighted {
+                false => storage.random(&tag_filter)?.into_iter().collect(),
+                true => {
+                    let ignore_list = ignore_list(&cfg, &random.ignore, random.no_ignore);
+                    let option_ignore_period = ignore_period(
+                        &storage,
+                        &cfg,
+                        &random.ignore_period,
+                        random.no_ignore_period,
+                    )?;
+                    storage.weighted_random(
+                        random.number.unwrap_or(1),
+                        option_ignore_period,
+                        ignore_list,
+                        &tag_filter,
+                        random.seed,
+                    )?
                 }
             };
-            debug!("resulting ignore list is {:?}", ignore_list);
-            debug!("what no_ignore_period is {}", what.no_ignore_period);
-            debug!("what ignore_period is {:?}", what.ignore_period);
-            debug!("configured ignore_period is {:?}", cfg.what.ignore_period);
-            let option_ignore_period: Option<Period> = match what.no_ignore_period {
-                // user explicitly used --no-look-ahead, overriding the Option<String> from the
-                // config with None.
-                // This will result in the None variant of Option<Period>, i.e. no ignore period.
-                true => None,
-                // there may be a look-ahead
-                false => match what.ignore_period {
-                    // the cli option --look-ahead was not explicitly used, so we use what is in
-                    // the config.
-                    // The cfg.what.look_ahead value is an Option<String>.
-                    None => match cfg.what.ignore_period {
-                        // If the config contains the None variant of Option<String>,
-                        // the result will be the None variant of Option<Period>, i. e. no period.
-                        None => None,
-                        // If the config contains a Some variant of Option<String>,
-                        // the result will be the Some variant of Option<Period>, i. e. some period.
-                        Some(ref period) => Some(Period::new(period)?),
-                    },
-                    // the cli option --look-ahead was explicitly used. The user wants to override
-                    // the Option<String> value from the config.
-                    // Here he only has the possibility to override it with a Some variant.
-                    // If he wished to override the config value with a None variant,
-                    // he should have done it by using the --no-look-ahead cli option
-                    Some(ref period) => Some(Period::new(period)?),
-                },
-            };
-            debug!("resulting ignore_period is {:?}", option_ignore_period);
-            let storage = open_storage()?;
-            let meals = storage.what(number, option_ignore_period, ignore_list)?;
-            debug!("{:?}", meals);
-            meals.into_iter().for_each(|meal| println!("{}", meal));
+            cli.format.print(&meals)?;
         }
 
-        Command::Random(_) => {
-            let storage = open_storage()?;
-            if let Some(meal) = storage.random()? {
-                println!("{}", meal);
+        Command::Plan(plan) => {
+            let ignore_list = ignore_list(&cfg, &plan.ignore, plan.no_ignore);
+            let storage = open_storage(&cli)?;
+            let period = storage.period(&plan.range)?;
+            let option_ignore_period =
+                ignore_period(&storage, &cfg, &plan.ignore_period, plan.no_ignore_period)?;
+            let strategy = plan.strategy.unwrap_or(cfg.what.strategy);
+            debug!("resulting strategy is {}", strategy);
+            let records = storage.plan(
+                period,
+                option_ignore_period,
+                ignore_list,
+                &tag_filter(&plan.tag, &plan.exclude_tag),
+                &diversity(
+                    &storage,
+                    &cfg,
+                    plan.one_per_tag,
+                    plan.many_per_tag,
+                    &plan.look_back,
+                    plan.no_look_back,
+                )?,
+                strategy.suggestion_strategy().as_ref(),
+            )?;
+            cli.format.print(&records)?;
+            if !plan.dry_run && !records.is_empty() {
+                if !plan.yes {
+                    confirm(
+                        &format!("Record {} planned meals?", records.len()),
+                        io::stdin().lock(),
+                        io::stderr(),
+                    )?;
+                }
+                storage.add_records(&records)?;
             }
         }
 
-        Command::Show(show) => {
-            let storage = open_storage()?;
-            let range = match show.range {
-                Some(ref range_from_cli) => range_from_cli,
-                None => &cfg.show.range,
+        Command::Confirm(confirm) => {
+            let storage = open_storage(&cli)?;
+            let option_period = match confirm.range {
+                Some(ref range) => Some(storage.period(range)?),
+                None => None,
             };
-            let meals = storage.show(range)?;
-            meals.into_iter().for_each(|meal| println!("{}", meal));
+            let confirmed = storage.confirm(option_period, confirm.meal.clone())?;
+            cli.format.print(&confirmed)?;
+        }
+
+        Command::Skip(skip) => {
+            let storage = open_storage(&cli)?;
+            let option_period = match skip.range {
+                Some(ref range) => Some(storage.period(range)?),
+                None => None,
+            };
+            let skipped = storage.skip(option_period, skip.meal.clone())?;
+            cli.format.print(&skipped)?;
+        }
+
+        Command::Pending(_) => {
+            let storage = open_storage(&cli)?;
+            let pending = storage.pending()?;
+            cli.format.print(&pending)?;
         }
 
+        Command::Show(show) => show_records(
+            &open_storage(&cli)?,
+            &cfg,
+            &show.range,
+            &tag_filter(&show.tag, &show.exclude_tag),
+            show.calendar,
+            cli.format,
+        )?,
+
+        Command::Cal(cal) => show_records(
+            &open_storage(&cli)?,
+            &cfg,
+            &cal.range,
+            &tag_filter(&cal.tag, &cal.exclude_tag),
+            true,
+            cli.format,
+        )?,
+
         Command::When(when) => {
-            let storage = open_storage()?;
-            let dates = storage.when(&when.meal)?;
-            dates
+            let storage = open_storage(&cli)?;
+            require_known_meal(&storage, &when.meal)?;
+            let dates: Vec<MealDate> = storage
+                .when(&when.meal)?
                 .into_iter()
-                .for_each(|naive_date| println!("{}", naive_date));
+                .map(|date| MealDate {
+                    meal: &when.meal,
+                    date,
+                })
+                .collect();
+            cli.format
+                .print_with(&dates, |meal_date| meal_date.date.to_string())?;
         }
 
-        Command::Unique(_) => {
-            let storage = open_storage()?;
-            let unique_meals = storage.get_last_cooked_unique()?;
-            unique_meals
-                .into_iter()
-                .for_each(|record| println!("{}", record.meal()));
+        Command::Unique(unique) => {
+            let storage = open_storage(&cli)?;
+            let unique_meals =
+                storage.get_last_cooked_unique(&tag_filter(&unique.tag, &unique.exclude_tag))?;
+            cli.format
+                .print_with(&unique_meals, |record| record.meal())?;
         }
 
         Command::Remove(remove) => {
-            let period = Period::new(&remove.range)?;
+            let storage = open_storage(&cli)?;
+            let period = storage.period(&remove.range)?;
+            if let Some(ref meal) = remove.meal {
+                require_known_meal(&storage, meal)?;
+            }
             let option_meal = remove.meal.clone();
-            let storage = open_storage()?;
-            let removed_records = storage.remove(period, option_meal)?;
-            removed_records
-                .into_iter()
-                .for_each(|record| println!("{}", record));
+            let records = storage.preview_remove(period.clone(), option_meal.clone())?;
+            if remove.dry_run {
+                cli.format.print(&records)?;
+            } else {
+                if !remove.yes && records.len() > cfg.confirm.threshold {
+                    confirm(
+                        &format!("Remove {} records?", records.len()),
+                        io::stdin().lock(),
+                        io::stderr(),
+                    )?;
+                }
+                let removed_records = storage.remove(period, option_meal)?;
+                cli.format.print(&removed_records)?;
+            }
         }
 
         Command::Rename(rename) => {
-            let storage = open_storage()?;
+            let storage = open_storage(&cli)?;
             let old_name = &rename.old_name;
             let new_name = &rename.new_name;
+            require_known_meal(&storage, old_name)?;
             let option_period = match rename.period {
-                Some(ref date_string) => Some(Period::new(date_string)?),
+                Some(ref date_string) => Some(storage.period(date_string)?),
                 None => None,
             };
-            let renamed_records = storage.rename(old_name, new_name, option_period)?;
-            renamed_records
-                .into_iter()
-                .for_each(|record| println!("{}", record));
+            let records = storage.preview_rename(old_name, option_period.clone())?;
+            if rename.dry_run {
+                cli.format.print(&records)?;
+            } else {
+                if !rename.yes && records.len() > cfg.confirm.threshold {
+                    confirm(
+                        &format!("Rename {} records?", records.len()),
+                        io::stdin().lock(),
+                        io::stderr(),
+                    )?;
+                }
+                let renamed_records = storage.rename(old_name, new_name, option_period)?;
+                cli.format.print(&renamed_records)?;
+            }
+        }
+
+        Command::Tui(_) => {
+            let storage = open_storage(&cli)?;
+            tui::run(storage, cfg)?;
+        }
+
+        Command::Undo(_) => {
+            let storage = open_storage(&cli)?;
+            match storage.undo()? {
+                Some(entry) => println!("undone: {}", entry.operation()),
+                None => println!("nothing to undo"),
+            }
+        }
+
+        Command::Redo(_) => {
+            let storage = open_storage(&cli)?;
+            match storage.redo()? {
+                Some(entry) => println!("redone: {}", entry.operation()),
+                None => println!("nothing to redo"),
+            }
+        }
+
+        Command::History(history) => {
+            let storage = open_storage(&cli)?;
+            let journal = storage.journal()?;
+            let timezone = storage.timezone();
+            let entries: Vec<HistoryEntry> = journal
+                .iter()
+                .skip(journal.len().saturating_sub(history.number))
+                .map(|entry| HistoryEntry::new(entry, &timezone))
+                .collect();
+            cli.format.print(&entries)?;
+        }
+
+        Command::Stats(stats) => {
+            let storage = open_storage(&cli)?;
+            let option_period = stats
+                .period
+                .as_ref()
+                .map(|period| storage.period(period))
+                .transpose()?;
+            let statistics = storage.statistics(option_period)?;
+            cli.format
+                .print(&StatisticRow::all(&statistics, stats.number))?;
+        }
+
+        Command::Export(export) => {
+            let format = export_format(cli.format, export.output.as_deref())?;
+            let storage = open_storage(&cli)?;
+            let range = match (&export.range, format) {
+                (Some(range_from_cli), _) => Some(range_from_cli),
+                (None, ExchangeFormat::Ics) => Some(&cfg.show.range),
+                (None, _) => None,
+            };
+            let option_period = range.map(|range| storage.period(range)).transpose()?;
+            match export.output {
+                Some(ref path) => {
+                    let mut writer = BufWriter::new(File::create(path)?);
+                    storage.export(format, option_period, &mut writer)?;
+                    writer.flush()?;
+                }
+                None => storage.export(format, option_period, io::stdout().lock())?,
+            }
+        }
+
+        Command::Import(import) => {
+            let format = exchange_format(import.format, Some(&import.file));
+            let storage = open_storage(&cli)?;
+            let report = match import.file.to_str() {
+                Some("-") => storage.import(format, io::stdin().lock(), import.dry_run)?,
+                _ => {
+                    let reader = BufReader::new(File::open(&import.file)?);
+                    storage.import(format, reader, import.dry_run)?
+                }
+            };
+            report
+                .duplicates
+                .iter()
+                .for_each(|record| println!("skipping duplicate {}", record));
+            let verb = match import.dry_run {
+                true => "would import",
+                false => "imported",
+            };
+            println!(
+                "{} {} records, skipped {} duplicates",
+                verb,
+                report.imported.len(),
+                report.duplicates.len()
+            );
+        }
+
+        Command::Cooldown(cooldown) => {
+            let storage = open_storage(&cli)?;
+            match cooldown {
+                CooldownCommand::Set(cooldown_set) => {
+                    let days = parse_duration(&cooldown_set.duration)?;
+                    storage.set_cooldown(&cooldown_set.meal, Some(days))?;
+                }
+                CooldownCommand::Clear(cooldown_clear) => {
+                    storage.set_cooldown(&cooldown_clear.meal, None)?;
+                }
+                CooldownCommand::List(_) => {
+                    let cooldowns: Vec<MealCooldown> = storage
+                        .meals()?
+                        .into_iter()
+                        .filter_map(|meal| {
+                            meal.cooldown().map(|days| MealCooldown {
+                                meal: meal.name(),
+                                days,
+                            })
+                        })
+                        .collect();
+                    cli.format.print(&cooldowns)?;
+                }
+            }
+        }
+
+        Command::Tag(tag) => {
+            let storage = open_storage(&cli)?;
+            match tag {
+                TagCommand::Add(tag_add) => {
+                    storage.add_tags(&tag_add.meal, &tag_add.tags)?;
+                }
+                TagCommand::Remove(tag_remove) => {
+                    storage.remove_tags(&tag_remove.meal, &tag_remove.tags)?;
+                }
+                TagCommand::List(tag_list) => {
+                    let meals = match tag_list.meal {
+                        Some(ref meal) => vec![storage
+                            .meal(meal)?
+                            .ok_or(libmrot::Error::UnknownMeal(meal.to_string()))?],
+                        None => storage.meals()?,
+                    };
+                    let tags: Vec<MealTags> = meals
+                        .into_iter()
+                        .filter(|meal| !meal.tags().is_empty())
+                        .map(|meal| MealTags {
+                            meal: meal.name(),
+                            tags: meal.tags().join(", "),
+                        })
+                        .collect();
+                    cli.format.print(&tags)?;
+                }
+            }
+        }
+
+        Command::Alias(alias) => {
+            let storage = open_storage(&cli)?;
+            match alias {
+                AliasCommand::Add(alias_add) => {
+                    storage.add_aliases(&alias_add.meal, &alias_add.aliases)?;
+                }
+                AliasCommand::Remove(alias_remove) => {
+                    storage.remove_aliases(&alias_remove.aliases)?;
+                }
+                AliasCommand::List(alias_list) => {
+                    let meals = match alias_list.meal {
+                        Some(ref meal) => vec![storage
+                            .meal(meal)?
+                            .ok_or(libmrot::Error::UnknownMeal(meal.to_string()))?],
+                        None => storage.meals()?,
+                    };
+                    let aliases: Vec<MealAliases> = meals
+                        .into_iter()
+                        .filter(|meal| !meal.aliases().is_empty())
+                        .map(|meal| MealAliases {
+                            meal: meal.name(),
+                            aliases: meal.aliases().join(", "),
+                        })
+                        .collect();
+                    cli.format.print(&aliases)?;
+                }
+            }
         }
 
         Command::Config(config) => match config {
@@ -165,14 +468,36 @@ pub fn run() -> Result<()> {
                         ConfigSetWhatCommand::IgnorePeriod(config_set_what_ignore_period) => {
                             verify_ignore_period_value(
                                 config_set_what_ignore_period.ignore_period.clone(),
+                                &cli,
                             )?;
                             cfg.what.ignore_period =
                                 config_set_what_ignore_period.ignore_period.clone();
                         }
+                        ConfigSetWhatCommand::Strategy(config_set_what_strategy) => {
+                            cfg.what.strategy = config_set_what_strategy.strategy;
+                        }
+                        ConfigSetWhatCommand::OnePerTag(config_set_what_one_per_tag) => {
+                            cfg.what.one_per_tag = config_set_what_one_per_tag.one_per_tag;
+                        }
+                        ConfigSetWhatCommand::LookBack(config_set_what_look_back) => {
+                            verify_ignore_period_value(
+                                config_set_what_look_back.look_back.clone(),
+                                &cli,
+                            )?;
+                            cfg.what.look_back = config_set_what_look_back.look_back.clone();
+                        }
                     },
                     ConfigSetCommand::Show(config_set_show) => {
                         cfg.show.range = config_set_show.range.clone();
                     }
+                    ConfigSetCommand::Timezone(config_set_timezone) => {
+                        let timezone: Timezone = config_set_timezone.timezone.parse()?;
+                        let storage = open_storage(&cli)?;
+                        storage.set_timezone(timezone)?;
+                    }
+                    ConfigSetCommand::Confirm(config_set_confirm) => {
+                        cfg.confirm.threshold = config_set_confirm.threshold;
+                    }
                 }
                 confy::store(APP_NAME, CONFIG_FILE_NAME, cfg)?
             }
@@ -185,10 +510,26 @@ pub fn run() -> Result<()> {
                     ConfigGetWhatCommand::IgnorePeriod(_) => {
                         println!("{:?}", cfg.what.ignore_period);
                     }
+                    ConfigGetWhatCommand::Strategy(_) => {
+                        println!("{}", cfg.what.strategy);
+                    }
+                    ConfigGetWhatCommand::OnePerTag(_) => {
+                        println!("{}", cfg.what.one_per_tag);
+                    }
+                    ConfigGetWhatCommand::LookBack(_) => {
+                        println!("{:?}", cfg.what.look_back);
+                    }
                 },
                 ConfigGetCommand::Show(_) => {
                     println!("{:?}", cfg.show.range);
                 }
+                ConfigGetCommand::Timezone(_) => {
+                    let storage = open_storage(&cli)?;
+                    println!("{}", storage.timezone());
+                }
+                ConfigGetCommand::Confirm(_) => {
+                    println!("{}", cfg.confirm.threshold);
+                }
             },
 
             ConfigCommand::Ignore(config_ignore) => match config_ignore {
@@ -254,9 +595,97 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
-fn open_storage() -> Result<Storage> {
+/// Picks the exchange format of `export` from the --format option or, if it was not given, from
+/// the extension of the file.
+fn export_format(format: OutputFormat, path: Option<&Path>) -> Result<ExchangeFormat> {
+    let option_format = match format {
+        OutputFormat::Text => None,
+        OutputFormat::Csv => Some(output::ExchangeFormat::Csv),
+        OutputFormat::Json => Some(output::ExchangeFormat::Json),
+        OutputFormat::Ics => Some(output::ExchangeFormat::Ics),
+        OutputFormat::Jsonl | OutputFormat::Tsv | OutputFormat::Table => {
+            return Err(Error::UnsupportedFormat(format.to_string()));
+        }
+    };
+    Ok(exchange_format(option_format, path))
+}
+
+/// Picks the exchange format given on the command line or, if it was not given, from the
+/// extension of the file.
+fn exchange_format(
+    option_format: Option<output::ExchangeFormat>,
+    path: Option<&Path>,
+) -> ExchangeFormat {
+    match option_format {
+        Some(format) => format.into(),
+        None => match path.and_then(|path| path.extension()) {
+            Some(extension) if extension == "json" => ExchangeFormat::Json,
+            Some(extension) if extension == "ics" => ExchangeFormat::Ics,
+            _ => ExchangeFormat::Csv,
+        },
+    }
+}
+
+/// Prints the records in the *range*, or the configured range of `show`, which pass the
+/// *tag_filter* in the *format*, in a calendar grid if *calendar*.
+fn show_records(
+    storage: &Storage,
+    cfg: &MrotConfig,
+    range: &Option<String>,
+    tag_filter: &TagFilter,
+    calendar: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let range = match range {
+        Some(range_from_cli) => range_from_cli,
+        None => &cfg.show.range,
+    };
+    let meals = storage.show(range, tag_filter)?;
+    if !calendar {
+        return format.print_with(&meals, record_with_status);
+    }
+    if format != OutputFormat::Text {
+        return Err(Error::UnsupportedFormat(format.to_string()));
+    }
+    let period = storage.period(range)?;
+    let calendar = Calendar::new(
+        &meals,
+        period.first_date(),
+        period.last_date(),
+        storage.today(),
+    )
+    .styled(use_colors());
+    println!("{}", calendar);
+    Ok(())
+}
+
+/// Returns the clock which tells the moment of the `--now` option, resolving a date and time in
+/// the *timezone* of the storage, or the system's clock if the option was not given.
+fn clock(cli: &Cli, timezone: &Timezone) -> Result<Box<dyn Clock>> {
+    match cli.now {
+        Some(Now::Exact(now)) => Ok(Box::new(FixedClock::new(now))),
+        Some(Now::WallClock(datetime)) => {
+            let now = timezone
+                .moment_of(&datetime)
+                .ok_or(Error::InvalidNow(datetime, *timezone))?;
+            Ok(Box::new(FixedClock::new(now)))
+        }
+        None => Ok(Box::new(SystemClock)),
+    }
+}
+
+/// Opens the storage with the clock of the `--now` option. The storage is migrated as of that
+/// moment, too.
+fn open_storage(cli: &Cli) -> Result<Storage> {
     let storage_path = get_storage_path()?;
-    Ok(Storage::open(&storage_path)?)
+    let clock = clock(cli, &SqliteStore::timezone_of(&storage_path)?)?;
+    Ok(Storage::open_at(&storage_path, clock.now())?.with_clock(clock))
+}
+
+/// Returns the timezone of the storage if it exists, otherwise the timezone of a new storage.
+/// Does not create or migrate the storage.
+fn storage_timezone() -> Result<Timezone> {
+    Ok(SqliteStore::timezone_of(&get_storage_path()?)?)
 }
 
 fn get_data_file_path(file: &str) -> Result<String> {
@@ -275,7 +704,7 @@ fn get_config_path() -> Result<String> {
     Ok(config_path.into_os_string().into_string()?)
 }
 
-fn get_storage_path() -> Result<String> {
+pub(crate) fn get_storage_path() -> Result<String> {
     get_data_file_path(STORAGE_FILE)
 }
 
@@ -292,9 +721,201 @@ fn print_completions<G: Generator>(generator: G, cmd: &mut ClapCommand) {
     );
 }
 
-fn verify_ignore_period_value(value: Option<String>) -> Result<()> {
+/// Resolves the meals to ignore in suggestions from the CLI options and the config.
+pub(crate) fn ignore_list(
+    cfg: &MrotConfig,
+    ignore: &Option<Vec<String>>,
+    no_ignore: bool,
+) -> Vec<String> {
+    if let Some(ignore) = ignore {
+        debug!("cli ignore is {:?}", ignore);
+    }
+    debug!("cli no_ignore is {}", no_ignore);
+    debug!("configured ignore list is {:?}", cfg.what.ignore);
+    let ignore_list: Vec<String> = if no_ignore {
+        Vec::new()
+    } else {
+        match ignore {
+            None => cfg.what.ignore.to_vec_string(),
+            Some(vec) => vec.clone(),
+        }
+    };
+    debug!("resulting ignore list is {:?}", ignore_list);
+    ignore_list
+}
+
+/// Resolves the diversity rule of suggestions and plans from the CLI options and the config.
+pub(crate) fn diversity<S: MealStore>(
+    storage: &Storage<S>,
+    cfg: &MrotConfig,
+    one_per_tag: bool,
+    many_per_tag: bool,
+    look_back: &Option<String>,
+    no_look_back: bool,
+) -> Result<Diversity> {
+    let one_per_tag = match (one_per_tag, many_per_tag) {
+        (true, _) => true,
+        (false, true) => false,
+        (false, false) => cfg.what.one_per_tag,
+    };
+    let look_back = match (no_look_back, look_back) {
+        (true, _) => None,
+        (false, Some(period)) => Some(period),
+        (false, None) => cfg.what.look_back.as_ref(),
+    };
+    let option_look_back = match look_back {
+        Some(period) => Some(storage.period(period)?),
+        None => None,
+    };
+    let diversity = Diversity::default()
+        .with_one_per_tag(one_per_tag)
+        .with_look_back(option_look_back);
+    debug!("resulting diversity is {:?}", diversity);
+    Ok(diversity)
+}
+
+/// Builds the filter of the meals' tags from the CLI options.
+fn tag_filter(tag: &Option<Vec<String>>, exclude_tag: &Option<Vec<String>>) -> TagFilter {
+    let tag_filter = TagFilter::default()
+        .with_tags(tag.as_deref().unwrap_or_default())
+        .with_excluded_tags(exclude_tag.as_deref().unwrap_or_default());
+    debug!("resulting tag filter is {:?}", tag_filter);
+    tag_filter
+}
+
+/// Resolves the period of planned meals to leave out of suggestions from the CLI options and the
+/// config.
+pub(crate) fn ignore_period<S: MealStore>(
+    storage: &Storage<S>,
+    cfg: &MrotConfig,
+    ignore_period: &Option<String>,
+    no_ignore_period: bool,
+) -> Result<Option<Period>> {
+    debug!("cli no_ignore_period is {}", no_ignore_period);
+    debug!("cli ignore_period is {:?}", ignore_period);
+    debug!("configured ignore_period is {:?}", cfg.what.ignore_period);
+    let option_ignore_period: Option<Period> = match no_ignore_period {
+        // user explicitly used --no-look-ahead, overriding the Option<String> from the
+        // config with None.
+        // This will result in the None variant of Option<Period>, i.e. no ignore period.
+        true => None,
+        // there may be a look-ahead
+        false => match ignore_period {
+            // the cli option --look-ahead was not explicitly used, so we use what is in
+            // the config.
+            // The cfg.what.look_ahead value is an Option<String>.
+            None => match cfg.what.ignore_period {
+                // If the config contains the None variant of Option<String>,
+                // the result will be the None variant of Option<Period>, i. e. no period.
+                None => None,
+                // If the config contains a Some variant of Option<String>,
+                // the result will be the Some variant of Option<Period>, i. e. some period.
+                Some(ref period) => Some(storage.period(period)?),
+            },
+            // the cli option --look-ahead was explicitly used. The user wants to override
+            // the Option<String> value from the config.
+            // Here he only has the possibility to override it with a Some variant.
+            // If he wished to override the config value with a None variant,
+            // he should have done it by using the --no-look-ahead cli option
+            Some(period) => Some(storage.period(period)?),
+        },
+    };
+    debug!("resulting ignore_period is {:?}", option_ignore_period);
+    Ok(option_ignore_period)
+}
+
+/// Returns [`Error::UnknownMeal`] with the meals of similar names if the storage does not know the
+/// meal.
+fn require_known_meal(storage: &Storage, meal: &str) -> Result<()> {
+    match storage.meal(meal)? {
+        Some(_) => Ok(()),
+        None => Err(Error::UnknownMeal(
+            meal.to_string(),
+            storage.similar_meals(meal)?,
+        )),
+    }
+}
+
+/// Warns if the meal is new to the storage, but the storage knows meals of similar names.
+fn warn_of_similar_meals(storage: &Storage, meal: &str, mut output: impl Write) -> Result<()> {
+    if storage.meal(meal)?.is_some() {
+        return Ok(());
+    }
+    let similar_meals = storage.similar_meals(meal)?;
+    if !similar_meals.is_empty() {
+        writeln!(
+            output,
+            "warning: '{}' is a new meal, {}",
+            meal,
+            did_you_mean(&similar_meals)
+        )?;
+    }
+    Ok(())
+}
+
+/// Asks the user the question and fails with [`Error::Aborted`] unless the answer is yes.
+fn confirm(question: &str, mut input: impl BufRead, mut output: impl Write) -> Result<()> {
+    write!(output, "{} [y/N] ", question)?;
+    output.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => Ok(()),
+        _ => Err(Error::Aborted),
+    }
+}
+
+fn verify_ignore_period_value(value: Option<String>, cli: &Cli) -> Result<()> {
     match value {
         None => Ok(()),
-        Some(ref date_expression) => Ok(mrot_parse(date_expression).map(|_| ())?),
+        Some(ref date_expression) => {
+            let timezone = storage_timezone()?;
+            let now = clock(cli, &timezone)?.local_now(&timezone);
+            Ok(mrot_parse(date_expression, now).map(|_| ())?)
+        }
     }
 }
+
+#[test]
+fn confirm_accepts_only_yes() {
+    for (answer, confirmed) in [
+        ("y\n", true),
+        ("Yes\n", true),
+        ("n\n", false),
+        ("\n", false),
+        ("", false),
+    ] {
+        let mut prompt = Vec::new();
+        let result = confirm("Remove 11 records?", answer.as_bytes(), &mut prompt);
+        assert_eq!(result.is_ok(), confirmed, "answer {:?}", answer);
+        assert_eq!(
+            String::from_utf8(prompt).unwrap(),
+            "Remove 11 records? [y/N] "
+        );
+    }
+}
+
+#[test]
+fn clock_tells_the_now_option_in_the_timezone_of_the_storage() {
+    use chrono::{TimeZone, Utc};
+
+    let sydney: Timezone = "Australia/Sydney".parse().unwrap();
+    let now = |option: &str| {
+        let cli = Cli::parse_from(["mrot", "--now", option, "what"]);
+        clock(&cli, &sydney).map(|clock| clock.now())
+    };
+    // midnight in Sydney is 14:00 UTC of the previous day
+    assert_eq!(
+        now("2025-04-15T00:30:00").unwrap(),
+        Utc.with_ymd_and_hms(2025, 4, 14, 14, 30, 0).unwrap()
+    );
+    assert_eq!(
+        now("2025-04-15T00:30:00Z").unwrap(),
+        Utc.with_ymd_and_hms(2025, 4, 15, 0, 30, 0).unwrap()
+    );
+    // the clocks in Sydney skip from 2:00 to 3:00 when daylight saving time begins
+    assert!(matches!(
+        now("2025-10-05T02:30:00"),
+        Err(Error::InvalidNow(_, _))
+    ));
+}
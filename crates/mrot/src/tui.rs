@@ -0,0 +1,587 @@
+//! Interactive terminal UI of `mrot tui`
+
+use crate::{
+    config::MrotConfig,
+    run::{diversity, ignore_list, ignore_period, APP_NAME, CONFIG_FILE_NAME},
+    Result,
+};
+use chrono::{Datelike, Days, NaiveDate};
+use libmrot::{
+    ignored_tag, normalize_name, MealRecord, MealStatistics, MealStore, Period, SqliteStore,
+    Status, Storage, TagFilter,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, Cell, List, ListItem, ListState, Paragraph, Row, Table},
+    DefaultTerminal, Frame,
+};
+
+/// Number of weeks shown in the calendar
+const WEEKS: u64 = 5;
+/// Width of the pane with the suggestions and the ignore list
+const SIDE_PANE_WIDTH: u16 = 32;
+const HELP: &str = "←→↑↓ move  tab switch pane  a add  d remove  r rename  n next meal  \
+                    i (un)ignore  I ignore list on/off  u undo  U redo  t today  q quit";
+
+/// The pane which the up and down keys move in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pane {
+    Calendar,
+    Suggestions,
+}
+
+/// What the keys do.
+#[derive(Debug, PartialEq)]
+enum Mode {
+    Normal,
+    /// Typing the new name of the selected record
+    Rename(String),
+}
+
+/// State of the terminal UI: a calendar of the records with a selected day, the suggestions what
+/// to eat and the ignore list of the config.
+struct App<S = SqliteStore> {
+    storage: Storage<S>,
+    cfg: MrotConfig,
+    /// Whether the ignore list of the config applies to the suggestions
+    ignoring: bool,
+    /// The Monday of the first week shown in the calendar
+    first_monday: NaiveDate,
+    selected_date: NaiveDate,
+    /// Index of the selected record among the records of the selected date
+    selected_record: usize,
+    /// The records of the weeks shown in the calendar
+    records: Vec<MealRecord>,
+    suggestions: Vec<MealStatistics>,
+    selected_suggestion: usize,
+    pane: Pane,
+    mode: Mode,
+    /// Outcome of the latest action, shown instead of the help
+    message: Option<String>,
+    /// Whether the ignore list changed since the config was saved
+    config_changed: bool,
+    quit: bool,
+}
+
+/// Runs the terminal UI until the user quits. Changes of the ignore list are saved in the config.
+pub(crate) fn run(storage: Storage, cfg: MrotConfig) -> Result<()> {
+    let mut app = App::new(storage, cfg)?;
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl<S: MealStore> App<S> {
+    fn new(storage: Storage<S>, cfg: MrotConfig) -> Result<Self> {
+        let today = storage.today();
+        let mut app = App {
+            storage,
+            cfg,
+            ignoring: true,
+            first_monday: monday_of(today) - Days::new(7),
+            selected_date: today,
+            selected_record: 0,
+            records: Vec::new(),
+            suggestions: Vec::new(),
+            selected_suggestion: 0,
+            pane: Pane::Calendar,
+            mode: Mode::Normal,
+            message: None,
+            config_changed: false,
+            quit: false,
+        };
+        app.refresh()?;
+        Ok(app)
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+            if self.config_changed {
+                confy::store(APP_NAME, CONFIG_FILE_NAME, &self.cfg)?;
+                self.config_changed = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reloads the records of the calendar and the suggestions from the storage.
+    fn refresh(&mut self) -> Result<()> {
+        let last_sunday = self.first_monday + Days::new(WEEKS * 7 - 1);
+        let period = self.storage.period_between(self.first_monday, last_sunday);
+        self.records = self.storage.show_period(period, &TagFilter::default())?;
+        let strategy = self.cfg.what.strategy.suggestion_strategy();
+        self.suggestions = self.storage.suggest_statistics(
+            self.cfg.what.number,
+            ignore_period(&self.storage, &self.cfg, &None, false)?,
+            ignore_list(&self.cfg, &None, !self.ignoring),
+            &TagFilter::default(),
+            &diversity(&self.storage, &self.cfg, false, false, &None, false)?,
+            strategy.as_ref(),
+        )?;
+        self.selected_suggestion = self
+            .selected_suggestion
+            .min(self.suggestions.len().saturating_sub(1));
+        self.selected_record = self
+            .selected_record
+            .min(self.records_of_selected_date().len().saturating_sub(1));
+        Ok(())
+    }
+
+    /// Handles the key. Errors of the storage are shown as the message instead of ending the UI.
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.message = None;
+        if let Err(error) = self.try_handle_key(key) {
+            self.message = Some(format!("error: {}", error));
+        }
+    }
+
+    fn try_handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        if let Mode::Rename(ref mut new_name) = self.mode {
+            match key.code {
+                KeyCode::Char(c) => new_name.push(c),
+                KeyCode::Backspace => {
+                    new_name.pop();
+                }
+                KeyCode::Esc => self.mode = Mode::Normal,
+                KeyCode::Enter => {
+                    let new_name = new_name.trim().to_string();
+                    self.mode = Mode::Normal;
+                    self.rename_selected_record(&new_name)?;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+        match (key.code, self.pane) {
+            (KeyCode::Char('q') | KeyCode::Esc, _) => self.quit = true,
+            (KeyCode::Tab | KeyCode::BackTab, Pane::Calendar) => self.pane = Pane::Suggestions,
+            (KeyCode::Tab | KeyCode::BackTab, Pane::Suggestions) => self.pane = Pane::Calendar,
+            (KeyCode::Left | KeyCode::Char('h'), _) => {
+                self.select_date(self.selected_date - Days::new(1))?
+            }
+            (KeyCode::Right | KeyCode::Char('l'), _) => {
+                self.select_date(self.selected_date + Days::new(1))?
+            }
+            (KeyCode::Up | KeyCode::Char('k'), Pane::Calendar) => {
+                self.select_date(self.selected_date - Days::new(7))?
+            }
+            (KeyCode::Down | KeyCode::Char('j'), Pane::Calendar) => {
+                self.select_date(self.selected_date + Days::new(7))?
+            }
+            (KeyCode::Up | KeyCode::Char('k'), Pane::Suggestions) => {
+                self.selected_suggestion = self.selected_suggestion.saturating_sub(1)
+            }
+            (KeyCode::Down | KeyCode::Char('j'), Pane::Suggestions) => {
+                self.selected_suggestion =
+                    (self.selected_suggestion + 1).min(self.suggestions.len().saturating_sub(1))
+            }
+            (KeyCode::PageUp, _) => self.select_date(self.selected_date - Days::new(WEEKS * 7))?,
+            (KeyCode::PageDown, _) => {
+                self.select_date(self.selected_date + Days::new(WEEKS * 7))?
+            }
+            (KeyCode::Char('t'), _) => self.select_date(self.storage.today())?,
+            (KeyCode::Char('n'), _) => {
+                let count = self.records_of_selected_date().len();
+                if count > 0 {
+                    self.selected_record = (self.selected_record + 1) % count;
+                }
+            }
+            (KeyCode::Char('a') | KeyCode::Enter, _) => self.add_selected_suggestion()?,
+            (KeyCode::Char('d') | KeyCode::Delete, _) => self.remove_selected_record()?,
+            (KeyCode::Char('r'), _) => {
+                if let Some(record) = self.selected_record() {
+                    self.mode = Mode::Rename(record.meal());
+                }
+            }
+            (KeyCode::Char('i'), _) => self.toggle_ignored()?,
+            (KeyCode::Char('I'), _) => {
+                self.ignoring = !self.ignoring;
+                self.refresh()?;
+            }
+            (KeyCode::Char('u'), _) => {
+                self.message = Some(match self.storage.undo()? {
+                    Some(entry) => format!("undone: {}", entry.operation()),
+                    None => String::from("nothing to undo"),
+                });
+                self.refresh()?;
+            }
+            (KeyCode::Char('U'), _) => {
+                self.message = Some(match self.storage.redo()? {
+                    Some(entry) => format!("redone: {}", entry.operation()),
+                    None => String::from("nothing to redo"),
+                });
+                self.refresh()?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Selects the date and scrolls the calendar to show it.
+    fn select_date(&mut self, date: NaiveDate) -> Result<()> {
+        self.selected_date = date;
+        self.selected_record = 0;
+        let last_sunday = self.first_monday + Days::new(WEEKS * 7 - 1);
+        if date < self.first_monday {
+            self.first_monday = monday_of(date);
+        } else if date > last_sunday {
+            self.first_monday = monday_of(date) - Days::new((WEEKS - 1) * 7);
+        }
+        self.refresh()
+    }
+
+    fn records_of_selected_date(&self) -> Vec<&MealRecord> {
+        self.records
+            .iter()
+            .filter(|record| record.naive_date() == self.selected_date)
+            .collect()
+    }
+
+    fn selected_record(&self) -> Option<&MealRecord> {
+        self.records_of_selected_date()
+            .get(self.selected_record)
+            .copied()
+    }
+
+    /// Returns the period of the selected date.
+    fn selected_period(&self) -> Period {
+        self.storage
+            .period_between(self.selected_date, self.selected_date)
+    }
+
+    fn add_selected_suggestion(&mut self) -> Result<()> {
+        let Some(meal) = self
+            .suggestions
+            .get(self.selected_suggestion)
+            .map(|suggestion| suggestion.meal())
+        else {
+            return Ok(());
+        };
+        self.storage
+            .add_meal_in_period(&meal, &self.selected_period())?;
+        self.message = Some(format!("added {} on {}", meal, self.selected_date));
+        self.refresh()
+    }
+
+    fn remove_selected_record(&mut self) -> Result<()> {
+        let Some(record) = self.selected_record().cloned() else {
+            return Ok(());
+        };
+        // other records of the meal on the same day are kept
+        self.storage.remove_records(std::slice::from_ref(&record))?;
+        self.message = Some(format!(
+            "removed {} on {}",
+            record.meal(),
+            self.selected_date
+        ));
+        self.refresh()
+    }
+
+    fn rename_selected_record(&mut self, new_name: &str) -> Result<()> {
+        let Some(meal) = self.selected_record().map(|record| record.meal()) else {
+            return Ok(());
+        };
+        if new_name.is_empty() || new_name == meal {
+            return Ok(());
+        }
+        self.storage
+            .rename(&meal, new_name, Some(self.selected_period()))?;
+        self.message = Some(format!("renamed {} to {}", meal, new_name));
+        self.refresh()
+    }
+
+    /// Adds the meal of the focused pane to the ignore list of the config, or removes it from it.
+    fn toggle_ignored(&mut self) -> Result<()> {
+        let option_meal = match self.pane {
+            Pane::Calendar => self.selected_record().map(|record| record.meal()),
+            Pane::Suggestions => self
+                .suggestions
+                .get(self.selected_suggestion)
+                .map(|suggestion| suggestion.meal()),
+        };
+        let Some(meal) = option_meal else {
+            return Ok(());
+        };
+        let entries = self.ignore_entries_of(&meal)?;
+        if !entries.is_empty() {
+            for entry in entries {
+                self.cfg.what.ignore.remove(&entry);
+            }
+            self.message = Some(format!("{} is no longer ignored", meal));
+        } else {
+            self.cfg.what.ignore.add(&meal);
+            self.message = Some(format!("{} is ignored", meal));
+        }
+        self.config_changed = true;
+        self.refresh()
+    }
+
+    /// Returns the entries of the ignore list which name the meal, like the suggestions compare
+    /// them: by their normalized names, with aliases resolved to the meal. Tags are left out.
+    fn ignore_entries_of(&self, meal: &str) -> Result<Vec<String>> {
+        let normalized_meal = normalize_name(meal);
+        let mut entries = Vec::new();
+        for entry in self.cfg.what.ignore.to_vec_string() {
+            if ignored_tag(&entry).is_none()
+                && normalize_name(&self.storage.resolve(&entry)?) == normalized_meal
+            {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main_area, footer_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [calendar_area, side_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(SIDE_PANE_WIDTH)])
+                .areas(main_area);
+        let [suggestions_area, ignored_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Percentage(40)]).areas(side_area);
+        frame.render_widget(self.calendar(), calendar_area);
+        let mut list_state = ListState::default().with_selected(Some(self.selected_suggestion));
+        frame.render_stateful_widget(self.suggestion_list(), suggestions_area, &mut list_state);
+        frame.render_widget(self.ignore_list(), ignored_area);
+        let footer = match (&self.mode, &self.message) {
+            (Mode::Rename(new_name), _) => format!(
+                "rename {} to: {}▏  (enter to rename, esc to cancel)",
+                self.selected_record()
+                    .map(|record| record.meal())
+                    .unwrap_or_default(),
+                new_name
+            ),
+            (Mode::Normal, Some(message)) => message.clone(),
+            (Mode::Normal, None) => HELP.to_string(),
+        };
+        frame.render_widget(Paragraph::new(footer), footer_area);
+    }
+
+    fn pane_block(&self, title: String, pane: Option<Pane>) -> Block<'static> {
+        let block = Block::bordered().title(title);
+        match pane == Some(self.pane) {
+            true => block.border_style(Style::new().add_modifier(Modifier::BOLD)),
+            false => block.border_style(Style::new().add_modifier(Modifier::DIM)),
+        }
+    }
+
+    fn calendar(&self) -> Table<'_> {
+        let today = self.storage.today();
+        let rows = (0..WEEKS).map(|week| {
+            let monday = self.first_monday + Days::new(week * 7);
+            let cells: Vec<Text> = monday
+                .iter_days()
+                .take(7)
+                .map(|date| self.day_cell(date, today))
+                .collect();
+            let height = cells.iter().map(Text::height).max().unwrap_or(1);
+            Row::new(cells.into_iter().map(Cell::from)).height(height as u16)
+        });
+        let header = Row::new(["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let title = format!(" {} ", self.selected_date.format("%B %Y"));
+        Table::new(rows, [Constraint::Ratio(1, 7); 7])
+            .header(header)
+            .block(self.pane_block(title, Some(Pane::Calendar)))
+    }
+
+    /// Returns the cell of the date: the day of the month followed by the meals of the day.
+    /// Planned meals are italic, skipped meals are crossed out. The selected date is reversed.
+    fn day_cell(&self, date: NaiveDate, today: NaiveDate) -> Text<'_> {
+        let day = match date.day() {
+            1 => date.format("%b %-d").to_string(),
+            _ => date.day().to_string(),
+        };
+        let mut day_style = Style::new();
+        if date == today {
+            day_style = day_style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        }
+        let mut lines = vec![Line::styled(day, day_style)];
+        let records = self
+            .records
+            .iter()
+            .filter(|record| record.naive_date() == date);
+        for (index, record) in records.enumerate() {
+            let mut style = match record.status() {
+                Status::Eaten => Style::new(),
+                Status::Planned => Style::new().add_modifier(Modifier::ITALIC),
+                Status::Skipped => Style::new().add_modifier(Modifier::CROSSED_OUT),
+            };
+            if date == self.selected_date && index == self.selected_record {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            lines.push(Line::styled(record.meal(), style));
+        }
+        if lines.len() == 1 {
+            lines.push(Line::styled("·", Style::new().add_modifier(Modifier::DIM)));
+        }
+        let text = Text::from(lines);
+        match date == self.selected_date {
+            true => text.style(Style::new().add_modifier(Modifier::REVERSED)),
+            false => text,
+        }
+    }
+
+    fn suggestion_list(&self) -> List<'_> {
+        let items = self.suggestions.iter().map(|suggestion| {
+            ListItem::new(format!(
+                "{} ({})",
+                suggestion.meal(),
+                suggestion.last_date()
+            ))
+        });
+        let title = match self.ignoring {
+            true => String::from(" What "),
+            false => String::from(" What (not ignoring) "),
+        };
+        let highlight_style = match self.pane {
+            Pane::Suggestions => Style::new().add_modifier(Modifier::REVERSED),
+            Pane::Calendar => Style::new().add_modifier(Modifier::BOLD),
+        };
+        List::new(items)
+            .block(self.pane_block(title, Some(Pane::Suggestions)))
+            .highlight_style(highlight_style)
+    }
+
+    fn ignore_list(&self) -> List<'_> {
+        let items = self
+            .cfg
+            .what
+            .ignore
+            .to_vec_string()
+            .into_iter()
+            .map(ListItem::new);
+        List::new(items).block(self.pane_block(String::from(" Ignored "), None))
+    }
+}
+
+/// Returns the Monday of the week of the date.
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday().into())
+}
+
+#[cfg(test)]
+fn test_app() -> App<libmrot::MemoryStore> {
+    use chrono::{TimeZone, Utc};
+    use libmrot::FixedClock;
+
+    // pretend that today is Wednesday, March 12th, 2025
+    let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 3, 12, 12, 0, 0).unwrap());
+    let storage = Storage::in_memory().with_clock(clock);
+    for (meal, day) in [("pizza", 1), ("curry", 5)] {
+        let date = NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        storage
+            .add_meal_in_period(meal, &storage.period_between(date, date))
+            .unwrap();
+    }
+    App::new(storage, MrotConfig::default()).unwrap()
+}
+
+#[cfg(test)]
+fn press<S: MealStore>(app: &mut App<S>, keys: &[KeyCode]) {
+    for &key in keys {
+        app.handle_key(KeyEvent::from(key));
+    }
+}
+
+#[test]
+fn add_rename_remove_and_undo_on_the_selected_day() {
+    let mut app = test_app();
+    assert_eq!(app.suggestions[0].meal(), "pizza");
+    press(&mut app, &[KeyCode::Right, KeyCode::Char('a')]);
+    let tomorrow = NaiveDate::from_ymd_opt(2025, 3, 13).unwrap();
+    assert_eq!(app.storage.when("pizza").unwrap().last(), Some(&tomorrow));
+    assert_eq!(app.suggestions[0].meal(), "curry");
+
+    press(&mut app, &[KeyCode::Char('r')]);
+    press(&mut app, &[KeyCode::Backspace; 5]);
+    let typed: Vec<KeyCode> = "pasta".chars().map(KeyCode::Char).collect();
+    press(&mut app, &typed);
+    press(&mut app, &[KeyCode::Enter]);
+    assert_eq!(app.mode, Mode::Normal);
+    assert_eq!(app.storage.when("pasta").unwrap(), vec![tomorrow]);
+
+    press(&mut app, &[KeyCode::Char('d')]);
+    assert!(app.storage.when("pasta").unwrap().is_empty());
+    assert_eq!(
+        app.message,
+        Some(String::from("removed pasta on 2025-03-13"))
+    );
+
+    let period = app.storage.period_between(tomorrow, tomorrow);
+    for _ in 0..2 {
+        app.storage.add_meal_in_period("pasta", &period).unwrap();
+    }
+    app.refresh().unwrap();
+    press(&mut app, &[KeyCode::Char('n'), KeyCode::Char('d')]);
+    assert_eq!(app.storage.when("pasta").unwrap(), vec![tomorrow]);
+    assert_eq!(
+        app.message,
+        Some(String::from("removed pasta on 2025-03-13"))
+    );
+
+    press(&mut app, &[KeyCode::Char('u')]);
+    assert_eq!(app.storage.when("pasta").unwrap(), vec![tomorrow; 2]);
+    assert_eq!(
+        app.message,
+        Some(String::from("undone: remove pasta (2025-03-13)"))
+    );
+    press(&mut app, &[KeyCode::Char('U')]);
+    assert_eq!(app.storage.when("pasta").unwrap(), vec![tomorrow]);
+    press(&mut app, &[KeyCode::Char('U')]);
+    assert_eq!(app.message, Some(String::from("nothing to redo")));
+}
+
+#[test]
+fn toggle_ignored_suggestion() {
+    let mut app = test_app();
+    press(&mut app, &[KeyCode::Tab, KeyCode::Char('i')]);
+    assert_eq!(app.cfg.what.ignore.to_vec_string(), vec!["pizza"]);
+    assert!(app.config_changed);
+    assert_eq!(app.suggestions.len(), 1);
+    press(&mut app, &[KeyCode::Char('I')]);
+    assert_eq!(app.suggestions.len(), 2);
+    press(&mut app, &[KeyCode::Char('i')]);
+    assert!(app.cfg.what.ignore.is_empty());
+
+    app.storage
+        .add_aliases("pizza", &[String::from("pie")])
+        .unwrap();
+    app.cfg.what.ignore.add("PIE");
+    app.cfg.what.ignore.add("Pizza");
+    press(&mut app, &[KeyCode::Char('i')]);
+    assert!(app.cfg.what.ignore.is_empty());
+    assert_eq!(
+        app.message,
+        Some(String::from("pizza is no longer ignored"))
+    );
+}
+
+#[test]
+fn draw_calendar_and_suggestions() {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    let app = test_app();
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    terminal.draw(|frame| app.draw(frame)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("March 2025"));
+    assert!(screen.contains("curry"));
+    assert!(screen.contains("pizza (2025-03-01)"));
+}